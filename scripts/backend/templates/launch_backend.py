@@ -18,6 +18,10 @@ STARTUP_HEARTBEAT_ENV = "ASTRBOT_BACKEND_STARTUP_HEARTBEAT_PATH"
 RUNTIME_CORE_LOCK_ENV = "ASTRBOT_DESKTOP_CORE_LOCK_PATH"
 STARTUP_HEARTBEAT_INTERVAL_SECONDS = 2.0
 STARTUP_HEARTBEAT_STOP_JOIN_TIMEOUT_SECONDS = 1.0
+# The backend reaches report_startup_progress through this module name.
+LAUNCHER_MODULE_NAME = "astrbot_desktop_launcher"
+_STARTUP_PROGRESS: dict[str, object] = {}
+_STARTUP_PROGRESS_LOCK = threading.Lock()
 
 
 def configure_stdio_utf8() -> None:
@@ -129,12 +133,30 @@ def resolve_startup_heartbeat_path() -> Path | None:
     return Path(raw)
 
 
+def report_startup_progress(
+    phase: str | None = None,
+    message: str | None = None,
+    percent: float | None = None,
+) -> None:
+    """Replaces the progress shown on the desktop loading page with the next heartbeat."""
+    progress = {"phase": phase, "message": message, "percent": percent}
+    with _STARTUP_PROGRESS_LOCK:
+        _STARTUP_PROGRESS.clear()
+        _STARTUP_PROGRESS.update(
+            {key: value for key, value in progress.items() if value is not None}
+        )
+
+
 def build_heartbeat_payload(state: str) -> dict[str, object]:
-    return {
+    payload: dict[str, object] = {
         "pid": os.getpid(),
         "state": state,
         "updated_at_ms": int(time.time() * 1000),
     }
+    if state == "starting":
+        with _STARTUP_PROGRESS_LOCK:
+            payload.update(_STARTUP_PROGRESS)
+    return payload
 
 
 def atomic_write_json(path: Path, payload: dict[str, object]) -> None:
@@ -231,6 +253,7 @@ def main() -> None:
     configure_stdio_utf8()
     configure_windows_dll_search_path()
     preload_windows_runtime_dlls()
+    sys.modules.setdefault(LAUNCHER_MODULE_NAME, sys.modules[__name__])
     start_startup_heartbeat()
     configure_runtime_core_lock_path()
 
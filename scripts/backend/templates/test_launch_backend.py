@@ -33,6 +33,22 @@ class StartupHeartbeatTests(unittest.TestCase):
 
             self.assertFalse(temp_path.exists())
 
+    def test_heartbeat_payload_carries_reported_progress_while_starting(self) -> None:
+        launch_backend.report_startup_progress(
+            phase="plugins", message="loading plugins 12/40", percent=30
+        )
+        try:
+            starting = launch_backend.build_heartbeat_payload("starting")
+            stopping = launch_backend.build_heartbeat_payload("stopping")
+        finally:
+            launch_backend.report_startup_progress()
+
+        self.assertEqual(starting["phase"], "plugins")
+        self.assertEqual(starting["message"], "loading plugins 12/40")
+        self.assertEqual(starting["percent"], 30)
+        self.assertNotIn("phase", stopping)
+        self.assertNotIn("percent", launch_backend.build_heartbeat_payload("starting"))
+
     def test_repeated_failures_warn_before_first_success(self) -> None:
         stop_event = mock.Mock()
         stop_event.wait.side_effect = [False, True]
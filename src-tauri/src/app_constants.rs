@@ -7,12 +7,26 @@ pub(crate) const PACKAGED_BACKEND_TIMEOUT_FALLBACK_MS: u64 = 15 * 60 * 1000;
 pub(crate) const GRACEFUL_RESTART_REQUEST_TIMEOUT_MS: u64 = 2_500;
 pub(crate) const GRACEFUL_RESTART_START_TIME_TIMEOUT_MS: u64 = 1_800;
 pub(crate) const GRACEFUL_RESTART_POLL_INTERVAL_MS: u64 = 350;
-pub(crate) const GRACEFUL_STOP_TIMEOUT_MS: u64 = 10_000;
+pub(crate) const BACKEND_GRACEFUL_STOP_TIMEOUT_ENV: &str =
+    "ASTRBOT_BACKEND_GRACEFUL_STOP_TIMEOUT_MS";
+pub(crate) const DEFAULT_GRACEFUL_STOP_TIMEOUT_MS: u64 = 10_000;
+pub(crate) const BACKEND_SHUTDOWN_HTTP_PATH_ENV: &str = "ASTRBOT_BACKEND_SHUTDOWN_HTTP_PATH";
+pub(crate) const BACKEND_SHUTDOWN_HTTP_TIMEOUT_ENV: &str =
+    "ASTRBOT_BACKEND_SHUTDOWN_HTTP_TIMEOUT_MS";
+pub(crate) const DEFAULT_BACKEND_SHUTDOWN_HTTP_TIMEOUT_MS: u64 = 15_000;
+pub(crate) const BACKEND_SHUTDOWN_REQUEST_TIMEOUT_MS: u64 = 2_500;
+pub(crate) const BACKEND_SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(200);
+pub(crate) const BACKEND_STOP_TIMEOUT_MIN_MS: u64 = 1_000;
+pub(crate) const BACKEND_STOP_TIMEOUT_MAX_MS: u64 = 10 * 60 * 1000;
+pub(crate) const BACKEND_ORPHAN_PROMPT_TIMEOUT: Duration = Duration::from_secs(60);
 pub(crate) const DEFAULT_BACKEND_READY_POLL_INTERVAL_MS: u64 = 300;
 pub(crate) const BACKEND_READY_POLL_INTERVAL_MIN_MS: u64 = 50;
 pub(crate) const BACKEND_READY_POLL_INTERVAL_MAX_MS: u64 = 10_000;
 pub(crate) const BACKEND_READY_POLL_INTERVAL_ENV: &str = "ASTRBOT_BACKEND_READY_POLL_INTERVAL_MS";
 pub(crate) const DEFAULT_BACKEND_READY_HTTP_PATH: &str = "/api/stat/start-time";
+pub(crate) const BACKEND_IDENTITY_VERSION_PATH: &str = "/api/stat/version";
+pub(crate) const BACKEND_IDENTITY_PROBE_TIMEOUT_MS: u64 = 1_800;
+pub(crate) const BACKEND_MIN_VERSION_ENV: &str = "ASTRBOT_BACKEND_MIN_VERSION";
 pub(crate) const BACKEND_READY_HTTP_PATH_ENV: &str = "ASTRBOT_BACKEND_READY_HTTP_PATH";
 pub(crate) const BACKEND_READY_PROBE_TIMEOUT_ENV: &str = "ASTRBOT_BACKEND_READY_PROBE_TIMEOUT_MS";
 pub(crate) const BACKEND_READY_PROBE_TIMEOUT_MIN_MS: u64 = 100;
@@ -35,10 +49,58 @@ pub(crate) const BRIDGE_BACKEND_PING_TIMEOUT_ENV: &str = "ASTRBOT_BRIDGE_BACKEND
 pub(crate) const DESKTOP_LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
 pub(crate) const BACKEND_LOG_MAX_BYTES: u64 = 20 * 1024 * 1024;
 pub(crate) const LOG_BACKUP_COUNT: usize = 5;
-pub(crate) const BACKEND_LOG_ROTATION_CHECK_INTERVAL: Duration = Duration::from_secs(20);
+pub(crate) const BACKEND_LOG_BUFFER_CAPACITY: usize = 2_000;
+/// Longer backend output lines are split so a child that never ends a line stays bounded.
+pub(crate) const BACKEND_LOG_LINE_MAX_BYTES: usize = 16 * 1024;
+pub(crate) const BACKEND_LOG_DEFAULT_LIMIT: usize = 500;
+pub(crate) const STARTUP_ERROR_RECENT_LOG_LINES: usize = 30;
+pub(crate) const LOG_QUERY_DEFAULT_LIMIT: usize = 200;
+pub(crate) const LOG_QUERY_MAX_LIMIT: usize = 2_000;
+pub(crate) const BACKEND_SUPERVISOR_POLL_INTERVAL: Duration = Duration::from_secs(1);
+pub(crate) const BACKEND_METRICS_INTERVAL_ENV: &str = "ASTRBOT_BACKEND_METRICS_INTERVAL_MS";
+pub(crate) const DEFAULT_BACKEND_METRICS_INTERVAL_MS: u64 = 5_000;
+pub(crate) const BACKEND_METRICS_INTERVAL_MIN_MS: u64 = 1_000;
+pub(crate) const BACKEND_METRICS_INTERVAL_MAX_MS: u64 = 10 * 60 * 1000;
+pub(crate) const BACKEND_LIVENESS_INTERVAL_ENV: &str = "ASTRBOT_BACKEND_LIVENESS_INTERVAL_MS";
+pub(crate) const DEFAULT_BACKEND_LIVENESS_INTERVAL_MS: u64 = 15_000;
+pub(crate) const BACKEND_LIVENESS_INTERVAL_MIN_MS: u64 = 1_000;
+pub(crate) const BACKEND_LIVENESS_INTERVAL_MAX_MS: u64 = 10 * 60 * 1000;
+pub(crate) const BACKEND_LIVENESS_FAILURE_THRESHOLD_ENV: &str =
+    "ASTRBOT_BACKEND_LIVENESS_FAILURE_THRESHOLD";
+pub(crate) const DEFAULT_BACKEND_LIVENESS_FAILURE_THRESHOLD: u32 = 3;
+pub(crate) const BACKEND_LIVENESS_RESTART_AFTER_ENV: &str =
+    "ASTRBOT_BACKEND_LIVENESS_RESTART_AFTER";
+pub(crate) const BACKEND_RESTART_SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+pub(crate) const BACKEND_AUTO_RESTART_ENV: &str = "ASTRBOT_BACKEND_AUTO_RESTART";
+pub(crate) const BACKEND_CRASH_RESTART_MAX_IN_WINDOW: usize = 5;
+pub(crate) const BACKEND_CRASH_RESTART_WINDOW: Duration = Duration::from_secs(10 * 60);
+pub(crate) const BACKEND_CRASH_RESTART_INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+pub(crate) const BACKEND_CRASH_RESTART_MAX_BACKOFF: Duration = Duration::from_secs(60);
+pub(crate) const BACKEND_CRASH_HISTORY_MAX_RECORDS: usize = 200;
+pub(crate) const BACKEND_CRASH_HISTORY_DEFAULT_LIMIT: usize = 50;
+pub(crate) const BACKEND_CRASH_LOG_TAIL_LINES: usize = 50;
 pub(crate) const DESKTOP_LOG_FILE: &str = "desktop.log";
+pub(crate) const DESKTOP_LOG_FORMAT_ENV: &str = "ASTRBOT_DESKTOP_LOG_FORMAT";
+pub(crate) const DESKTOP_LOG_FORMAT_STATE_FIELD: &str = "logFormat";
+pub(crate) const DESKTOP_LOG_LEVEL_ENV: &str = "ASTRBOT_DESKTOP_LOG_LEVEL";
+pub(crate) const DESKTOP_LOG_LEVEL_STATE_FIELD: &str = "logLevel";
+pub(crate) const LOG_RETENTION_STATE_FIELD: &str = "logRetention";
+pub(crate) const RESOURCE_WATCHDOG_STATE_FIELD: &str = "resourceWatchdog";
+pub(crate) const BACKEND_ENV_STATE_FIELD: &str = "backendEnv";
+pub(crate) const PROXY_STATE_FIELD: &str = "proxy";
+pub(crate) const RESTART_SCHEDULE_STATE_FIELD: &str = "restartSchedule";
+pub(crate) const LOG_VIEWER_WINDOW_LABEL: &str = "log-viewer";
 pub(crate) const TRAY_ID: &str = "astrbot-tray";
 pub(crate) const TRAY_RESTART_BACKEND_EVENT: &str = "astrbot://tray-restart-backend";
+pub(crate) const BACKEND_CRASH_LOOP_EVENT: &str = "astrbot://backend-crash-loop";
+pub(crate) const BACKEND_LOG_EVENT: &str = "astrbot://backend-log";
+pub(crate) const BACKEND_METRICS_EVENT: &str = "astrbot://backend-metrics";
+pub(crate) const BACKEND_WATCHDOG_EVENT: &str = "astrbot://backend-watchdog";
+pub(crate) const BACKEND_HEALTH_EVENT: &str = "astrbot://backend-health";
+pub(crate) const BACKEND_STATE_EVENT: &str = "astrbot://backend-state";
+pub(crate) const BACKEND_ENV_CHANGED_EVENT: &str = "astrbot://backend-env-changed";
+// Keep this in sync with STARTUP_PROGRESS_EVENT in ui/startup-copy.js.
+pub(crate) const BACKEND_STARTUP_PROGRESS_EVENT: &str = "astrbot://backend-startup-progress";
 pub(crate) const DEFAULT_SHELL_LOCALE: &str = "zh-CN";
 pub(crate) const STARTUP_MODE_ENV: &str = "ASTRBOT_DESKTOP_STARTUP_MODE";
 #[cfg(target_os = "windows")]
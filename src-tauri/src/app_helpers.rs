@@ -1,4 +1,5 @@
 use std::{
+    env,
     ffi::OsString,
     sync::{Mutex, OnceLock},
 };
@@ -6,16 +7,21 @@ use std::{
 use tauri::{AppHandle, Manager};
 
 use crate::{
-    backend, bridge, logging, runtime_paths, window, BackendState, LaunchPlan, DESKTOP_LOG_FILE,
-    DESKTOP_LOG_MAX_BYTES, LOG_BACKUP_COUNT,
+    backend, bridge, desktop_state, log_retention, logging, runtime_paths, window, BackendRegistry,
+    LaunchPlan, DESKTOP_LOG_FILE, DESKTOP_LOG_FORMAT_ENV, DESKTOP_LOG_FORMAT_STATE_FIELD,
+    DESKTOP_LOG_LEVEL_ENV, DESKTOP_LOG_LEVEL_STATE_FIELD, DESKTOP_LOG_MAX_BYTES, LOG_BACKUP_COUNT,
+    LOG_RETENTION_STATE_FIELD,
 };
 
 static DESKTOP_LOG_WRITE_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
 static BACKEND_PATH_OVERRIDE: OnceLock<Option<OsString>> = OnceLock::new();
+static DESKTOP_LOG_FORMAT: OnceLock<logging::DesktopLogFormat> = OnceLock::new();
+static DESKTOP_LOG_LEVEL: OnceLock<logging::DesktopLogLevel> = OnceLock::new();
+static LOG_RETENTION_POLICY: OnceLock<log_retention::LogRetentionPolicy> = OnceLock::new();
 
 pub(crate) fn navigate_main_window_to_backend(app_handle: &AppHandle) -> Result<(), String> {
-    let state = app_handle.state::<BackendState>();
-    window::main_window::navigate_main_window_to_backend(app_handle, &state.backend_url)
+    let state = app_handle.state::<BackendRegistry>().selected();
+    window::main_window::navigate_main_window_to_backend(app_handle, &state.backend_url())
 }
 
 pub(crate) fn inject_desktop_bridge(webview: &tauri::Webview<tauri::Wry>) {
@@ -37,29 +43,126 @@ pub(crate) fn build_debug_command(plan: &LaunchPlan) -> Vec<String> {
 }
 
 pub(crate) fn append_desktop_log(message: &str) {
-    append_desktop_log_with_category(logging::DesktopLogCategory::Runtime, message);
+    append_log(
+        logging::DesktopLogCategory::Runtime,
+        logging::DesktopLogLevel::Info,
+        message,
+    );
 }
 
 pub(crate) fn append_startup_log(message: &str) {
-    append_desktop_log_with_category(logging::DesktopLogCategory::Startup, message);
+    append_log(
+        logging::DesktopLogCategory::Startup,
+        logging::DesktopLogLevel::Info,
+        message,
+    );
 }
 
 pub(crate) fn append_restart_log(message: &str) {
-    append_desktop_log_with_category(logging::DesktopLogCategory::Restart, message);
+    append_log(
+        logging::DesktopLogCategory::Restart,
+        logging::DesktopLogLevel::Info,
+        message,
+    );
 }
 
 pub(crate) fn append_shutdown_log(message: &str) {
-    append_desktop_log_with_category(logging::DesktopLogCategory::Shutdown, message);
+    append_log(
+        logging::DesktopLogCategory::Shutdown,
+        logging::DesktopLogLevel::Info,
+        message,
+    );
+}
+
+/// Runtime-category message at an explicit level.
+pub(crate) fn append_desktop_log_at(level: logging::DesktopLogLevel, message: &str) {
+    append_log(logging::DesktopLogCategory::Runtime, level, message);
+}
+
+pub(crate) fn append_startup_log_at(level: logging::DesktopLogLevel, message: &str) {
+    append_log(logging::DesktopLogCategory::Startup, level, message);
+}
+
+pub(crate) fn append_restart_log_at(level: logging::DesktopLogLevel, message: &str) {
+    append_log(logging::DesktopLogCategory::Restart, level, message);
+}
+
+pub(crate) fn append_shutdown_log_at(level: logging::DesktopLogLevel, message: &str) {
+    append_log(logging::DesktopLogCategory::Shutdown, level, message);
+}
+
+pub(crate) fn configured_desktop_log_level() -> logging::DesktopLogLevel {
+    *DESKTOP_LOG_LEVEL.get_or_init(|| {
+        let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+        logging::resolve_desktop_log_level(
+            env::var(DESKTOP_LOG_LEVEL_ENV).ok().as_deref(),
+            desktop_state::read_shared_desktop_state_field(
+                DESKTOP_LOG_LEVEL_STATE_FIELD,
+                packaged_root_dir.as_deref(),
+            )
+            .as_ref(),
+        )
+    })
 }
 
-fn append_desktop_log_with_category(category: logging::DesktopLogCategory, message: &str) {
+/// The bridge override while one is set, otherwise the configured level.
+pub(crate) fn effective_desktop_log_level() -> logging::DesktopLogLevel {
+    logging::runtime_min_log_level().unwrap_or_else(configured_desktop_log_level)
+}
+
+/// Shared by `desktop.log` and every instance's `backend.log`; read once per run.
+pub(crate) fn log_retention_policy() -> log_retention::LogRetentionPolicy {
+    // The desktop logger itself asks for the policy, so warnings are written once it is set.
+    let mut warnings = Vec::new();
+    let policy = *LOG_RETENTION_POLICY.get_or_init(|| {
+        let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+        log_retention::resolve_log_retention_policy(
+            desktop_state::read_shared_desktop_state_field(
+                LOG_RETENTION_STATE_FIELD,
+                packaged_root_dir.as_deref(),
+            )
+            .as_ref(),
+            LOG_BACKUP_COUNT,
+            |message| warnings.push(message),
+        )
+    });
+    for warning in warnings {
+        append_desktop_log_at(logging::DesktopLogLevel::Warn, &warning);
+    }
+    policy
+}
+
+fn desktop_log_format() -> logging::DesktopLogFormat {
+    *DESKTOP_LOG_FORMAT.get_or_init(|| {
+        let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+        logging::resolve_desktop_log_format(
+            env::var(DESKTOP_LOG_FORMAT_ENV).ok().as_deref(),
+            desktop_state::read_shared_desktop_state_field(
+                DESKTOP_LOG_FORMAT_STATE_FIELD,
+                packaged_root_dir.as_deref(),
+            )
+            .as_ref(),
+        )
+    })
+}
+
+pub(crate) fn append_log(
+    category: logging::DesktopLogCategory,
+    level: logging::DesktopLogLevel,
+    message: &str,
+) {
+    if level < effective_desktop_log_level() {
+        return;
+    }
     logging::append_desktop_log(
         category,
+        level,
         message,
+        desktop_log_format(),
         runtime_paths::default_packaged_root_dir(),
         DESKTOP_LOG_FILE,
         DESKTOP_LOG_MAX_BYTES,
-        LOG_BACKUP_COUNT,
+        &log_retention_policy(),
         &DESKTOP_LOG_WRITE_LOCK,
     )
 }
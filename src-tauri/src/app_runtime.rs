@@ -4,12 +4,14 @@ use tauri::{
 };
 
 use crate::{
-    app_runtime_events, append_desktop_log, append_startup_log, bridge, lifecycle, startup_task,
-    tray, window, BackendState, DEFAULT_SHELL_LOCALE, DESKTOP_LOG_FILE, STARTUP_MODE_ENV,
+    app_runtime_events, append_desktop_log, append_startup_log, append_startup_log_at, bridge,
+    lifecycle, logging::DesktopLogLevel, startup_task, tray, window, BackendRegistry, BackendState,
+    DEFAULT_SHELL_LOCALE, DESKTOP_LOG_FILE, STARTUP_MODE_ENV,
 };
 
 fn configure_plugins(builder: Builder<tauri::Wry>) -> Builder<tauri::Wry> {
     builder
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
@@ -20,7 +22,11 @@ fn configure_plugins(builder: Builder<tauri::Wry>) -> Builder<tauri::Wry> {
 
 fn configure_window_events(builder: Builder<tauri::Wry>) -> Builder<tauri::Wry> {
     builder.on_window_event(|window, event| {
-        let is_quitting = window.app_handle().state::<BackendState>().is_quitting();
+        let is_quitting = window
+            .app_handle()
+            .state::<BackendRegistry>()
+            .selected()
+            .is_quitting();
         let action = match &event {
             WindowEvent::CloseRequested { .. } => app_runtime_events::main_window_action(
                 window.label(),
@@ -64,10 +70,10 @@ fn configure_window_events(builder: Builder<tauri::Wry>) -> Builder<tauri::Wry>
 
 fn handle_page_load_started(webview: &tauri::Webview<tauri::Wry>, payload: &PageLoadPayload<'_>) {
     append_desktop_log(&format!("page-load started: {}", payload.url()));
-    let state = webview.app_handle().state::<BackendState>();
+    let state = webview.app_handle().state::<BackendRegistry>().selected();
     let action = app_runtime_events::page_load_action(
         PageLoadEvent::Started,
-        bridge::desktop::should_inject_desktop_bridge(&state.backend_url, payload.url()),
+        bridge::desktop::should_inject_desktop_bridge(&state.backend_url(), payload.url()),
         false,
     );
 
@@ -78,10 +84,10 @@ fn handle_page_load_started(webview: &tauri::Webview<tauri::Wry>, payload: &Page
 
 fn handle_page_load_finished(webview: &tauri::Webview<tauri::Wry>, payload: &PageLoadPayload<'_>) {
     append_desktop_log(&format!("page-load finished: {}", payload.url()));
-    let state = webview.app_handle().state::<BackendState>();
+    let state = webview.app_handle().state::<BackendRegistry>().selected();
     let action = app_runtime_events::page_load_action(
         PageLoadEvent::Finished,
-        bridge::desktop::should_inject_desktop_bridge(&state.backend_url, payload.url()),
+        bridge::desktop::should_inject_desktop_bridge(&state.backend_url(), payload.url()),
         window::startup_loading::should_apply_startup_loading_mode(
             webview.window().label(),
             payload.url(),
@@ -115,7 +121,10 @@ fn configure_setup(builder: Builder<tauri::Wry>) -> Builder<tauri::Wry> {
     builder.setup(|app| {
         let app_handle = app.handle().clone();
         if let Err(error) = tray::setup::setup_tray(&app_handle) {
-            append_startup_log(&format!("failed to initialize tray: {error}"));
+            append_startup_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to initialize tray: {error}"),
+            );
         }
 
         startup_task::spawn_startup_task(app_handle.clone(), append_startup_log);
@@ -159,7 +168,10 @@ pub(crate) fn run() {
     let builder = configure_setup(builder);
 
     builder
-        .manage(BackendState::default())
+        .manage(BackendRegistry::new(BackendState::for_profile(
+            crate::backend::profile::load_active_profile(),
+            Default::default(),
+        )))
         .invoke_handler(tauri::generate_handler![
             crate::bridge::commands::desktop_bridge_is_desktop_runtime,
             crate::bridge::commands::desktop_bridge_get_backend_state,
@@ -169,6 +181,25 @@ pub(crate) fn run() {
             crate::bridge::commands::desktop_bridge_set_app_update_channel,
             crate::bridge::commands::desktop_bridge_restart_backend,
             crate::bridge::commands::desktop_bridge_stop_backend,
+            crate::bridge::commands::desktop_bridge_cancel_startup,
+            crate::bridge::commands::desktop_bridge_retry_startup,
+            crate::bridge::commands::desktop_bridge_resolve_orphan_backend,
+            crate::bridge::commands::desktop_bridge_open_log_viewer,
+            crate::bridge::commands::desktop_bridge_quit_app,
+            crate::bridge::commands::desktop_bridge_get_backend_crash_history,
+            crate::bridge::commands::desktop_bridge_get_backend_logs,
+            crate::bridge::commands::desktop_bridge_query_logs,
+            crate::bridge::commands::desktop_bridge_export_diagnostics,
+            crate::bridge::commands::desktop_bridge_get_backend_metrics,
+            crate::bridge::commands::desktop_bridge_get_restart_schedule,
+            crate::bridge::commands::desktop_bridge_set_restart_schedule,
+            crate::bridge::commands::desktop_bridge_get_proxy_config,
+            crate::bridge::commands::desktop_bridge_set_proxy_config,
+            crate::bridge::commands::desktop_bridge_get_backend_env,
+            crate::bridge::commands::desktop_bridge_set_backend_env,
+            crate::bridge::commands::desktop_bridge_remove_backend_env,
+            crate::bridge::commands::desktop_bridge_get_log_level,
+            crate::bridge::commands::desktop_bridge_set_log_level,
             crate::bridge::commands::desktop_bridge_open_external_url,
             crate::bridge::commands::desktop_bridge_check_app_update,
             crate::bridge::commands::desktop_bridge_install_app_update
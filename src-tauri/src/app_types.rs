@@ -1,25 +1,48 @@
 use serde::Deserialize;
 use std::{
-    env,
+    collections::BTreeMap,
     path::PathBuf,
     process::Child,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
+        Arc, Condvar, Mutex, OnceLock,
     },
+    time::Instant,
+};
+use tauri::{
+    menu::{CheckMenuItem, MenuItem, Submenu},
+    AppHandle,
 };
-use tauri::menu::MenuItem;
 
-use crate::{backend, exit_state, DEFAULT_BACKEND_URL};
+use crate::{backend, exit_state, log_query};
 
 #[derive(Clone)]
 pub(crate) struct TrayMenuState {
     pub(crate) toggle_item: MenuItem<tauri::Wry>,
     pub(crate) reload_item: MenuItem<tauri::Wry>,
     pub(crate) restart_backend_item: MenuItem<tauri::Wry>,
+    pub(crate) switch_profile_menu: Submenu<tauri::Wry>,
+    pub(crate) profile_items: Vec<(String, CheckMenuItem<tauri::Wry>)>,
+    pub(crate) implicit_default_profile_item: Option<CheckMenuItem<tauri::Wry>>,
+    pub(crate) instances_menu: Submenu<tauri::Wry>,
+    pub(crate) instance_menus: Vec<TrayInstanceMenu>,
+    pub(crate) log_viewer_item: MenuItem<tauri::Wry>,
+    pub(crate) export_diagnostics_item: MenuItem<tauri::Wry>,
     pub(crate) quit_item: MenuItem<tauri::Wry>,
 }
 
+/// Per-profile entry of the tray "Instances" submenu.
+#[derive(Clone)]
+pub(crate) struct TrayInstanceMenu {
+    pub(crate) instance_id: String,
+    /// `None` for the implicit default profile, whose name follows the shell locale.
+    pub(crate) name: Option<String>,
+    pub(crate) submenu: Submenu<tauri::Wry>,
+    pub(crate) open_item: MenuItem<tauri::Wry>,
+    pub(crate) restart_item: MenuItem<tauri::Wry>,
+    pub(crate) stop_item: MenuItem<tauri::Wry>,
+}
+
 #[derive(Debug, Deserialize)]
 pub(crate) struct RuntimeManifest {
     pub(crate) python: Option<String>,
@@ -37,16 +60,57 @@ pub(crate) struct LaunchPlan {
     pub(crate) packaged_mode: bool,
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct BackendSpawnInfo {
+    pub(crate) pid: u32,
+    pub(crate) started_at: Instant,
+    pub(crate) command: Vec<String>,
+    pub(crate) log_path: Option<PathBuf>,
+    pub(crate) startup_heartbeat_path: Option<PathBuf>,
+    /// Environment overrides the child was spawned with; `None` for an adopted backend.
+    pub(crate) env_overrides: Option<backend::env_overrides::BackendEnvOverrides>,
+}
+
 #[derive(Debug)]
 pub(crate) struct BackendState {
+    pub(crate) id: String,
+    pub(crate) profile: Option<backend::profile::BackendProfile>,
     pub(crate) child: Mutex<Option<Child>>,
-    pub(crate) backend_url: String,
+    /// Backend left running by a crashed desktop session and managed by pid from now on.
+    pub(crate) adopted_pid: Mutex<Option<u32>>,
+    pub(crate) orphan_prompt: Mutex<backend::orphan::OrphanPrompt>,
+    pub(crate) orphan_prompt_changed: Condvar,
+    pub(crate) spawn_info: Mutex<Option<BackendSpawnInfo>>,
+    pub(crate) backend_url: Mutex<String>,
     pub(crate) restart_auth_token: Mutex<Option<String>>,
     pub(crate) startup_loading_mode: Mutex<Option<&'static str>>,
-    pub(crate) log_rotator_stop: Mutex<Option<Arc<AtomicBool>>>,
-    pub(crate) exit_state: Mutex<exit_state::ExitStateMachine>,
+    /// Kept so a startup page that (re)loads after the failure still shows the error.
+    pub(crate) startup_error: Mutex<Option<StartupErrorDetails>>,
+    pub(crate) log_buffer: Arc<Mutex<backend::log_stream::BackendLogRing>>,
+    pub(crate) resource_monitor: Mutex<backend::resource_monitor::ResourceMonitorState>,
+    /// Unix time in milliseconds of the next scheduled restart, if any.
+    pub(crate) next_scheduled_restart: Mutex<Option<i64>>,
+    pub(crate) health: Mutex<backend::liveness::BackendHealth>,
+    pub(crate) lifecycle: Mutex<backend::lifecycle::BackendLifecycle>,
+    /// Set on first launch; used to push lifecycle events from paths without an app handle.
+    pub(crate) app_handle: OnceLock<AppHandle>,
+    pub(crate) supervisor_stop: Mutex<Option<Arc<AtomicBool>>>,
+    pub(crate) crash_restart_tracker: Mutex<backend::supervisor_policy::CrashRestartTracker>,
+    pub(crate) last_exit_status: Mutex<Option<String>>,
+    pub(crate) crash_loop_error: Mutex<Option<String>>,
+    /// Shared by every instance in the registry so quitting is observed shell-wide.
+    pub(crate) exit_state: Arc<Mutex<exit_state::ExitStateMachine>>,
     pub(crate) is_spawning: AtomicBool,
     pub(crate) is_restarting: AtomicBool,
+    /// Raised by the startup page to abandon the current readiness wait.
+    pub(crate) startup_cancel_requested: AtomicBool,
+}
+
+#[derive(Debug)]
+pub(crate) struct BackendRegistry {
+    pub(crate) instances: Mutex<BTreeMap<String, Arc<BackendState>>>,
+    pub(crate) selected_id: Mutex<String>,
+    pub(crate) exit_state: Arc<Mutex<exit_state::ExitStateMachine>>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -56,6 +120,13 @@ pub(crate) struct BackendBridgeState {
     pub(crate) spawning: bool,
     pub(crate) restarting: bool,
     pub(crate) can_manage: bool,
+    /// Managed by pid after being adopted from a previous desktop session.
+    pub(crate) adopted: bool,
+    /// `false` while adopted: the backend's output pipes belonged to the previous session.
+    pub(crate) logs_available: bool,
+    pub(crate) crash_loop_error: Option<String>,
+    #[serde(flatten)]
+    pub(crate) lifecycle: backend::lifecycle::BackendLifecycleSnapshot,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -64,6 +135,94 @@ pub(crate) struct BackendBridgeResult {
     pub(crate) reason: Option<String>,
 }
 
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendCrashHistoryResult {
+    pub(crate) ok: bool,
+    pub(crate) reason: Option<String>,
+    pub(crate) records: Vec<backend::crash_history::BackendCrashRecord>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StartupErrorDetails {
+    pub(crate) message: String,
+    pub(crate) desktop_log_path: String,
+    pub(crate) backend_log_path: String,
+    pub(crate) recent_logs: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendLogsResult {
+    pub(crate) ok: bool,
+    pub(crate) reason: Option<String>,
+    pub(crate) lines: Vec<backend::log_stream::BackendLogLine>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendMetricsResult {
+    pub(crate) ok: bool,
+    pub(crate) reason: Option<String>,
+    pub(crate) metrics: Option<backend::resource_monitor::BackendMetrics>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DesktopProxyConfigResult {
+    pub(crate) ok: bool,
+    pub(crate) reason: Option<String>,
+    pub(crate) proxy: Option<crate::proxy_config::ProxyConfig>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendEnvResult {
+    pub(crate) ok: bool,
+    pub(crate) reason: Option<String>,
+    pub(crate) variables: Vec<backend::env_overrides::BackendEnvVariable>,
+    pub(crate) restart_required: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendRestartScheduleResult {
+    pub(crate) ok: bool,
+    pub(crate) reason: Option<String>,
+    pub(crate) schedule: Option<backend::restart_schedule::RestartSchedule>,
+    pub(crate) next_restart_at_ms: Option<i64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DesktopLogLevelResult {
+    pub(crate) ok: bool,
+    pub(crate) reason: Option<String>,
+    pub(crate) level: &'static str,
+    pub(crate) configured_level: &'static str,
+    pub(crate) overridden: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct DiagnosticsExportResult {
+    pub(crate) ok: bool,
+    pub(crate) reason: Option<String>,
+    pub(crate) path: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogQueryResult {
+    pub(crate) ok: bool,
+    pub(crate) reason: Option<String>,
+    pub(crate) entries: Vec<log_query::LogEntry>,
+    pub(crate) has_more: bool,
+    pub(crate) next_offset: Option<usize>,
+    /// Passed back as `after` to follow the active file from where this result stopped.
+    pub(crate) cursor: Option<log_query::LogCursor>,
+}
+
 pub(crate) struct AtomicFlagGuard<'a> {
     flag: &'a AtomicBool,
 }
@@ -89,19 +248,53 @@ impl Drop for AtomicFlagGuard<'_> {
 
 impl Default for BackendState {
     fn default() -> Self {
+        Self::for_profile(
+            None,
+            Arc::new(Mutex::new(exit_state::ExitStateMachine::default())),
+        )
+    }
+}
+
+impl BackendState {
+    pub(crate) fn for_profile(
+        profile: Option<backend::profile::BackendProfile>,
+        exit_state: Arc<Mutex<exit_state::ExitStateMachine>>,
+    ) -> Self {
         Self {
+            id: profile
+                .as_ref()
+                .map(|profile| profile.id.clone())
+                .unwrap_or_else(|| backend::profile::DEFAULT_PROFILE_ID.to_string()),
+            backend_url: Mutex::new(backend::dashboard_port::resolve_profile_backend_url(
+                profile.as_ref(),
+            )),
+            profile,
             child: Mutex::new(None),
-            backend_url: backend::config::normalize_backend_url(
-                &env::var("ASTRBOT_BACKEND_URL")
-                    .unwrap_or_else(|_| DEFAULT_BACKEND_URL.to_string()),
-                DEFAULT_BACKEND_URL,
-            ),
+            adopted_pid: Mutex::new(None),
+            orphan_prompt: Mutex::new(backend::orphan::OrphanPrompt::default()),
+            orphan_prompt_changed: Condvar::new(),
+            spawn_info: Mutex::new(None),
             restart_auth_token: Mutex::new(None),
             startup_loading_mode: Mutex::new(None),
-            log_rotator_stop: Mutex::new(None),
-            exit_state: Mutex::new(exit_state::ExitStateMachine::default()),
+            startup_error: Mutex::new(None),
+            log_buffer: Arc::new(Mutex::new(backend::log_stream::BackendLogRing::new(
+                crate::BACKEND_LOG_BUFFER_CAPACITY,
+            ))),
+            resource_monitor: Mutex::new(backend::resource_monitor::ResourceMonitorState::default()),
+            next_scheduled_restart: Mutex::new(None),
+            health: Mutex::new(backend::liveness::BackendHealth::default()),
+            lifecycle: Mutex::new(backend::lifecycle::BackendLifecycle::default()),
+            app_handle: OnceLock::new(),
+            supervisor_stop: Mutex::new(None),
+            crash_restart_tracker: Mutex::new(
+                backend::supervisor_policy::CrashRestartTracker::default(),
+            ),
+            last_exit_status: Mutex::new(None),
+            crash_loop_error: Mutex::new(None),
+            exit_state,
             is_spawning: AtomicBool::new(false),
             is_restarting: AtomicBool::new(false),
+            startup_cancel_requested: AtomicBool::new(false),
         }
     }
 }
@@ -0,0 +1,313 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    process::ExitStatus,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+const CRASH_HISTORY_FILE_NAME: &str = "backend_crash_history.jsonl";
+const LOG_TAIL_MAX_BYTES: u64 = 64 * 1024;
+/// Recorded for adopted backends, which are watched by pid and leave no exit status behind.
+const UNKNOWN_EXIT_STATUS: &str = "exited (status unavailable)";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendCrashRecord {
+    pub(crate) timestamp: String,
+    pub(crate) timestamp_ms: u64,
+    pub(crate) pid: u32,
+    pub(crate) status: String,
+    pub(crate) exit_code: Option<i32>,
+    pub(crate) signal: Option<i32>,
+    pub(crate) command: Vec<String>,
+    pub(crate) uptime_ms: Option<u64>,
+    pub(crate) log_tail: Vec<String>,
+}
+
+impl BackendCrashRecord {
+    pub(crate) fn new(
+        pid: u32,
+        status: Option<&ExitStatus>,
+        command: Vec<String>,
+        uptime: Option<Duration>,
+        log_tail: Vec<String>,
+        now: SystemTime,
+    ) -> Self {
+        let timestamp_ms = now
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+        Self {
+            timestamp: chrono::DateTime::<chrono::Local>::from(now).to_rfc3339(),
+            timestamp_ms,
+            pid,
+            status: status.map_or_else(|| UNKNOWN_EXIT_STATUS.to_string(), ToString::to_string),
+            exit_code: status.and_then(ExitStatus::code),
+            signal: status.and_then(exit_signal),
+            command,
+            uptime_ms: uptime.map(|value| value.as_millis() as u64),
+            log_tail,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+pub(crate) fn resolve_crash_history_path(desktop_state_path: &Path) -> PathBuf {
+    desktop_state_path.with_file_name(CRASH_HISTORY_FILE_NAME)
+}
+
+pub(crate) fn append_crash_record(
+    path: &Path,
+    record: &BackendCrashRecord,
+    max_records: usize,
+) -> Result<(), String> {
+    if let Some(parent_dir) = path.parent() {
+        fs::create_dir_all(parent_dir).map_err(|error| {
+            format!(
+                "Failed to create crash history directory {}: {}",
+                parent_dir.display(),
+                error
+            )
+        })?;
+    }
+
+    let line = serde_json::to_string(record)
+        .map_err(|error| format!("Failed to serialize backend crash record: {error}"))?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(format!("{line}\n").as_bytes()))
+        .map_err(|error| {
+            format!(
+                "Failed to append backend crash record {}: {}",
+                path.display(),
+                error
+            )
+        })?;
+
+    trim_crash_history(path, max_records)
+}
+
+/// Rewrites the history down to its newest `max_records` lines once it holds more than that;
+/// the common append below the cap only counts lines.
+fn trim_crash_history(path: &Path, max_records: usize) -> Result<(), String> {
+    let read_error = |error: std::io::Error| {
+        format!(
+            "Failed to read backend crash history {}: {}",
+            path.display(),
+            error
+        )
+    };
+    let mut record_count = 0;
+    for line in BufReader::new(File::open(path).map_err(read_error)?).split(b'\n') {
+        if !line.map_err(read_error)?.trim_ascii().is_empty() {
+            record_count += 1;
+        }
+    }
+    if record_count <= max_records {
+        return Ok(());
+    }
+
+    let raw = fs::read_to_string(path).map_err(read_error)?;
+    let lines = raw
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>();
+    let mut kept = lines[lines.len().saturating_sub(max_records)..].join("\n");
+    kept.push('\n');
+
+    let tmp_name = format!(
+        "{}.tmp",
+        path.file_name()
+            .map(|value| value.to_string_lossy())
+            .unwrap_or_default()
+    );
+    let tmp_path = path.with_file_name(tmp_name);
+    let mut file = File::create(&tmp_path).map_err(|error| {
+        format!(
+            "Failed to create temporary crash history file {}: {}",
+            tmp_path.display(),
+            error
+        )
+    })?;
+    file.write_all(kept.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|error| {
+            format!(
+                "Failed to write temporary crash history file {}: {}",
+                tmp_path.display(),
+                error
+            )
+        })?;
+    fs::rename(&tmp_path, path).map_err(|error| {
+        format!(
+            "Failed to trim backend crash history {}: {}",
+            path.display(),
+            error
+        )
+    })
+}
+
+pub(crate) fn read_crash_history(
+    path: &Path,
+    limit: usize,
+) -> Result<Vec<BackendCrashRecord>, String> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(format!(
+                "Failed to read backend crash history {}: {}",
+                path.display(),
+                error
+            ));
+        }
+    };
+
+    // Newest first; malformed lines (e.g. a torn write) are skipped rather than failing the read.
+    Ok(raw
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<BackendCrashRecord>(line).ok())
+        .take(limit)
+        .collect())
+}
+
+pub(crate) fn read_log_tail(path: &Path, max_lines: usize) -> Vec<String> {
+    if max_lines == 0 {
+        return Vec::new();
+    }
+    let Ok(mut file) = File::open(path) else {
+        return Vec::new();
+    };
+    let Ok(length) = file.metadata().map(|metadata| metadata.len()) else {
+        return Vec::new();
+    };
+    let start = length.saturating_sub(LOG_TAIL_MAX_BYTES);
+    if file.seek(SeekFrom::Start(start)).is_err() {
+        return Vec::new();
+    }
+    let mut buffer = Vec::new();
+    if file.read_to_end(&mut buffer).is_err() {
+        return Vec::new();
+    }
+
+    let text = String::from_utf8_lossy(&buffer);
+    let mut lines = text.lines().collect::<Vec<_>>();
+    if start > 0 && !lines.is_empty() {
+        // The first line is likely cut in half by the byte window.
+        lines.remove(0);
+    }
+    let skip = lines.len().saturating_sub(max_lines);
+    lines[skip..].iter().map(|line| line.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_record(pid: u32) -> BackendCrashRecord {
+        BackendCrashRecord {
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            timestamp_ms: 1_767_225_600_000,
+            pid,
+            status: "exit status: 1".to_string(),
+            exit_code: Some(1),
+            signal: None,
+            command: vec!["python".to_string(), "main.py".to_string()],
+            uptime_ms: Some(1_000),
+            log_tail: vec!["Traceback".to_string()],
+        }
+    }
+
+    #[test]
+    fn append_and_read_crash_history_returns_newest_first() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join("data").join(CRASH_HISTORY_FILE_NAME);
+
+        append_crash_record(&path, &sample_record(1), 10).expect("append first");
+        append_crash_record(&path, &sample_record(2), 10).expect("append second");
+
+        let history = read_crash_history(&path, 10).expect("read history");
+        assert_eq!(
+            history.iter().map(|record| record.pid).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+    }
+
+    #[test]
+    fn append_crash_record_trims_to_max_records() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join(CRASH_HISTORY_FILE_NAME);
+
+        for pid in 1..=5 {
+            append_crash_record(&path, &sample_record(pid), 3).expect("append record");
+        }
+
+        let history = read_crash_history(&path, 10).expect("read history");
+        assert_eq!(
+            history.iter().map(|record| record.pid).collect::<Vec<_>>(),
+            vec![5, 4, 3]
+        );
+        assert!(!path
+            .with_file_name(format!("{CRASH_HISTORY_FILE_NAME}.tmp"))
+            .exists());
+    }
+
+    #[test]
+    fn read_crash_history_skips_malformed_lines_and_missing_file() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join(CRASH_HISTORY_FILE_NAME);
+        assert_eq!(read_crash_history(&path, 10), Ok(Vec::new()));
+
+        let valid = serde_json::to_string(&sample_record(7)).expect("serialize record");
+        fs::write(&path, format!("{valid}\n{{\"pid\":\n")).expect("write history");
+
+        let history = read_crash_history(&path, 10).expect("read history");
+        assert_eq!(history, vec![sample_record(7)]);
+    }
+
+    #[test]
+    fn crash_record_without_exit_status_marks_it_unavailable() {
+        let record =
+            BackendCrashRecord::new(42, None, Vec::new(), None, Vec::new(), SystemTime::now());
+        assert_eq!(record.status, UNKNOWN_EXIT_STATUS);
+        assert_eq!(record.exit_code, None);
+        assert_eq!(record.signal, None);
+    }
+
+    #[test]
+    fn read_log_tail_returns_last_lines() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join("backend.log");
+        fs::write(&path, "one\ntwo\nthree\nfour\n").expect("write log");
+
+        assert_eq!(read_log_tail(&path, 2), vec!["three", "four"]);
+        assert!(read_log_tail(&temp_dir.path().join("missing.log"), 2).is_empty());
+    }
+
+    #[test]
+    fn resolve_crash_history_path_sits_next_to_desktop_state() {
+        let state_path = PathBuf::from("/tmp/astrbot-root/data/desktop_state.json");
+
+        assert_eq!(
+            resolve_crash_history_path(&state_path),
+            PathBuf::from("/tmp/astrbot-root/data").join(CRASH_HISTORY_FILE_NAME)
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    collections::HashMap,
+    env,
+    net::{Ipv4Addr, TcpListener},
+    path::Path,
+    sync::{Mutex, OnceLock},
+};
+
+use url::Url;
+
+use crate::{
+    append_desktop_log, append_desktop_log_at, backend, backend::profile::BackendProfile,
+    desktop_state, logging::DesktopLogLevel, BackendState, DEFAULT_BACKEND_URL,
+};
+
+const FREE_PORT_ATTEMPTS: usize = 16;
+
+/// Ports handed to managed instances, keyed by port with the owning instance id, so two
+/// instances starting at the same time never pick the same port before either has bound it.
+static DASHBOARD_PORT_CLAIMS: OnceLock<Mutex<HashMap<u16, String>>> = OnceLock::new();
+
+const DASHBOARD_PORT_FIELD: &str = "dashboardPort";
+const DASHBOARD_PORT_OVERRIDE_ENVS: [&str; 3] = [
+    "ASTRBOT_BACKEND_URL",
+    "DASHBOARD_PORT",
+    "ASTRBOT_DASHBOARD_PORT",
+];
+
+pub(crate) fn is_dashboard_port_selection_enabled(profile: Option<&BackendProfile>) -> bool {
+    let profile_pins_port = profile.is_some_and(|profile| profile.dashboard_port.is_some());
+    !profile_pins_port && is_dashboard_port_selection_enabled_with(|key| env::var(key).ok())
+}
+
+fn is_dashboard_port_selection_enabled_with<F>(lookup: F) -> bool
+where
+    F: Fn(&str) -> Option<String>,
+{
+    DASHBOARD_PORT_OVERRIDE_ENVS
+        .iter()
+        .all(|key| lookup(key).is_none())
+}
+
+pub(crate) fn is_loopback_port_available(port: u16) -> bool {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_ok()
+}
+
+pub(crate) fn find_free_loopback_port() -> Option<u16> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).ok()?;
+    listener.local_addr().ok().map(|address| address.port())
+}
+
+pub(crate) fn backend_url_port(backend_url: &str) -> Option<u16> {
+    Url::parse(backend_url).ok()?.port_or_known_default()
+}
+
+pub(crate) fn backend_url_with_port(backend_url: &str, port: u16) -> Option<String> {
+    let mut parsed = Url::parse(backend_url).ok()?;
+    parsed.set_port(Some(port)).ok()?;
+    Some(parsed.to_string())
+}
+
+/// Base backend URL for a profile: `ASTRBOT_BACKEND_URL` (or the default) with the profile's
+/// pinned dashboard port applied.
+pub(crate) fn resolve_profile_backend_url(profile: Option<&BackendProfile>) -> String {
+    let backend_url = backend::config::normalize_backend_url(
+        &env::var("ASTRBOT_BACKEND_URL").unwrap_or_else(|_| DEFAULT_BACKEND_URL.to_string()),
+        DEFAULT_BACKEND_URL,
+    );
+    profile
+        .and_then(|profile| profile.dashboard_port)
+        .and_then(|port| backend_url_with_port(&backend_url, port))
+        .unwrap_or(backend_url)
+}
+
+pub(crate) fn read_persisted_dashboard_port(state_path: &Path) -> Option<u16> {
+    desktop_state::read_desktop_state_field_at(state_path, DASHBOARD_PORT_FIELD)?
+        .as_u64()
+        .and_then(|port| u16::try_from(port).ok())
+        .filter(|port| *port != 0)
+}
+
+pub(crate) fn write_persisted_dashboard_port(port: u16, state_path: &Path) -> Result<(), String> {
+    desktop_state::write_desktop_state_field_at(
+        state_path,
+        DASHBOARD_PORT_FIELD,
+        Some(serde_json::Value::from(port)),
+    )
+}
+
+fn lock_port_claims() -> std::sync::MutexGuard<'static, HashMap<u16, String>> {
+    match DASHBOARD_PORT_CLAIMS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+    {
+        Ok(guard) => guard,
+        Err(error) => error.into_inner(),
+    }
+}
+
+fn is_claimed_by_other(claims: &HashMap<u16, String>, port: u16, instance_id: &str) -> bool {
+    claims
+        .get(&port)
+        .is_some_and(|owner| owner.as_str() != instance_id)
+}
+
+pub(crate) fn release_dashboard_port_claims(instance_id: &str) {
+    lock_port_claims().retain(|_, owner| owner.as_str() != instance_id);
+}
+
+impl BackendState {
+    pub(crate) fn backend_url(&self) -> String {
+        match self.backend_url.lock() {
+            Ok(guard) => guard.clone(),
+            Err(error) => error.into_inner().clone(),
+        }
+    }
+
+    pub(crate) fn set_backend_url(&self, backend_url: String) {
+        match self.backend_url.lock() {
+            Ok(mut guard) => *guard = backend_url,
+            Err(error) => *error.into_inner() = backend_url,
+        }
+    }
+
+    fn set_backend_url_port(&self, port: u16) {
+        let current = self.backend_url();
+        if backend_url_port(&current) == Some(port) {
+            return;
+        }
+        if let Some(updated) = backend_url_with_port(&current, port) {
+            append_desktop_log(&format!("backend url switched to {updated}"));
+            self.set_backend_url(updated);
+        }
+    }
+
+    pub(crate) fn restore_persisted_dashboard_port(&self) {
+        let Some(state_path) = self.desktop_state_path() else {
+            return;
+        };
+        if let Some(port) = read_persisted_dashboard_port(&state_path) {
+            self.set_backend_url_port(port);
+        }
+    }
+
+    /// Picks the port the managed backend should listen on, moving off the current port when
+    /// something else (including another managed instance) already holds it, and persists the
+    /// choice for the next launch.
+    pub(crate) fn prepare_dashboard_port(&self) -> Result<u16, String> {
+        let current_port = backend_url_port(&self.backend_url())
+            .ok_or_else(|| "Backend URL has no usable port.".to_string())?;
+        let port = {
+            let mut claims = lock_port_claims();
+            let port = if !is_claimed_by_other(&claims, current_port, &self.id)
+                && is_loopback_port_available(current_port)
+            {
+                current_port
+            } else {
+                let free_port = (0..FREE_PORT_ATTEMPTS)
+                    .filter_map(|_| find_free_loopback_port())
+                    .find(|port| !is_claimed_by_other(&claims, *port, &self.id))
+                    .ok_or_else(|| {
+                        "Failed to find a free loopback port for backend.".to_string()
+                    })?;
+                append_desktop_log(&format!(
+                    "dashboard port {current_port} is occupied; using free port {free_port}"
+                ));
+                free_port
+            };
+            claims.retain(|_, owner| owner.as_str() != self.id);
+            claims.insert(port, self.id.clone());
+            port
+        };
+
+        self.set_backend_url_port(port);
+        if let Some(state_path) = self.desktop_state_path() {
+            if read_persisted_dashboard_port(&state_path) != Some(port) {
+                if let Err(error) = write_persisted_dashboard_port(port, &state_path) {
+                    append_desktop_log_at(
+                        DesktopLogLevel::Warn,
+                        &format!("failed to persist dashboard port: {error}"),
+                    );
+                }
+            }
+        }
+        Ok(port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dashboard_port_selection_is_disabled_by_any_override() {
+        assert!(is_dashboard_port_selection_enabled_with(|_| None));
+        for key in DASHBOARD_PORT_OVERRIDE_ENVS {
+            assert!(!is_dashboard_port_selection_enabled_with(|candidate| {
+                (candidate == key).then(|| "6185".to_string())
+            }));
+        }
+    }
+
+    #[test]
+    fn backend_url_with_port_replaces_port_and_keeps_path() {
+        assert_eq!(
+            backend_url_with_port("http://127.0.0.1:6185/", 6200).as_deref(),
+            Some("http://127.0.0.1:6200/")
+        );
+        assert_eq!(
+            backend_url_with_port("http://localhost:6185/api", 7000).as_deref(),
+            Some("http://localhost:7000/api")
+        );
+        assert_eq!(backend_url_port("http://127.0.0.1:6185/"), Some(6185));
+    }
+
+    #[test]
+    fn port_claims_only_block_other_instances() {
+        let claims = HashMap::from([(6185, "default".to_string())]);
+
+        assert!(!is_claimed_by_other(&claims, 6185, "default"));
+        assert!(is_claimed_by_other(&claims, 6185, "staging"));
+        assert!(!is_claimed_by_other(&claims, 6186, "staging"));
+    }
+
+    #[test]
+    fn occupied_loopback_port_is_reported_unavailable() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("bind listener");
+        let port = listener.local_addr().expect("local addr").port();
+
+        assert!(!is_loopback_port_available(port));
+        let free_port = find_free_loopback_port().expect("find free port");
+        assert_ne!(free_port, port);
+    }
+}
@@ -10,7 +10,7 @@ use crate::{backend::http_response, BackendState, GRACEFUL_RESTART_START_TIME_TI
 
 impl BackendState {
     pub(crate) fn ping_backend(&self, timeout_ms: u64) -> bool {
-        let parsed = match Url::parse(&self.backend_url) {
+        let parsed = match Url::parse(&self.backend_url()) {
             Ok(url) => url,
             Err(_) => return false,
         };
@@ -30,6 +30,8 @@ impl BackendState {
             .any(|address| TcpStream::connect_timeout(address, timeout).is_ok())
     }
 
+    /// Talks to the backend over a plain `TcpStream`, so configured proxies never see this
+    /// loopback traffic.
     pub(crate) fn request_backend_response_bytes(
         &self,
         method: &str,
@@ -38,7 +40,7 @@ impl BackendState {
         body: Option<&str>,
         auth_token: Option<&str>,
     ) -> Option<Vec<u8>> {
-        let base = Url::parse(&self.backend_url).ok()?;
+        let base = Url::parse(&self.backend_url()).ok()?;
         let request_url = base.join(api_path).ok()?;
         if request_url.scheme() != "http" {
             return None;
@@ -38,6 +38,17 @@ pub fn parse_backend_start_time(payload: &serde_json::Value) -> Option<i64> {
         .and_then(|value| i64::try_from(value).ok())
 }
 
+pub fn parse_backend_version(payload: &serde_json::Value) -> Option<String> {
+    if payload.get("status").and_then(|value| value.as_str()) != Some("ok") {
+        return None;
+    }
+    let version = payload.get("data")?.get("version")?.as_str()?.trim();
+    if version.is_empty() {
+        return None;
+    }
+    Some(version.to_string())
+}
+
 fn parse_http_response_parts(raw: &[u8]) -> Option<(Cow<'_, str>, &[u8])> {
     let header_end = raw.windows(4).position(|window| window == b"\r\n\r\n")?;
     let (header_bytes, body_bytes) = raw.split_at(header_end + 4);
@@ -138,4 +149,16 @@ mod tests {
         });
         assert_eq!(parse_backend_start_time(&payload), None);
     }
+
+    #[test]
+    fn parse_backend_version_reads_trimmed_version() {
+        let payload = json!({
+            "status": "ok",
+            "data": { "version": " v4.5.0 " }
+        });
+        assert_eq!(parse_backend_version(&payload).as_deref(), Some("v4.5.0"));
+
+        let missing = json!({ "status": "ok", "data": {} });
+        assert_eq!(parse_backend_version(&missing), None);
+    }
 }
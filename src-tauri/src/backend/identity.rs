@@ -0,0 +1,131 @@
+use std::env;
+
+use semver::Version;
+
+use crate::{
+    append_desktop_log, append_desktop_log_at, backend, backend::http_response,
+    logging::DesktopLogLevel, BackendState, BACKEND_IDENTITY_PROBE_TIMEOUT_MS,
+    BACKEND_IDENTITY_VERSION_PATH, BACKEND_MIN_VERSION_ENV,
+};
+
+pub(crate) fn parse_backend_semver(raw: &str) -> Option<Version> {
+    let trimmed = raw.trim();
+    let trimmed = trimmed
+        .strip_prefix('v')
+        .or_else(|| trimmed.strip_prefix('V'))
+        .unwrap_or(trimmed);
+    Version::parse(trimmed).ok()
+}
+
+pub(crate) fn check_backend_version_compatibility(
+    version: Option<&str>,
+    min_version: Option<&str>,
+) -> Result<(), String> {
+    let Some(min_version_raw) = min_version.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(());
+    };
+    let Some(min_version) = parse_backend_semver(min_version_raw) else {
+        append_desktop_log_at(
+            DesktopLogLevel::Warn,
+            &format!("ignoring invalid {BACKEND_MIN_VERSION_ENV} value: {min_version_raw}"),
+        );
+        return Ok(());
+    };
+    let Some(version_raw) = version else {
+        append_desktop_log_at(
+            DesktopLogLevel::Warn,
+            "backend version is unavailable; skipping desktop compatibility check",
+        );
+        return Ok(());
+    };
+    match parse_backend_semver(version_raw) {
+        Some(version) if version < min_version => Err(format!(
+            "AstrBot backend {version_raw} is older than the minimum version {min_version_raw} supported by this desktop shell."
+        )),
+        Some(_) => Ok(()),
+        None => {
+            append_desktop_log(&format!(
+                "backend version '{version_raw}' is not semver; skipping desktop compatibility check"
+            ));
+            Ok(())
+        }
+    }
+}
+
+impl BackendState {
+    /// Confirms that whatever answers on `backend_url` is an AstrBot dashboard before the shell
+    /// attaches to it instead of spawning its own backend.
+    pub(crate) fn verify_backend_identity(&self) -> Result<(), String> {
+        let ready_path = backend::runtime::backend_ready_http_path(append_desktop_log);
+        let Some(start_time) = self
+            .request_backend_json(
+                "GET",
+                &ready_path,
+                BACKEND_IDENTITY_PROBE_TIMEOUT_MS,
+                None,
+                None,
+            )
+            .as_ref()
+            .and_then(http_response::parse_backend_start_time)
+        else {
+            let backend_url = self.backend_url();
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!(
+                    "backend url {backend_url} is reachable but did not answer the AstrBot identity probe"
+                ),
+            );
+            return Err(format!(
+                "The port used by {backend_url} is occupied by a foreign process that is not an AstrBot dashboard. Stop that process or point ASTRBOT_BACKEND_URL to another address."
+            ));
+        };
+
+        let version = self
+            .request_backend_json(
+                "GET",
+                BACKEND_IDENTITY_VERSION_PATH,
+                BACKEND_IDENTITY_PROBE_TIMEOUT_MS,
+                None,
+                None,
+            )
+            .as_ref()
+            .and_then(http_response::parse_backend_version);
+        check_backend_version_compatibility(
+            version.as_deref(),
+            env::var(BACKEND_MIN_VERSION_ENV).ok().as_deref(),
+        )?;
+
+        append_desktop_log(&format!(
+            "verified reachable backend identity: start_time={}, version={}",
+            start_time,
+            version.as_deref().unwrap_or("unknown")
+        ));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_backend_semver_accepts_v_prefix() {
+        assert_eq!(parse_backend_semver("v4.5.1"), Version::parse("4.5.1").ok());
+        assert_eq!(parse_backend_semver("4.5.1"), Version::parse("4.5.1").ok());
+        assert_eq!(parse_backend_semver("latest"), None);
+    }
+
+    #[test]
+    fn check_backend_version_compatibility_rejects_older_backend() {
+        assert!(check_backend_version_compatibility(Some("v4.0.0"), Some("4.5.0")).is_err());
+        assert!(check_backend_version_compatibility(Some("v4.5.0"), Some("4.5.0")).is_ok());
+    }
+
+    #[test]
+    fn check_backend_version_compatibility_allows_unknown_inputs() {
+        assert!(check_backend_version_compatibility(None, Some("4.5.0")).is_ok());
+        assert!(check_backend_version_compatibility(Some("dev"), Some("4.5.0")).is_ok());
+        assert!(check_backend_version_compatibility(Some("v1.0.0"), None).is_ok());
+        assert!(check_backend_version_compatibility(Some("v1.0.0"), Some("")).is_ok());
+    }
+}
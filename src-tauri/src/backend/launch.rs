@@ -1,17 +1,25 @@
 use std::{
-    env,
-    fs::{self, OpenOptions},
+    env, fs,
     process::{Command, Stdio},
+    sync::{Arc, Mutex},
+    time::Instant,
 };
 
+#[cfg(target_os = "linux")]
+use std::os::unix::process::CommandExt;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
 use tauri::AppHandle;
 
 use crate::{
-    append_desktop_log, backend_path_override, build_debug_command, launch_plan, logging,
-    runtime_paths, BackendState, BACKEND_LOG_MAX_BYTES, DEFAULT_SHELL_LOCALE, LOG_BACKUP_COUNT,
+    append_desktop_log, append_desktop_log_at, backend,
+    backend::lifecycle::LifecycleEvent,
+    backend::log_stream::{BackendLogFile, BackendLogStream},
+    backend_path_override, build_debug_command, launch_plan, log_retention_policy,
+    logging::{self, DesktopLogLevel},
+    proxy_config, runtime_paths, BackendSpawnInfo, BackendState, BACKEND_LOG_MAX_BYTES,
+    DEFAULT_SHELL_LOCALE,
 };
 #[cfg(target_os = "windows")]
 use crate::{CREATE_NEW_PROCESS_GROUP, CREATE_NO_WINDOW};
@@ -43,21 +51,23 @@ fn mark_as_desktop_managed(command: &mut Command) {
 
 impl BackendState {
     pub(crate) fn resolve_launch_plan(&self, app: &AppHandle) -> Result<crate::LaunchPlan, String> {
-        if let Some(custom_cmd) = env::var("ASTRBOT_BACKEND_CMD")
-            .ok()
-            .map(|value| value.trim().to_string())
-            .filter(|value| !value.is_empty())
+        let root_dir = self.astrbot_root_dir();
+        if let Some(custom_cmd) =
+            backend::profile::profile_backend_cmd_override(self.profile.as_ref())
         {
-            return launch_plan::resolve_custom_launch(custom_cmd);
+            return launch_plan::resolve_custom_launch(custom_cmd, root_dir);
         }
 
-        if let Some(plan) =
-            launch_plan::resolve_packaged_launch(app, DEFAULT_SHELL_LOCALE, append_desktop_log)?
-        {
+        if let Some(plan) = launch_plan::resolve_packaged_launch(
+            app,
+            root_dir.clone(),
+            DEFAULT_SHELL_LOCALE,
+            append_desktop_log,
+        )? {
             return Ok(plan);
         }
 
-        launch_plan::resolve_dev_launch()
+        launch_plan::resolve_dev_launch(root_dir)
     }
 
     pub(crate) fn start_backend_process(
@@ -65,6 +75,7 @@ impl BackendState {
         app: &AppHandle,
         plan: &crate::LaunchPlan,
     ) -> Result<(), String> {
+        self.attach_app_handle(app);
         if self
             .child
             .lock()
@@ -113,12 +124,20 @@ impl BackendState {
         if let Some(path_override) = backend_path_override() {
             command.env("PATH", path_override);
         }
+        if let Some(profile) = self.profile.as_ref() {
+            command.envs(&profile.env);
+        }
         #[cfg(target_os = "windows")]
         {
             if plan.packaged_mode {
                 command.creation_flags(CREATE_NO_WINDOW | CREATE_NEW_PROCESS_GROUP);
             }
         }
+        #[cfg(target_os = "linux")]
+        {
+            // Lead a fresh process group so stop/restart can signal every subprocess at once.
+            command.process_group(0);
+        }
 
         if plan.packaged_mode {
             sanitize_packaged_python_environment(&mut command, append_desktop_log);
@@ -126,11 +145,46 @@ impl BackendState {
             if env::var("DASHBOARD_HOST").is_err() && env::var("ASTRBOT_DASHBOARD_HOST").is_err() {
                 command.env("DASHBOARD_HOST", "127.0.0.1");
             }
-            if env::var("DASHBOARD_PORT").is_err() && env::var("ASTRBOT_DASHBOARD_PORT").is_err() {
-                command.env("DASHBOARD_PORT", "6185");
+            if let Some(port) = self
+                .profile
+                .as_ref()
+                .and_then(|profile| profile.dashboard_port)
+                .filter(|_| {
+                    env::var("DASHBOARD_PORT").is_err()
+                        && env::var("ASTRBOT_DASHBOARD_PORT").is_err()
+                })
+            {
+                command.env("DASHBOARD_PORT", port.to_string());
+            } else if backend::dashboard_port::is_dashboard_port_selection_enabled(
+                self.profile.as_ref(),
+            ) {
+                let port = self.prepare_dashboard_port()?;
+                command.env("DASHBOARD_PORT", port.to_string());
             }
         }
 
+        let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+        let proxy_config = proxy_config::read_proxy_config(packaged_root_dir.as_deref())
+            .unwrap_or_else(|error| {
+                append_desktop_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!("ignoring proxy configuration: {error}"),
+                );
+                Default::default()
+            });
+        let inherited_no_proxy = env::var("NO_PROXY").or_else(|_| env::var("no_proxy")).ok();
+        proxy_config.apply_to_backend(&mut command, inherited_no_proxy.as_deref());
+
+        // Applied after the packaged sanitizing so users can still set PYTHONPATH and friends.
+        let env_overrides = self.backend_env_overrides().unwrap_or_else(|error| {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("ignoring backend environment overrides: {error}"),
+            );
+            Default::default()
+        });
+        env_overrides.apply(&mut command);
+
         if let Some(root_dir) = &plan.root_dir {
             command.env(crate::ASTRBOT_ROOT_ENV, root_dir);
         }
@@ -141,50 +195,27 @@ impl BackendState {
             command.env("ASTRBOT_WEBUI_DIR", webui_dir);
         }
 
-        let backend_log_path = Some(logging::resolve_backend_log_path(
+        let backend_log_path = logging::resolve_backend_log_path(
             plan.root_dir.as_deref(),
             runtime_paths::default_packaged_root_dir(),
-        ));
-        if let Some(log_path) = backend_log_path.as_ref() {
-            if let Some(log_parent) = log_path.parent() {
-                fs::create_dir_all(log_parent).map_err(|error| {
-                    format!(
-                        "Failed to create backend log directory {}: {}",
-                        log_parent.display(),
-                        error
-                    )
-                })?;
-            }
-            logging::rotate_log_if_needed(
-                log_path,
-                BACKEND_LOG_MAX_BYTES,
-                LOG_BACKUP_COUNT,
-                "backend",
-                false,
+        );
+        let log_file = BackendLogFile::open(
+            backend_log_path.clone(),
+            BACKEND_LOG_MAX_BYTES,
+            log_retention_policy(),
+        )
+        .map(|log_file| Arc::new(Mutex::new(log_file)))
+        .map_err(|error| {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("backend log file is unavailable, keeping output in memory only: {error}"),
             );
-            let stdout_file = OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(log_path)
-                .map_err(|error| {
-                    format!(
-                        "Failed to open backend log {}: {}",
-                        log_path.display(),
-                        error
-                    )
-                })?;
-            let stderr_file = stdout_file
-                .try_clone()
-                .map_err(|error| format!("Failed to clone backend log handle: {error}"))?;
-            command.stdout(Stdio::from(stdout_file));
-            command.stderr(Stdio::from(stderr_file));
-        } else {
-            self.stop_backend_log_rotation_worker();
-            command.stdout(Stdio::null());
-            command.stderr(Stdio::null());
-        }
+        })
+        .ok();
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
 
-        let child = command.spawn().map_err(|error| {
+        let mut child = command.spawn().map_err(|error| {
             format!(
                 "Failed to spawn backend process with command {:?}: {}",
                 build_debug_command(plan),
@@ -192,6 +223,12 @@ impl BackendState {
             )
         })?;
         let child_pid = child.id();
+        if let Some(stdout) = child.stdout.take() {
+            self.spawn_backend_log_reader(app, BackendLogStream::Stdout, stdout, log_file.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            self.spawn_backend_log_reader(app, BackendLogStream::Stderr, stderr, log_file.clone());
+        }
         append_desktop_log(&format!(
             "spawned backend: cmd={:?}, cwd={}",
             build_debug_command(plan),
@@ -201,11 +238,21 @@ impl BackendState {
             .child
             .lock()
             .map_err(|_| "Backend process lock poisoned.")? = Some(child);
-        if let Some(log_path) = backend_log_path {
-            self.start_backend_log_rotation_worker(app, log_path, child_pid);
-        } else {
-            self.stop_backend_log_rotation_worker();
+        if self.is_selected(app) {
+            logging::set_logged_backend_pid(Some(child_pid));
         }
+        self.set_spawn_info(BackendSpawnInfo {
+            pid: child_pid,
+            started_at: Instant::now(),
+            command: build_debug_command(plan),
+            log_path: log_file.is_some().then_some(backend_log_path),
+            startup_heartbeat_path: plan.startup_heartbeat_path.clone(),
+            env_overrides: Some(env_overrides),
+        });
+        self.write_backend_pidfile(child_pid, plan);
+        self.apply_lifecycle_event(LifecycleEvent::Spawned);
+        self.start_resource_monitor(app, child_pid);
+        self.start_restart_scheduler(app, child_pid);
         Ok(())
     }
 }
@@ -0,0 +1,344 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    append_desktop_log_at,
+    backend::{liveness::BackendHealth, log_stream::now_ms, readiness::StartupProgress},
+    logging::DesktopLogLevel,
+    BackendState, BACKEND_STATE_EVENT,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum BackendLifecycleState {
+    Starting,
+    Ready,
+    /// Liveness probes are failing but have not reached the unresponsive threshold yet.
+    Degraded,
+    Unresponsive,
+    Crashed,
+    #[default]
+    Stopped,
+    Restarting,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ReadinessPhase {
+    #[default]
+    Waiting,
+    TcpUp,
+    HttpUp,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReadinessProgress {
+    pub(crate) phase: ReadinessPhase,
+    pub(crate) tcp_up_at_ms: Option<u64>,
+    pub(crate) http_up_at_ms: Option<u64>,
+    pub(crate) last_heartbeat_at_ms: Option<u64>,
+    pub(crate) startup_progress: Option<StartupProgress>,
+    /// Filled in when a snapshot is taken.
+    pub(crate) heartbeat_age_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum LifecycleEvent {
+    Spawned,
+    TcpUp,
+    StartupHeartbeat {
+        updated_at_ms: u64,
+        progress: StartupProgress,
+    },
+    Ready,
+    Health(BackendHealth),
+    StartFailed(String),
+    Exited,
+    CrashLoop(String),
+    RestartBegan,
+    RestartFailed(String),
+    Stopped,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct BackendLifecycle {
+    pub(crate) state: BackendLifecycleState,
+    pub(crate) since_ms: u64,
+    pub(crate) last_error: Option<String>,
+    pub(crate) readiness: ReadinessProgress,
+}
+
+impl BackendLifecycle {
+    /// Applies `event` and returns the previous state when the state changed.
+    pub(crate) fn apply(
+        &mut self,
+        event: LifecycleEvent,
+        now_ms: u64,
+    ) -> Option<BackendLifecycleState> {
+        use BackendLifecycleState::*;
+
+        let restarting = self.state == Restarting;
+        let next = match event {
+            LifecycleEvent::Spawned => {
+                self.readiness = ReadinessProgress::default();
+                if restarting {
+                    Restarting
+                } else {
+                    Starting
+                }
+            }
+            LifecycleEvent::TcpUp => {
+                if self.readiness.phase == ReadinessPhase::Waiting {
+                    self.readiness.phase = ReadinessPhase::TcpUp;
+                    self.readiness.tcp_up_at_ms = Some(now_ms);
+                }
+                self.state
+            }
+            LifecycleEvent::StartupHeartbeat {
+                updated_at_ms,
+                progress,
+            } => {
+                self.readiness.last_heartbeat_at_ms = Some(updated_at_ms);
+                self.readiness.startup_progress = (!progress.is_empty()).then_some(progress);
+                self.state
+            }
+            LifecycleEvent::Ready => {
+                self.readiness.phase = ReadinessPhase::HttpUp;
+                self.readiness.tcp_up_at_ms.get_or_insert(now_ms);
+                self.readiness.http_up_at_ms.get_or_insert(now_ms);
+                self.last_error = None;
+                Ready
+            }
+            LifecycleEvent::Health(health) => match self.state {
+                Ready | Degraded | Unresponsive => match health.healthy {
+                    Some(false) => Unresponsive,
+                    _ if health.consecutive_failures > 0 => Degraded,
+                    _ => Ready,
+                },
+                other => other,
+            },
+            LifecycleEvent::StartFailed(error) | LifecycleEvent::RestartFailed(error) => {
+                self.last_error = Some(error);
+                // Keep Crashed when the failure was the child dying.
+                if self.state == Crashed {
+                    Crashed
+                } else {
+                    Stopped
+                }
+            }
+            LifecycleEvent::Exited => Crashed,
+            LifecycleEvent::CrashLoop(error) => {
+                self.last_error = Some(error);
+                Crashed
+            }
+            LifecycleEvent::RestartBegan => Restarting,
+            // A restart stops the old child on its way to spawning a new one.
+            LifecycleEvent::Stopped if restarting => Restarting,
+            LifecycleEvent::Stopped => Stopped,
+        };
+        if next == self.state {
+            return None;
+        }
+        let previous = self.state;
+        self.state = next;
+        self.since_ms = now_ms;
+        Some(previous)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendLifecycleSnapshot {
+    pub(crate) state: BackendLifecycleState,
+    pub(crate) state_since_ms: u64,
+    pub(crate) last_error: Option<String>,
+    pub(crate) last_exit_status: Option<String>,
+    pub(crate) pid: Option<u32>,
+    pub(crate) uptime_ms: Option<u64>,
+    pub(crate) readiness: ReadinessProgress,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackendStatePayload<'a> {
+    instance_id: &'a str,
+    #[serde(flatten)]
+    snapshot: BackendLifecycleSnapshot,
+}
+
+impl BackendState {
+    /// Remembers the app handle so lifecycle transitions from paths without one (stop,
+    /// readiness polling) can still be pushed to the page.
+    pub(crate) fn attach_app_handle(&self, app: &AppHandle) {
+        let _ = self.app_handle.set(app.clone());
+    }
+
+    fn lifecycle(&self) -> BackendLifecycle {
+        match self.lifecycle.lock() {
+            Ok(guard) => guard.clone(),
+            Err(error) => error.into_inner().clone(),
+        }
+    }
+
+    pub(crate) fn lifecycle_snapshot(&self) -> BackendLifecycleSnapshot {
+        let lifecycle = self.lifecycle();
+        let pid = self.current_child_pid();
+        let uptime_ms = pid
+            .and_then(|pid| self.spawn_info_for_pid(pid))
+            .map(|info| u64::try_from(info.started_at.elapsed().as_millis()).unwrap_or(u64::MAX));
+        let mut readiness = lifecycle.readiness;
+        readiness.heartbeat_age_ms = readiness
+            .last_heartbeat_at_ms
+            .map(|updated_at| now_ms().saturating_sub(updated_at));
+        BackendLifecycleSnapshot {
+            state: lifecycle.state,
+            state_since_ms: lifecycle.since_ms,
+            last_error: lifecycle.last_error,
+            last_exit_status: self.last_exit_status(),
+            pid,
+            uptime_ms,
+            readiness,
+        }
+    }
+
+    /// Feeds one event to the state machine; state transitions are logged, and they as well as
+    /// readiness phase or startup progress changes are pushed as `astrbot://backend-state`.
+    pub(crate) fn apply_lifecycle_event(&self, event: LifecycleEvent) {
+        let (transition, readiness_changed) = {
+            let mut guard = match self.lifecycle.lock() {
+                Ok(guard) => guard,
+                Err(error) => error.into_inner(),
+            };
+            let phase = guard.readiness.phase;
+            let progress = guard.readiness.startup_progress.clone();
+            let transition = guard
+                .apply(event, now_ms())
+                .map(|previous| (previous, guard.state));
+            let readiness_changed =
+                guard.readiness.phase != phase || guard.readiness.startup_progress != progress;
+            (transition, readiness_changed)
+        };
+        if let Some((previous, current)) = transition {
+            append_desktop_log_at(
+                DesktopLogLevel::Debug,
+                &format!(
+                    "backend instance={} state: {previous:?} -> {current:?}",
+                    self.id
+                ),
+            );
+        } else if !readiness_changed {
+            return;
+        }
+
+        let Some(app) = self.app_handle.get() else {
+            return;
+        };
+        if let Err(error) = app.emit(
+            BACKEND_STATE_EVENT,
+            BackendStatePayload {
+                instance_id: &self.id,
+                snapshot: self.lifecycle_snapshot(),
+            },
+        ) {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to emit backend state event: {error}"),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply_all(lifecycle: &mut BackendLifecycle, events: Vec<LifecycleEvent>) {
+        for (index, event) in events.into_iter().enumerate() {
+            lifecycle.apply(event, index as u64 + 1);
+        }
+    }
+
+    #[test]
+    fn startup_walks_through_readiness_phases() {
+        let mut lifecycle = BackendLifecycle::default();
+        assert_eq!(
+            lifecycle.apply(LifecycleEvent::Spawned, 1),
+            Some(BackendLifecycleState::Stopped)
+        );
+        assert_eq!(lifecycle.apply(LifecycleEvent::TcpUp, 2), None);
+        assert_eq!(lifecycle.readiness.phase, ReadinessPhase::TcpUp);
+        lifecycle.apply(LifecycleEvent::TcpUp, 3);
+        assert_eq!(lifecycle.readiness.tcp_up_at_ms, Some(2));
+
+        assert_eq!(
+            lifecycle.apply(LifecycleEvent::Ready, 4),
+            Some(BackendLifecycleState::Starting)
+        );
+        assert_eq!(lifecycle.readiness.phase, ReadinessPhase::HttpUp);
+        assert_eq!(lifecycle.readiness.http_up_at_ms, Some(4));
+        assert_eq!(lifecycle.since_ms, 4);
+    }
+
+    #[test]
+    fn health_only_moves_a_ready_backend() {
+        let failing = BackendHealth {
+            healthy: Some(true),
+            consecutive_failures: 1,
+            ..BackendHealth::default()
+        };
+        let unhealthy = BackendHealth {
+            healthy: Some(false),
+            consecutive_failures: 3,
+            ..BackendHealth::default()
+        };
+        let mut lifecycle = BackendLifecycle::default();
+        lifecycle.apply(LifecycleEvent::Health(unhealthy), 1);
+        assert_eq!(lifecycle.state, BackendLifecycleState::Stopped);
+
+        apply_all(
+            &mut lifecycle,
+            vec![LifecycleEvent::Spawned, LifecycleEvent::Ready],
+        );
+        lifecycle.apply(LifecycleEvent::Health(failing), 10);
+        assert_eq!(lifecycle.state, BackendLifecycleState::Degraded);
+        lifecycle.apply(LifecycleEvent::Health(unhealthy), 11);
+        assert_eq!(lifecycle.state, BackendLifecycleState::Unresponsive);
+        lifecycle.apply(LifecycleEvent::Health(BackendHealth::default()), 12);
+        assert_eq!(lifecycle.state, BackendLifecycleState::Ready);
+    }
+
+    #[test]
+    fn restart_stays_restarting_until_ready_and_keeps_crash_errors() {
+        let mut lifecycle = BackendLifecycle::default();
+        apply_all(
+            &mut lifecycle,
+            vec![
+                LifecycleEvent::Spawned,
+                LifecycleEvent::Ready,
+                LifecycleEvent::RestartBegan,
+                LifecycleEvent::Stopped,
+                LifecycleEvent::Spawned,
+            ],
+        );
+        assert_eq!(lifecycle.state, BackendLifecycleState::Restarting);
+        lifecycle.apply(LifecycleEvent::Ready, 10);
+        assert_eq!(lifecycle.state, BackendLifecycleState::Ready);
+
+        apply_all(
+            &mut lifecycle,
+            vec![
+                LifecycleEvent::Exited,
+                LifecycleEvent::StartFailed("exited before ready".to_string()),
+            ],
+        );
+        assert_eq!(lifecycle.state, BackendLifecycleState::Crashed);
+        assert_eq!(lifecycle.last_error.as_deref(), Some("exited before ready"));
+
+        lifecycle.apply(LifecycleEvent::RestartFailed("timeout".to_string()), 20);
+        assert_eq!(lifecycle.state, BackendLifecycleState::Crashed);
+        lifecycle.apply(LifecycleEvent::RestartBegan, 21);
+        lifecycle.apply(LifecycleEvent::RestartFailed("timeout".to_string()), 22);
+        assert_eq!(lifecycle.state, BackendLifecycleState::Stopped);
+    }
+}
@@ -0,0 +1,332 @@
+use std::{env, thread, time::Duration};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    append_desktop_log, append_desktop_log_at, append_restart_log_at, backend,
+    backend::{lifecycle::LifecycleEvent, log_stream::now_ms},
+    logging::DesktopLogLevel,
+    restart_backend_flow, tray, BackendRegistry, BackendState, BACKEND_HEALTH_EVENT,
+    BACKEND_LIVENESS_FAILURE_THRESHOLD_ENV, BACKEND_LIVENESS_INTERVAL_ENV,
+    BACKEND_LIVENESS_INTERVAL_MAX_MS, BACKEND_LIVENESS_INTERVAL_MIN_MS,
+    BACKEND_LIVENESS_RESTART_AFTER_ENV, DEFAULT_BACKEND_LIVENESS_FAILURE_THRESHOLD,
+    DEFAULT_BACKEND_LIVENESS_INTERVAL_MS, DEFAULT_SHELL_LOCALE,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LivenessConfig {
+    pub(crate) interval: Duration,
+    pub(crate) path: String,
+    pub(crate) probe_timeout_ms: u64,
+    /// Consecutive failed probes before the backend is reported unhealthy.
+    pub(crate) failure_threshold: u32,
+    /// Consecutive failed probes before a still-running backend is restarted; `None` never
+    /// restarts.
+    pub(crate) restart_after: Option<u32>,
+}
+
+fn parse_count<F>(raw: &str, env_name: &str, fallback: u32, log: F) -> u32
+where
+    F: Fn(&str),
+{
+    match raw.trim().parse::<u32>() {
+        Ok(value) => value,
+        Err(_) => {
+            log(&format!(
+                "invalid {env_name}='{raw}', fallback to {fallback}"
+            ));
+            fallback
+        }
+    }
+}
+
+/// `None` when liveness probing is disabled through `ASTRBOT_BACKEND_LIVENESS_INTERVAL_MS`.
+pub(crate) fn resolve_liveness_config_with<L, F>(
+    lookup: L,
+    path: String,
+    probe_timeout_ms: u64,
+    log: F,
+) -> Option<LivenessConfig>
+where
+    L: Fn(&str) -> Option<String>,
+    F: Fn(&str) + Copy,
+{
+    let interval_ms = match lookup(BACKEND_LIVENESS_INTERVAL_ENV) {
+        Some(raw) if matches!(raw.trim().to_ascii_lowercase().as_str(), "0" | "off") => {
+            return None;
+        }
+        Some(raw) => backend::config::parse_clamped_timeout_env(
+            &raw,
+            BACKEND_LIVENESS_INTERVAL_ENV,
+            DEFAULT_BACKEND_LIVENESS_INTERVAL_MS,
+            BACKEND_LIVENESS_INTERVAL_MIN_MS,
+            BACKEND_LIVENESS_INTERVAL_MAX_MS,
+            |message| log(&message),
+        ),
+        None => DEFAULT_BACKEND_LIVENESS_INTERVAL_MS,
+    };
+    let failure_threshold = lookup(BACKEND_LIVENESS_FAILURE_THRESHOLD_ENV)
+        .map(|raw| {
+            parse_count(
+                &raw,
+                BACKEND_LIVENESS_FAILURE_THRESHOLD_ENV,
+                DEFAULT_BACKEND_LIVENESS_FAILURE_THRESHOLD,
+                log,
+            )
+        })
+        .unwrap_or(DEFAULT_BACKEND_LIVENESS_FAILURE_THRESHOLD)
+        .max(1);
+    let restart_after = lookup(BACKEND_LIVENESS_RESTART_AFTER_ENV)
+        .map(|raw| parse_count(&raw, BACKEND_LIVENESS_RESTART_AFTER_ENV, 0, log))
+        .filter(|count| *count > 0);
+    Some(LivenessConfig {
+        interval: Duration::from_millis(interval_ms),
+        path,
+        probe_timeout_ms,
+        failure_threshold,
+        restart_after,
+    })
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendHealth {
+    /// `None` until the first liveness probe after startup.
+    pub(crate) healthy: Option<bool>,
+    pub(crate) consecutive_failures: u32,
+    pub(crate) last_status: Option<u16>,
+    pub(crate) last_checked_ms: Option<u64>,
+}
+
+impl BackendHealth {
+    /// Applies one probe result; returns `true` when the healthy flag changed.
+    pub(crate) fn record_probe(
+        &mut self,
+        status: Option<u16>,
+        failure_threshold: u32,
+        checked_at_ms: u64,
+    ) -> bool {
+        let ok = status.is_some_and(|status| (200..400).contains(&status));
+        self.consecutive_failures = if ok {
+            0
+        } else {
+            self.consecutive_failures.saturating_add(1)
+        };
+        self.last_status = status;
+        self.last_checked_ms = Some(checked_at_ms);
+
+        let healthy = match self.healthy {
+            // A single good answer is enough to come back.
+            _ if ok => true,
+            Some(true) | None => self.consecutive_failures < failure_threshold,
+            Some(false) => false,
+        };
+        let changed = self.healthy != Some(healthy);
+        self.healthy = Some(healthy);
+        changed
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackendHealthPayload<'a> {
+    instance_id: &'a str,
+    #[serde(flatten)]
+    health: BackendHealth,
+}
+
+impl BackendState {
+    pub(crate) fn backend_health(&self) -> BackendHealth {
+        match self.health.lock() {
+            Ok(guard) => *guard,
+            Err(error) => *error.into_inner(),
+        }
+    }
+
+    fn update_backend_health<F>(&self, update: F) -> BackendHealth
+    where
+        F: FnOnce(&mut BackendHealth),
+    {
+        let mut guard = match self.health.lock() {
+            Ok(guard) => guard,
+            Err(error) => error.into_inner(),
+        };
+        update(&mut guard);
+        *guard
+    }
+
+    /// Probes the ready path of the child that just passed its readiness check, until that
+    /// child is gone or replaced.
+    pub(crate) fn start_liveness_monitor(&self, app: &AppHandle, plan: &crate::LaunchPlan) {
+        let Some(child_pid) = self.current_child_pid() else {
+            return;
+        };
+        let readiness = backend::runtime::backend_readiness_config(plan, append_desktop_log);
+        let Some(config) = resolve_liveness_config_with(
+            |key| env::var(key).ok(),
+            readiness.path,
+            readiness.probe_timeout_ms,
+            append_desktop_log,
+        ) else {
+            return;
+        };
+        self.update_backend_health(|health| *health = BackendHealth::default());
+
+        let app_handle = app.clone();
+        let instance_id = self.id.clone();
+        let spawn_result = thread::Builder::new()
+            .name(format!("backend-liveness-{instance_id}"))
+            .spawn(move || loop {
+                thread::sleep(config.interval);
+                let Some(state) = app_handle.state::<BackendRegistry>().get(&instance_id) else {
+                    return;
+                };
+                if state.current_child_pid() != Some(child_pid) {
+                    return;
+                }
+                // Restarts and respawns take the backend down on purpose.
+                if restart_backend_flow::is_backend_action_in_progress(&state) {
+                    continue;
+                }
+                state.run_liveness_probe(&app_handle, &config, child_pid);
+            });
+        if let Err(error) = spawn_result {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to spawn backend liveness monitor: {error}"),
+            );
+        }
+    }
+
+    fn run_liveness_probe(&self, app: &AppHandle, config: &LivenessConfig, child_pid: u32) {
+        let status = self.request_backend_status_code(
+            "GET",
+            &config.path,
+            config.probe_timeout_ms,
+            None,
+            None,
+        );
+        let mut changed = false;
+        let health = self.update_backend_health(|health| {
+            changed = health.record_probe(status, config.failure_threshold, now_ms());
+        });
+        self.apply_lifecycle_event(LifecycleEvent::Health(health));
+
+        if changed {
+            let (level, verdict) = if health.healthy == Some(true) {
+                (DesktopLogLevel::Info, "healthy")
+            } else {
+                (DesktopLogLevel::Warn, "unresponsive")
+            };
+            append_desktop_log_at(
+                level,
+                &format!(
+                    "backend instance={} pid={child_pid} is {verdict}: path={}, status={}, consecutive_failures={}",
+                    self.id,
+                    config.path,
+                    status.map_or_else(|| "none".to_string(), |status| status.to_string()),
+                    health.consecutive_failures
+                ),
+            );
+            if let Err(error) = app.emit(
+                BACKEND_HEALTH_EVENT,
+                BackendHealthPayload {
+                    instance_id: &self.id,
+                    health,
+                },
+            ) {
+                append_desktop_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!("failed to emit backend health event: {error}"),
+                );
+            }
+            tray::labels::update_tray_instance_statuses(
+                app,
+                DEFAULT_SHELL_LOCALE,
+                append_desktop_log,
+            );
+        }
+
+        if config
+            .restart_after
+            .is_some_and(|restart_after| health.consecutive_failures >= restart_after)
+        {
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!(
+                    "backend pid={child_pid} failed {} liveness probes in a row while still running; restarting",
+                    health.consecutive_failures
+                ),
+            );
+            if let Err(error) =
+                restart_backend_flow::restart_backend_instance(app, Some(&self.id), None)
+            {
+                append_restart_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("liveness restart failed: {error}"),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn resolve(vars: &[(&str, &str)]) -> Option<LivenessConfig> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        resolve_liveness_config_with(
+            |key| vars.get(key).cloned(),
+            "/api/stat/start-time".to_string(),
+            800,
+            |_| {},
+        )
+    }
+
+    #[test]
+    fn liveness_config_defaults_and_overrides() {
+        let defaults = resolve(&[]).expect("enabled by default");
+        assert_eq!(
+            defaults.interval,
+            Duration::from_millis(DEFAULT_BACKEND_LIVENESS_INTERVAL_MS)
+        );
+        assert_eq!(
+            defaults.failure_threshold,
+            DEFAULT_BACKEND_LIVENESS_FAILURE_THRESHOLD
+        );
+        assert_eq!(defaults.restart_after, None);
+
+        assert_eq!(resolve(&[(BACKEND_LIVENESS_INTERVAL_ENV, "off")]), None);
+        let custom = resolve(&[
+            (BACKEND_LIVENESS_FAILURE_THRESHOLD_ENV, "0"),
+            (BACKEND_LIVENESS_RESTART_AFTER_ENV, "6"),
+        ])
+        .expect("enabled");
+        assert_eq!(custom.failure_threshold, 1);
+        assert_eq!(custom.restart_after, Some(6));
+    }
+
+    #[test]
+    fn health_flips_after_threshold_and_recovers_on_first_success() {
+        let mut health = BackendHealth::default();
+        assert!(health.record_probe(Some(200), 2, 1));
+        assert_eq!(health.healthy, Some(true));
+
+        assert!(!health.record_probe(None, 2, 2));
+        assert!(health.record_probe(Some(503), 2, 3));
+        assert_eq!(health.healthy, Some(false));
+        assert_eq!(health.consecutive_failures, 2);
+        assert!(!health.record_probe(None, 2, 4));
+
+        assert!(health.record_probe(Some(302), 2, 5));
+        assert_eq!(health.healthy, Some(true));
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.last_checked_ms, Some(5));
+    }
+}
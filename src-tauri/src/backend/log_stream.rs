@@ -0,0 +1,440 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    append_desktop_log_at,
+    log_retention::{self, LogRetentionPolicy},
+    logging::{self, DesktopLogLevel},
+    BackendState, BACKEND_LOG_EVENT, BACKEND_LOG_LINE_MAX_BYTES,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum BackendLogStream {
+    Stdout,
+    Stderr,
+    /// Notes the desktop app adds to the console, e.g. why no output is shown.
+    Desktop,
+}
+
+impl BackendLogStream {
+    fn as_label(self) -> &'static str {
+        match self {
+            Self::Stdout => "stdout",
+            Self::Stderr => "stderr",
+            Self::Desktop => "desktop",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendLogLine {
+    pub(crate) seq: u64,
+    pub(crate) instance_id: String,
+    pub(crate) stream: BackendLogStream,
+    pub(crate) timestamp_ms: u64,
+    pub(crate) line: String,
+}
+
+/// Most recent backend output lines, kept so a console view opened late can backfill.
+#[derive(Debug)]
+pub(crate) struct BackendLogRing {
+    lines: VecDeque<BackendLogLine>,
+    capacity: usize,
+    next_seq: u64,
+}
+
+impl BackendLogRing {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity.min(1024)),
+            capacity,
+            next_seq: 1,
+        }
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        instance_id: &str,
+        stream: BackendLogStream,
+        timestamp_ms: u64,
+        line: String,
+    ) -> BackendLogLine {
+        let entry = BackendLogLine {
+            seq: self.next_seq,
+            instance_id: instance_id.to_string(),
+            stream,
+            timestamp_ms,
+            line,
+        };
+        self.next_seq += 1;
+        if self.capacity == 0 {
+            return entry;
+        }
+        while self.lines.len() >= self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(entry.clone());
+        entry
+    }
+
+    pub(crate) fn recent(&self, limit: usize) -> Vec<BackendLogLine> {
+        let skip = self.lines.len().saturating_sub(limit);
+        self.lines.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// Append-only `backend.log` writer that rotates by renaming once it grows past `max_bytes`
+/// (or at the first line of a new day under daily rotation), shared by the stdout and stderr
+/// reader threads.
+#[derive(Debug)]
+pub(crate) struct BackendLogFile {
+    path: PathBuf,
+    file: Option<File>,
+    written: u64,
+    opened_on: NaiveDate,
+    max_bytes: u64,
+    retention: LogRetentionPolicy,
+    /// Set after a failed write so a full disk logs once instead of once per line.
+    write_failed: bool,
+}
+
+impl BackendLogFile {
+    pub(crate) fn open(
+        path: PathBuf,
+        max_bytes: u64,
+        retention: LogRetentionPolicy,
+    ) -> Result<Self, String> {
+        if let Some(log_parent) = path.parent() {
+            fs::create_dir_all(log_parent).map_err(|error| {
+                format!(
+                    "Failed to create backend log directory {}: {}",
+                    log_parent.display(),
+                    error
+                )
+            })?;
+        }
+        log_retention::rotate_log_if_needed(&path, max_bytes, &retention, "backend", false, warn);
+        let (file, written) = open_append(&path)?;
+        Ok(Self {
+            path,
+            file: Some(file),
+            written,
+            opened_on: Local::now().date_naive(),
+            max_bytes,
+            retention,
+            write_failed: false,
+        })
+    }
+
+    pub(crate) fn write_line(&mut self, line: &str) {
+        let size_due = self.max_bytes > 0 && self.written >= self.max_bytes;
+        let day_due = self.retention.is_daily() && Local::now().date_naive() != self.opened_on;
+        if size_due || day_due {
+            self.rotate();
+        }
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        match file
+            .write_all(line.as_bytes())
+            .and_then(|()| file.write_all(b"\n"))
+        {
+            Ok(()) => {
+                self.written += line.len() as u64 + 1;
+                self.write_failed = false;
+            }
+            Err(error) => {
+                if !self.write_failed {
+                    warn(format!(
+                        "[log stream:backend] failed to write {}: {}",
+                        self.path.display(),
+                        error
+                    ));
+                }
+                self.write_failed = true;
+            }
+        }
+    }
+
+    fn rotate(&mut self) {
+        // Close the handle first so the rename also works on Windows.
+        self.file = None;
+        log_retention::rotate_log_if_needed(
+            &self.path,
+            self.max_bytes,
+            &self.retention,
+            "backend",
+            false,
+            warn,
+        );
+        match open_append(&self.path) {
+            Ok((file, written)) => {
+                self.file = Some(file);
+                self.written = written;
+                self.opened_on = Local::now().date_naive();
+            }
+            Err(error) => warn(format!("[log stream:backend] {error}")),
+        }
+    }
+}
+
+fn warn(message: String) {
+    append_desktop_log_at(DesktopLogLevel::Warn, &message);
+}
+
+fn open_append(path: &Path) -> Result<(File, u64), String> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|error| format!("Failed to open backend log {}: {}", path.display(), error))?;
+    let written = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+    Ok((file, written))
+}
+
+/// Splits child output at `\n`, `\r\n` or a lone `\r` (progress bars redraw with it) and cuts
+/// lines at `max_bytes`.
+struct BackendLogLineReader<R> {
+    reader: BufReader<R>,
+    max_bytes: usize,
+    /// The previous line ended in `\r`; a `\n` right after it completes that line ending.
+    skip_lf: bool,
+}
+
+impl<R: Read> BackendLogLineReader<R> {
+    fn new(pipe: R, max_bytes: usize) -> Self {
+        Self {
+            reader: BufReader::new(pipe),
+            max_bytes,
+            skip_lf: false,
+        }
+    }
+
+    /// Reads the next line into `raw` without its line ending; `Ok(false)` at EOF.
+    fn read_line(&mut self, raw: &mut Vec<u8>) -> io::Result<bool> {
+        raw.clear();
+        loop {
+            let available = match self.reader.fill_buf() {
+                Ok(available) => available,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            if available.is_empty() {
+                return Ok(!raw.is_empty());
+            }
+            if std::mem::take(&mut self.skip_lf) && available[0] == b'\n' {
+                self.reader.consume(1);
+                continue;
+            }
+
+            let window = &available[..available.len().min(self.max_bytes - raw.len())];
+            if let Some(end) = window.iter().position(|byte| matches!(byte, b'\n' | b'\r')) {
+                raw.extend_from_slice(&window[..end]);
+                self.skip_lf = window[end] == b'\r';
+                self.reader.consume(end + 1);
+                return Ok(true);
+            }
+            let taken = window.len();
+            raw.extend_from_slice(window);
+            self.reader.consume(taken);
+            if raw.len() >= self.max_bytes {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+impl BackendState {
+    /// Reads one child pipe until EOF, teeing every line to the log file, the ring buffer and
+    /// the `BACKEND_LOG_EVENT` stream of the main window, the only page with the bridge.
+    pub(crate) fn spawn_backend_log_reader<R>(
+        &self,
+        app: &AppHandle,
+        stream: BackendLogStream,
+        pipe: R,
+        log_file: Option<Arc<Mutex<BackendLogFile>>>,
+    ) where
+        R: Read + Send + 'static,
+    {
+        let app_handle = app.clone();
+        let instance_id = self.id.clone();
+        let log_buffer = Arc::clone(&self.log_buffer);
+        let spawn_result = thread::Builder::new()
+            .name(format!("backend-{}-{}", instance_id, stream.as_label()))
+            .spawn(move || {
+                let mut reader = BackendLogLineReader::new(pipe, BACKEND_LOG_LINE_MAX_BYTES);
+                let mut raw = Vec::new();
+                let mut emit_failed = false;
+                loop {
+                    match reader.read_line(&mut raw) {
+                        Ok(false) => break,
+                        Ok(true) => {}
+                        Err(error) => {
+                            append_desktop_log_at(
+                                DesktopLogLevel::Warn,
+                                &format!(
+                                    "backend {} reader stopped for instance {}: {}",
+                                    stream.as_label(),
+                                    instance_id,
+                                    error
+                                ),
+                            );
+                            break;
+                        }
+                    }
+
+                    let line = String::from_utf8_lossy(&raw).into_owned();
+                    if let Some(log_file) = log_file.as_ref() {
+                        match log_file.lock() {
+                            Ok(mut guard) => guard.write_line(&line),
+                            Err(error) => error.into_inner().write_line(&line),
+                        }
+                    }
+                    let entry = match log_buffer.lock() {
+                        Ok(mut guard) => guard.push(&instance_id, stream, now_ms(), line),
+                        Err(error) => error
+                            .into_inner()
+                            .push(&instance_id, stream, now_ms(), line),
+                    };
+                    if let Err(error) = app_handle.emit_to("main", BACKEND_LOG_EVENT, &entry) {
+                        if !emit_failed {
+                            warn(format!(
+                                "[log stream:backend] failed to emit backend log event: {error}"
+                            ));
+                        }
+                        emit_failed = true;
+                    }
+                }
+            });
+        if let Err(error) = spawn_result {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!(
+                    "failed to spawn backend {} reader: {}",
+                    stream.as_label(),
+                    error
+                ),
+            );
+        }
+    }
+
+    /// `backend.log` of the running child, or where the next spawn of this instance will write.
+    pub(crate) fn backend_log_path(&self) -> PathBuf {
+        let spawned_log_path = match self.spawn_info.lock() {
+            Ok(guard) => guard.as_ref().and_then(|info| info.log_path.clone()),
+            Err(error) => error
+                .into_inner()
+                .as_ref()
+                .and_then(|info| info.log_path.clone()),
+        };
+        spawned_log_path.unwrap_or_else(|| {
+            logging::resolve_backend_log_path(
+                self.astrbot_root_dir().as_deref(),
+                crate::runtime_paths::default_packaged_root_dir(),
+            )
+        })
+    }
+
+    /// Adds a desktop-authored line to the backend console, in the ring and on the page.
+    pub(crate) fn push_backend_log_note(&self, app: &AppHandle, line: &str) {
+        let entry = match self.log_buffer.lock() {
+            Ok(mut guard) => guard.push(&self.id, BackendLogStream::Desktop, now_ms(), line.into()),
+            Err(error) => {
+                error
+                    .into_inner()
+                    .push(&self.id, BackendLogStream::Desktop, now_ms(), line.into())
+            }
+        };
+        if let Err(error) = app.emit_to("main", BACKEND_LOG_EVENT, &entry) {
+            warn(format!(
+                "[log stream:backend] failed to emit backend log event: {error}"
+            ));
+        }
+    }
+
+    pub(crate) fn recent_backend_logs(&self, limit: usize) -> Vec<BackendLogLine> {
+        match self.log_buffer.lock() {
+            Ok(guard) => guard.recent(limit),
+            Err(error) => error.into_inner().recent(limit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn log_ring_keeps_latest_lines_with_increasing_seq() {
+        let mut ring = BackendLogRing::new(2);
+        for line in ["one", "two", "three"] {
+            ring.push("default", BackendLogStream::Stdout, 0, line.to_string());
+        }
+
+        let recent = ring.recent(10);
+        assert_eq!(
+            recent
+                .iter()
+                .map(|entry| (entry.seq, entry.line.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(2, "two"), (3, "three")]
+        );
+        assert_eq!(ring.recent(1)[0].line, "three");
+    }
+
+    #[test]
+    fn log_line_reader_splits_on_any_line_ending_and_caps_long_lines() {
+        let mut reader =
+            BackendLogLineReader::new(&b"ready\r\n50%\r100%\rbad \xff\n0123456789tail"[..], 10);
+        let mut raw = Vec::new();
+        let mut lines = Vec::new();
+        while reader.read_line(&mut raw).expect("read line") {
+            lines.push(String::from_utf8_lossy(&raw).into_owned());
+        }
+        assert_eq!(
+            lines,
+            vec!["ready", "50%", "100%", "bad \u{fffd}", "0123456789", "tail"]
+        );
+    }
+
+    #[test]
+    fn log_file_rotates_when_size_limit_is_reached() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join("logs").join("backend.log");
+        let mut log_file = BackendLogFile::open(path.clone(), 8, LogRetentionPolicy::size_based(2))
+            .expect("open log file");
+
+        log_file.write_line("first line");
+        log_file.write_line("second");
+
+        assert_eq!(
+            fs::read_to_string(&path).expect("read active log"),
+            "second\n"
+        );
+        assert_eq!(
+            fs::read_to_string(path.with_file_name("backend.log.1")).expect("read backup"),
+            "first line\n"
+        );
+    }
+}
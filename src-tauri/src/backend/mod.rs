@@ -1,10 +1,25 @@
 pub(crate) mod config;
+pub(crate) mod crash_history;
+pub(crate) mod dashboard_port;
+pub(crate) mod env_overrides;
 pub(crate) mod http;
 pub(crate) mod http_response;
+pub(crate) mod identity;
 pub(crate) mod launch;
+pub(crate) mod lifecycle;
+pub(crate) mod liveness;
+pub(crate) mod log_stream;
+pub(crate) mod orphan;
 pub(crate) mod path;
 pub(crate) mod process_lifecycle;
+pub(crate) mod profile;
 pub(crate) mod readiness;
+pub(crate) mod registry;
+pub(crate) mod resource_monitor;
+pub(crate) mod resource_watchdog;
 pub(crate) mod restart;
+pub(crate) mod restart_schedule;
 pub(crate) mod restart_strategy;
 pub(crate) mod runtime;
+pub(crate) mod supervisor;
+pub(crate) mod supervisor_policy;
@@ -0,0 +1,543 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::MutexGuard,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    append_desktop_log, append_desktop_log_at, append_restart_log, backend,
+    backend::{lifecycle::LifecycleEvent, log_stream::now_ms},
+    logging::{self, DesktopLogLevel},
+    process_control, runtime_paths, window, BackendRegistry, BackendSpawnInfo, BackendState,
+    LaunchPlan, BACKEND_ORPHAN_PROMPT_TIMEOUT,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendPidFile {
+    pub(crate) pid: u32,
+    pub(crate) started_at_ms: u64,
+    pub(crate) command: Vec<String>,
+    pub(crate) plan_hash: String,
+}
+
+/// A backend left running by an earlier desktop session, verified by pid and command line.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OrphanBackend {
+    pub(crate) pid: u32,
+    pub(crate) started_at_ms: u64,
+    /// `false` when the orphan was launched with a different command, cwd or root.
+    pub(crate) plan_matches: bool,
+    #[serde(skip)]
+    pub(crate) command: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum OrphanAction {
+    Adopt,
+    Respawn,
+}
+
+impl OrphanAction {
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "adopt" => Some(Self::Adopt),
+            "respawn" => Some(Self::Respawn),
+            _ => None,
+        }
+    }
+}
+
+impl OrphanBackend {
+    /// Used when nobody answers the startup page prompt.
+    pub(crate) fn default_action(&self) -> OrphanAction {
+        if self.plan_matches {
+            OrphanAction::Adopt
+        } else {
+            OrphanAction::Respawn
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct OrphanPrompt {
+    pending: Option<OrphanBackend>,
+    choice: Option<OrphanAction>,
+}
+
+pub(crate) fn resolve_pidfile_path(desktop_state_path: &Path, instance_id: &str) -> PathBuf {
+    let file_id: String = instance_id
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    desktop_state_path.with_file_name(format!("backend-{file_id}.pid.json"))
+}
+
+/// FNV-1a over the parts of the plan that decide which backend runs; stable across builds.
+pub(crate) fn launch_plan_hash(plan: &LaunchPlan) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let root_dir = plan
+        .root_dir
+        .as_ref()
+        .map(|root| root.display().to_string())
+        .unwrap_or_default();
+    let cwd = plan.cwd.display().to_string();
+    let parts = std::iter::once(plan.cmd.as_str())
+        .chain(plan.args.iter().map(String::as_str))
+        .chain([cwd.as_str(), root_dir.as_str()]);
+    for part in parts {
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+/// Compares argument by argument after whitespace splitting, since `ps` cannot report the
+/// original boundaries; the program only has to match by file name.
+pub(crate) fn command_line_matches(recorded: &[String], actual: &[String]) -> bool {
+    let recorded_tokens: Vec<&str> = recorded
+        .iter()
+        .flat_map(|arg| arg.split_whitespace())
+        .collect();
+    let actual_tokens: Vec<&str> = actual
+        .iter()
+        .flat_map(|arg| arg.split_whitespace())
+        .collect();
+    let (Some((recorded_program, recorded_args)), Some((actual_program, actual_args))) =
+        (recorded_tokens.split_first(), actual_tokens.split_first())
+    else {
+        return false;
+    };
+    recorded_args == actual_args
+        && Path::new(recorded_program).file_name() == Path::new(actual_program).file_name()
+}
+
+/// Compares only the executable, ignoring directory, case and an `.exe` extension.
+pub(crate) fn program_name_matches(recorded: &[String], executable: &str) -> bool {
+    let stem = |program: &str| {
+        let name = program.rsplit(['/', '\\']).next().unwrap_or(program);
+        let name = name.to_ascii_lowercase();
+        name.strip_suffix(".exe")
+            .map(str::to_string)
+            .unwrap_or(name)
+    };
+    recorded
+        .first()
+        .is_some_and(|program| !executable.is_empty() && stem(program) == stem(executable))
+}
+
+/// `Some(true)` when `pid` is alive and runs `command`; `None` when it is alive but neither its
+/// command line nor its executable can be read.
+fn process_runs_command(pid: u32, command: &[String]) -> Option<bool> {
+    if !process_control::is_process_alive(pid) {
+        return Some(false);
+    }
+    if let Some(actual) = process_control::read_process_command_line(pid) {
+        return Some(command_line_matches(command, &actual));
+    }
+    // Hosts that hide command lines still expose the executable, which is enough to tell the
+    // backend apart from an unrelated process that reused its pid.
+    process_control::read_process_executable_name(pid)
+        .map(|executable| program_name_matches(command, &executable))
+}
+
+pub(crate) fn read_pidfile(path: &Path) -> Option<BackendPidFile> {
+    let raw = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+pub(crate) fn write_pidfile(path: &Path, record: &BackendPidFile) -> Result<(), String> {
+    if let Some(parent_dir) = path.parent() {
+        fs::create_dir_all(parent_dir).map_err(|error| {
+            format!(
+                "Failed to create backend pidfile directory {}: {error}",
+                parent_dir.display()
+            )
+        })?;
+    }
+    let raw = serde_json::to_string_pretty(record)
+        .map_err(|error| format!("Failed to serialize backend pidfile: {error}"))?;
+    fs::write(path, raw).map_err(|error| {
+        format!(
+            "Failed to write backend pidfile {}: {error}",
+            path.display()
+        )
+    })
+}
+
+impl BackendState {
+    fn pidfile_path(&self) -> Option<PathBuf> {
+        self.desktop_state_path()
+            .map(|path| resolve_pidfile_path(&path, &self.id))
+    }
+
+    pub(crate) fn write_backend_pidfile(&self, pid: u32, plan: &LaunchPlan) {
+        let Some(path) = self.pidfile_path() else {
+            return;
+        };
+        let record = BackendPidFile {
+            pid,
+            started_at_ms: now_ms(),
+            command: crate::build_debug_command(plan),
+            plan_hash: launch_plan_hash(plan),
+        };
+        if let Err(error) = write_pidfile(&path, &record) {
+            append_desktop_log_at(DesktopLogLevel::Warn, &error);
+        }
+    }
+
+    /// Removes the pidfile when it still describes `pid`; a newer spawn keeps its own file.
+    pub(crate) fn clear_backend_pidfile(&self, pid: u32) {
+        let Some(path) = self.pidfile_path() else {
+            return;
+        };
+        if read_pidfile(&path).is_some_and(|record| record.pid != pid) {
+            return;
+        }
+        if let Err(error) = fs::remove_file(&path) {
+            if error.kind() != std::io::ErrorKind::NotFound {
+                append_desktop_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!(
+                        "failed to remove backend pidfile {}: {error}",
+                        path.display()
+                    ),
+                );
+            }
+        }
+    }
+
+    pub(crate) fn adopted_backend_pid(&self) -> Option<u32> {
+        match self.adopted_pid.lock() {
+            Ok(guard) => *guard,
+            Err(error) => *error.into_inner(),
+        }
+    }
+
+    pub(crate) fn set_adopted_backend_pid(&self, pid: Option<u32>) {
+        match self.adopted_pid.lock() {
+            Ok(mut guard) => *guard = pid,
+            Err(error) => *error.into_inner() = pid,
+        }
+    }
+
+    /// Drops every trace of the adopted backend `pid` once it has exited.
+    pub(crate) fn forget_adopted_backend(&self, pid: u32) {
+        match self.adopted_pid.lock() {
+            Ok(mut guard) if *guard == Some(pid) => *guard = None,
+            Err(error) => {
+                let mut guard = error.into_inner();
+                if *guard == Some(pid) {
+                    *guard = None;
+                }
+            }
+            Ok(_) => {}
+        }
+        self.clear_backend_pidfile(pid);
+        logging::clear_logged_backend_pid(pid);
+    }
+
+    /// Whether the adopted `pid` still runs the command recorded when it was spawned, so a pid
+    /// reused by an unrelated process is never signalled.
+    pub(crate) fn adopted_backend_still_running(&self, pid: u32) -> bool {
+        let recorded = self
+            .pidfile_path()
+            .and_then(|path| read_pidfile(&path))
+            .filter(|record| record.pid == pid)
+            .map(|record| record.command)
+            .or_else(|| self.spawn_info_for_pid(pid).map(|info| info.command));
+        recorded.is_some_and(|command| process_runs_command(pid, &command) == Some(true))
+    }
+
+    fn detect_orphan_backend(&self, plan: &LaunchPlan) -> Option<OrphanBackend> {
+        if self.current_child_pid().is_some() {
+            return None;
+        }
+        let path = self.pidfile_path()?;
+        let record = read_pidfile(&path)?;
+        match process_runs_command(record.pid, &record.command) {
+            Some(true) => {}
+            Some(false) => {
+                append_desktop_log(&format!(
+                    "discarding stale backend pidfile: pid={} is gone or runs a different command",
+                    record.pid
+                ));
+                self.clear_backend_pidfile(record.pid);
+                return None;
+            }
+            None => {
+                // Neither adopted nor stopped: it may be an unrelated process that reused the pid.
+                append_desktop_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!(
+                        "backend pid={} from the pidfile is still alive but its command cannot be read; leaving it running and keeping {}",
+                        record.pid,
+                        path.display()
+                    ),
+                );
+                return None;
+            }
+        }
+        let orphan = OrphanBackend {
+            pid: record.pid,
+            started_at_ms: record.started_at_ms,
+            plan_matches: record.plan_hash == launch_plan_hash(plan),
+            command: record.command,
+        };
+        append_desktop_log(&format!(
+            "found orphaned backend from a previous session: pid={}, plan_matches={}",
+            orphan.pid, orphan.plan_matches
+        ));
+        Some(orphan)
+    }
+
+    /// Deals with a backend left behind by a crashed desktop session. Returns `true` when it was
+    /// adopted and is ready; otherwise it has been stopped and a fresh spawn should follow.
+    pub(crate) fn take_over_orphan_backend(&self, app: &AppHandle) -> Result<bool, String> {
+        let Ok(plan) = self.resolve_launch_plan(app) else {
+            return Ok(false);
+        };
+        let Some(orphan) = self.detect_orphan_backend(&plan) else {
+            return Ok(false);
+        };
+
+        if self.await_orphan_action(app, &orphan) == OrphanAction::Adopt {
+            match self.adopt_orphan_backend(app, &plan, &orphan) {
+                Ok(()) => return Ok(true),
+                Err(error) => append_desktop_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!(
+                        "cannot adopt orphaned backend pid={}, respawning instead: {error}",
+                        orphan.pid
+                    ),
+                ),
+            }
+        }
+
+        append_restart_log(&format!("stopping orphaned backend pid={}", orphan.pid));
+        let stop_timeout = backend::runtime::backend_stop_config(append_desktop_log).signal_timeout;
+        if !process_control::stop_process_by_pid(orphan.pid, stop_timeout, append_desktop_log) {
+            return Err(format!(
+                "Orphaned backend process {} did not exit after {}ms.",
+                orphan.pid,
+                stop_timeout.as_millis()
+            ));
+        }
+        self.clear_backend_pidfile(orphan.pid);
+        Ok(false)
+    }
+
+    fn lock_orphan_prompt(&self) -> MutexGuard<'_, OrphanPrompt> {
+        match self.orphan_prompt.lock() {
+            Ok(guard) => guard,
+            Err(error) => error.into_inner(),
+        }
+    }
+
+    fn set_pending_orphan(&self, orphan: Option<OrphanBackend>) {
+        *self.lock_orphan_prompt() = OrphanPrompt {
+            pending: orphan,
+            choice: None,
+        };
+    }
+
+    /// The orphan the startup page should currently ask about, if any.
+    pub(crate) fn pending_orphan(&self) -> Option<OrphanBackend> {
+        self.lock_orphan_prompt().pending.clone()
+    }
+
+    fn await_orphan_action(&self, app: &AppHandle, orphan: &OrphanBackend) -> OrphanAction {
+        let default_action = orphan.default_action();
+        let is_selected = app.state::<BackendRegistry>().selected_id() == self.id;
+        // Stashed first so a startup page that finishes loading meanwhile still shows it.
+        self.set_pending_orphan(Some(orphan.clone()));
+        if !is_selected
+            || !window::startup_loading::show_orphan_prompt(app, orphan, append_desktop_log)
+        {
+            self.set_pending_orphan(None);
+            append_desktop_log(&format!(
+                "orphaned backend pid={} handled with default action {default_action:?}",
+                orphan.pid
+            ));
+            return default_action;
+        }
+
+        let deadline = Instant::now() + BACKEND_ORPHAN_PROMPT_TIMEOUT;
+        let mut prompt = self.lock_orphan_prompt();
+        while prompt.choice.is_none() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            prompt = match self.orphan_prompt_changed.wait_timeout(prompt, remaining) {
+                Ok((guard, _)) => guard,
+                Err(error) => error.into_inner().0,
+            };
+        }
+        prompt.pending = None;
+        prompt.choice.take().unwrap_or_else(|| {
+            append_desktop_log(&format!(
+                "no answer for orphaned backend pid={}, using default action {default_action:?}",
+                orphan.pid
+            ));
+            default_action
+        })
+    }
+
+    /// Answers the startup page prompt raised by `take_over_orphan_backend`.
+    pub(crate) fn choose_orphan_action(&self, action: OrphanAction) -> Result<(), String> {
+        let mut prompt = self.lock_orphan_prompt();
+        if prompt.pending.is_none() {
+            return Err("No orphaned backend is waiting for a decision.".to_string());
+        }
+        prompt.choice = Some(action);
+        self.orphan_prompt_changed.notify_all();
+        Ok(())
+    }
+
+    fn adopt_orphan_backend(
+        &self,
+        app: &AppHandle,
+        plan: &LaunchPlan,
+        orphan: &OrphanBackend,
+    ) -> Result<(), String> {
+        if !self.ping_backend(backend::runtime::backend_ping_timeout_ms(
+            append_desktop_log,
+        )) {
+            return Err("it is not reachable".to_string());
+        }
+
+        self.set_adopted_backend_pid(Some(orphan.pid));
+        if self.is_selected(app) {
+            logging::set_logged_backend_pid(Some(orphan.pid));
+        }
+        let uptime = Duration::from_millis(now_ms().saturating_sub(orphan.started_at_ms));
+        let log_path = logging::resolve_backend_log_path(
+            plan.root_dir.as_deref(),
+            runtime_paths::default_packaged_root_dir(),
+        );
+        self.set_spawn_info(BackendSpawnInfo {
+            pid: orphan.pid,
+            started_at: Instant::now()
+                .checked_sub(uptime)
+                .unwrap_or_else(Instant::now),
+            command: orphan.command.clone(),
+            log_path: log_path.is_file().then_some(log_path),
+            startup_heartbeat_path: plan.startup_heartbeat_path.clone(),
+            env_overrides: None,
+        });
+        append_desktop_log(&format!(
+            "adopted orphaned backend pid={}; watching it by pid",
+            orphan.pid
+        ));
+        append_desktop_log_at(
+            DesktopLogLevel::Warn,
+            &format!(
+                "logs unavailable for adopted backend pid={}: its stdout/stderr belonged to the previous desktop session",
+                orphan.pid
+            ),
+        );
+        self.push_backend_log_note(
+            app,
+            &format!(
+                "Logs are unavailable for the adopted backend (pid {}); restart the backend to capture its output again.",
+                orphan.pid
+            ),
+        );
+        self.apply_lifecycle_event(LifecycleEvent::Spawned);
+        self.apply_lifecycle_event(LifecycleEvent::Ready);
+        self.start_backend_supervisor(app);
+        self.start_resource_monitor(app, orphan.pid);
+        self.start_restart_scheduler(app, orphan.pid);
+        self.start_liveness_monitor(app, plan);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(cmd: &str, args: &[&str]) -> LaunchPlan {
+        LaunchPlan {
+            cmd: cmd.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            cwd: PathBuf::from("/opt/astrbot"),
+            root_dir: Some(PathBuf::from("/home/user/.astrbot")),
+            webui_dir: None,
+            startup_heartbeat_path: None,
+            packaged_mode: true,
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn pidfile_round_trips_next_to_desktop_state() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = resolve_pidfile_path(&dir.path().join("desktop_state.json"), "work/prod");
+        assert_eq!(path, dir.path().join("backend-work_prod.pid.json"));
+
+        let record = BackendPidFile {
+            pid: 4242,
+            started_at_ms: 1_700_000_000_000,
+            command: strings(&["python", "main.py"]),
+            plan_hash: launch_plan_hash(&plan("python", &["main.py"])),
+        };
+        write_pidfile(&path, &record).expect("write pidfile");
+        assert_eq!(read_pidfile(&path), Some(record));
+    }
+
+    #[test]
+    fn launch_plan_hash_tracks_command_and_args() {
+        let base = launch_plan_hash(&plan("python", &["main.py"]));
+        assert_eq!(base, launch_plan_hash(&plan("python", &["main.py"])));
+        assert_ne!(
+            base,
+            launch_plan_hash(&plan("python", &["main.py", "--debug"]))
+        );
+        // Argument boundaries count: "a b" is not "a", "b".
+        assert_ne!(
+            launch_plan_hash(&plan("python", &["a b"])),
+            launch_plan_hash(&plan("python", &["a", "b"]))
+        );
+    }
+
+    #[test]
+    fn command_line_matching_relaxes_only_the_program_path() {
+        let recorded = strings(&["/opt/astrbot/python/bin/python3", "launch_backend.py"]);
+        assert!(command_line_matches(
+            &recorded,
+            &strings(&["python3", "launch_backend.py"])
+        ));
+        assert!(!command_line_matches(
+            &recorded,
+            &strings(&["python3", "other.py"])
+        ));
+        assert!(!command_line_matches(&recorded, &[]));
+        assert!(program_name_matches(
+            &strings(&[r"C:\AstrBot\Python\python.exe", "launch_backend.py"]),
+            "PYTHON.EXE"
+        ));
+        assert!(program_name_matches(&recorded, "python3"));
+        assert!(!program_name_matches(&recorded, "node"));
+        assert_eq!(OrphanAction::parse(" Adopt "), Some(OrphanAction::Adopt));
+        assert_eq!(OrphanAction::parse("kill"), None);
+    }
+}
@@ -1,140 +1,291 @@
 use std::{
-    path::PathBuf,
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use tauri::{AppHandle, Manager};
-
 use crate::{
-    append_desktop_log, logging, process_control, BackendState, BACKEND_LOG_MAX_BYTES,
-    BACKEND_LOG_ROTATION_CHECK_INTERVAL, GRACEFUL_STOP_TIMEOUT_MS, LOG_BACKUP_COUNT,
+    append_desktop_log, append_shutdown_log, append_shutdown_log_at, backend,
+    backend::lifecycle::LifecycleEvent,
+    logging::{self, DesktopLogLevel},
+    process_control, BackendState, BACKEND_GRACEFUL_STOP_TIMEOUT_ENV,
+    BACKEND_SHUTDOWN_HTTP_PATH_ENV, BACKEND_SHUTDOWN_HTTP_TIMEOUT_ENV,
+    BACKEND_SHUTDOWN_POLL_INTERVAL, BACKEND_SHUTDOWN_REQUEST_TIMEOUT_MS,
+    BACKEND_STOP_TIMEOUT_MAX_MS, BACKEND_STOP_TIMEOUT_MIN_MS,
+    DEFAULT_BACKEND_SHUTDOWN_HTTP_TIMEOUT_MS, DEFAULT_GRACEFUL_STOP_TIMEOUT_MS,
 };
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BackendStopConfig {
+    /// Endpoint asked to shut the backend down before any signal is sent; `None` skips that stage.
+    pub(crate) http_path: Option<String>,
+    pub(crate) http_timeout: Duration,
+    pub(crate) signal_timeout: Duration,
+}
+
+pub(crate) fn resolve_stop_config_with<L, F>(lookup: L, log: F) -> BackendStopConfig
+where
+    L: Fn(&str) -> Option<String>,
+    F: Fn(&str) + Copy,
+{
+    let timeout = |env_name: &str, fallback_ms: u64| {
+        let ms = lookup(env_name)
+            .map(|raw| {
+                backend::config::parse_clamped_timeout_env(
+                    &raw,
+                    env_name,
+                    fallback_ms,
+                    BACKEND_STOP_TIMEOUT_MIN_MS,
+                    BACKEND_STOP_TIMEOUT_MAX_MS,
+                    |message| log(&message),
+                )
+            })
+            .unwrap_or(fallback_ms);
+        Duration::from_millis(ms)
+    };
+    let http_path = lookup(BACKEND_SHUTDOWN_HTTP_PATH_ENV).and_then(|raw| {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("off") {
+            return None;
+        }
+        Some(if trimmed.starts_with('/') {
+            trimmed.to_string()
+        } else {
+            format!("/{trimmed}")
+        })
+    });
+    BackendStopConfig {
+        http_path,
+        http_timeout: timeout(
+            BACKEND_SHUTDOWN_HTTP_TIMEOUT_ENV,
+            DEFAULT_BACKEND_SHUTDOWN_HTTP_TIMEOUT_MS,
+        ),
+        signal_timeout: timeout(
+            BACKEND_GRACEFUL_STOP_TIMEOUT_ENV,
+            DEFAULT_GRACEFUL_STOP_TIMEOUT_MS,
+        ),
+    }
+}
+
 impl BackendState {
     pub(crate) fn stop_backend(&self) -> Result<(), String> {
-        self.stop_backend_log_rotation_worker();
+        self.stop_backend_supervisor();
+        self.stop_managed_child()
+    }
+
+    pub(crate) fn stop_managed_child(&self) -> Result<(), String> {
+        let child_pid = {
+            let guard = self
+                .child
+                .lock()
+                .map_err(|_| "Backend process lock poisoned.".to_string())?;
+            match guard.as_ref() {
+                Some(child) => child.id(),
+                None => {
+                    drop(guard);
+                    return self.stop_adopted_backend();
+                }
+            }
+        };
+
+        let config = backend::runtime::backend_stop_config(append_desktop_log);
+        let descendants = process_control::snapshot_descendants(child_pid);
+        // The HTTP stage can take seconds; holding the child lock through it would stall every
+        // status query that needs the pid.
+        self.request_http_shutdown(child_pid, &config, || self.managed_child_exited(child_pid));
+
         let mut guard = self
             .child
             .lock()
             .map_err(|_| "Backend process lock poisoned.".to_string())?;
-
-        let Some(child) = guard.as_mut() else {
-            return Ok(());
+        let stopped = match guard.as_mut() {
+            Some(child) if child.id() == child_pid => {
+                process_control::stop_child_process_gracefully(
+                    child,
+                    config.signal_timeout,
+                    append_desktop_log,
+                )
+            }
+            // Reaped by someone else while the lock was released.
+            _ => true,
         };
-
-        if process_control::stop_child_process_gracefully(
-            child,
-            Duration::from_millis(GRACEFUL_STOP_TIMEOUT_MS),
-            append_desktop_log,
-        ) {
-            *guard = None;
+        if stopped {
+            if guard.as_ref().is_some_and(|child| child.id() == child_pid) {
+                *guard = None;
+            }
+            drop(guard);
+            report_surviving_descendants(child_pid, &descendants);
+            self.clear_backend_pidfile(child_pid);
+            logging::clear_logged_backend_pid(child_pid);
+            self.apply_lifecycle_event(LifecycleEvent::Stopped);
             return Ok(());
         }
 
         Err(format!(
             "Backend process did not exit after {}ms graceful stop timeout.",
-            GRACEFUL_STOP_TIMEOUT_MS
+            config.signal_timeout.as_millis()
         ))
     }
 
-    pub(crate) fn stop_backend_log_rotation_worker(&self) {
-        match self.log_rotator_stop.lock() {
-            Ok(mut guard) => {
-                if let Some(flag) = guard.take() {
-                    flag.store(true, Ordering::Relaxed);
-                }
-            }
-            Err(error) => {
-                append_desktop_log(&format!(
-                    "backend log rotator stop flag lock poisoned: {error}"
-                ));
-            }
-        }
-    }
-
-    fn child_matches_pid_and_alive(&self, child_pid: u32) -> bool {
+    /// Whether the child `pid` is gone; a missing or replaced child counts as exited.
+    fn managed_child_exited(&self, pid: u32) -> bool {
         let mut guard = match self.child.lock() {
             Ok(guard) => guard,
-            Err(error) => {
-                append_desktop_log(&format!(
-                    "backend child lock poisoned while checking log rotator worker pid={child_pid}: {error}"
-                ));
-                return false;
-            }
+            Err(error) => error.into_inner(),
         };
+        match guard.as_mut() {
+            Some(child) if child.id() == pid => matches!(child.try_wait(), Ok(Some(_))),
+            _ => true,
+        }
+    }
 
-        let Some(child) = guard.as_mut() else {
-            return false;
+    fn stop_adopted_backend(&self) -> Result<(), String> {
+        let Some(pid) = self.adopted_backend_pid() else {
+            return Ok(());
         };
-        if child.id() != child_pid {
-            return false;
+        let config = backend::runtime::backend_stop_config(append_desktop_log);
+        if self.adopted_backend_still_running(pid) {
+            self.request_http_shutdown(pid, &config, || !process_control::is_process_alive(pid));
+        }
+        // Checked again right before signalling: the pid may have been reused meanwhile.
+        if !self.adopted_backend_still_running(pid) {
+            append_shutdown_log(&format!(
+                "adopted backend pid={pid} no longer runs the recorded command; nothing to signal"
+            ));
+        } else if !process_control::stop_process_by_pid(
+            pid,
+            config.signal_timeout,
+            append_desktop_log,
+        ) {
+            return Err(format!(
+                "Adopted backend process {pid} did not exit after {}ms graceful stop timeout.",
+                config.signal_timeout.as_millis()
+            ));
         }
+        self.forget_adopted_backend(pid);
+        self.apply_lifecycle_event(LifecycleEvent::Stopped);
+        Ok(())
+    }
 
-        match child.try_wait() {
-            Ok(None) => true,
-            Ok(Some(status)) => {
-                append_desktop_log(&format!(
-                    "backend process exited, stop log rotator worker: pid={child_pid}, status={status}"
-                ));
-                false
-            }
-            Err(error) => {
-                append_desktop_log(&format!(
-                    "failed to poll backend process status for log rotator worker pid={child_pid}: {error}"
-                ));
-                false
+    /// Asks the backend to shut itself down over HTTP and waits until it exits, or until its
+    /// heartbeat reports `stopping` and the port is closed. Signals take over afterwards either
+    /// way, so a rejected request or a timeout only costs the wait.
+    fn request_http_shutdown<E>(&self, pid: u32, config: &BackendStopConfig, mut exited: E)
+    where
+        E: FnMut() -> bool,
+    {
+        let Some(path) = config.http_path.as_deref() else {
+            return;
+        };
+        let auth_token = self.get_restart_auth_token();
+        match self.request_backend_status_code(
+            "POST",
+            path,
+            BACKEND_SHUTDOWN_REQUEST_TIMEOUT_MS,
+            Some("{}"),
+            auth_token.as_deref(),
+        ) {
+            Some(code) if !(200..300).contains(&code) => {
+                append_shutdown_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!(
+                        "backend pid={pid} rejected HTTP shutdown request with status {code}; escalating to signals"
+                    ),
+                );
+                return;
             }
+            // No status usually means the backend went away before answering.
+            _ => append_shutdown_log(&format!("requested HTTP shutdown of backend pid={pid}")),
         }
-    }
 
-    pub(crate) fn start_backend_log_rotation_worker(
-        &self,
-        app: &AppHandle,
-        log_path: PathBuf,
-        child_pid: u32,
-    ) {
-        self.stop_backend_log_rotation_worker();
-        let stop_flag = Arc::new(AtomicBool::new(false));
-        match self.log_rotator_stop.lock() {
-            Ok(mut guard) => {
-                *guard = Some(stop_flag.clone());
+        let heartbeat_path = self
+            .spawn_info_for_pid(pid)
+            .and_then(|info| info.startup_heartbeat_path);
+        let ping_timeout_ms = backend::runtime::backend_ping_timeout_ms(append_desktop_log);
+        let started_at = Instant::now();
+        loop {
+            if exited() {
+                append_shutdown_log(&format!(
+                    "backend pid={pid} exited {}ms after HTTP shutdown request",
+                    started_at.elapsed().as_millis()
+                ));
+                return;
             }
-            Err(error) => {
-                append_desktop_log(&format!(
-                    "backend log rotator stop flag lock poisoned on start: {error}"
+            let stopping = heartbeat_path.as_deref().is_none_or(|path| {
+                backend::readiness::startup_heartbeat_reports_stopping(path, pid)
+            });
+            if stopping && !self.ping_backend(ping_timeout_ms) {
+                append_shutdown_log(&format!(
+                    "backend pid={pid} reported stopping and closed its port {}ms after HTTP shutdown request",
+                    started_at.elapsed().as_millis()
                 ));
                 return;
             }
-        }
-
-        let app_handle = app.clone();
-        thread::spawn(move || {
-            let log_scope = format!("backend(pid={child_pid})");
-            loop {
-                if stop_flag.load(Ordering::Relaxed) {
-                    break;
-                }
-                thread::sleep(BACKEND_LOG_ROTATION_CHECK_INTERVAL);
-                if stop_flag.load(Ordering::Relaxed) {
-                    break;
-                }
-                let state = app_handle.state::<BackendState>();
-                if !state.child_matches_pid_and_alive(child_pid) {
-                    break;
-                }
-                logging::rotate_log_if_needed(
-                    &log_path,
-                    BACKEND_LOG_MAX_BYTES,
-                    LOG_BACKUP_COUNT,
-                    &log_scope,
-                    true,
+            if started_at.elapsed() >= config.http_timeout {
+                append_shutdown_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!(
+                        "backend pid={pid} did not stop within {}ms of HTTP shutdown request; escalating to signals",
+                        config.http_timeout.as_millis()
+                    ),
                 );
+                return;
             }
-        });
+            thread::sleep(BACKEND_SHUTDOWN_POLL_INTERVAL);
+        }
+    }
+}
+
+fn report_surviving_descendants(child_pid: u32, descendants: &[u32]) {
+    let survivors = process_control::surviving_descendants(child_pid, descendants);
+    if survivors.is_empty() {
+        return;
+    }
+    append_shutdown_log(&format!(
+        "backend pid={child_pid} stopped but {} descendant process(es) survived: {survivors:?}",
+        survivors.len()
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_stop_config_defaults_to_signals_only() {
+        let config = resolve_stop_config_with(|_| None, |_| {});
+        assert_eq!(config.http_path, None);
+        assert_eq!(
+            config.http_timeout,
+            Duration::from_millis(DEFAULT_BACKEND_SHUTDOWN_HTTP_TIMEOUT_MS)
+        );
+        assert_eq!(
+            config.signal_timeout,
+            Duration::from_millis(DEFAULT_GRACEFUL_STOP_TIMEOUT_MS)
+        );
+    }
+
+    #[test]
+    fn resolve_stop_config_reads_path_and_clamps_stage_timeouts() {
+        let config = resolve_stop_config_with(
+            |name| match name {
+                BACKEND_SHUTDOWN_HTTP_PATH_ENV => Some(" api/stat/shutdown ".to_string()),
+                BACKEND_SHUTDOWN_HTTP_TIMEOUT_ENV => Some("60000".to_string()),
+                BACKEND_GRACEFUL_STOP_TIMEOUT_ENV => Some("10".to_string()),
+                _ => None,
+            },
+            |_| {},
+        );
+        assert_eq!(config.http_path.as_deref(), Some("/api/stat/shutdown"));
+        assert_eq!(config.http_timeout, Duration::from_secs(60));
+        assert_eq!(
+            config.signal_timeout,
+            Duration::from_millis(BACKEND_STOP_TIMEOUT_MIN_MS)
+        );
+
+        let disabled = resolve_stop_config_with(
+            |name| (name == BACKEND_SHUTDOWN_HTTP_PATH_ENV).then(|| "off".to_string()),
+            |_| {},
+        );
+        assert_eq!(disabled.http_path, None);
     }
 }
@@ -0,0 +1,242 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    env,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{desktop_state, runtime_paths, BackendState};
+
+const PROFILES_FIELD: &str = "profiles";
+const ACTIVE_PROFILE_FIELD: &str = "activeProfile";
+pub(crate) const DEFAULT_PROFILE_ID: &str = "default";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendProfile {
+    pub(crate) id: String,
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    #[serde(default)]
+    pub(crate) root: Option<String>,
+    #[serde(default)]
+    pub(crate) dashboard_port: Option<u16>,
+    #[serde(default)]
+    pub(crate) backend_cmd: Option<String>,
+    #[serde(default)]
+    pub(crate) env: BTreeMap<String, String>,
+}
+
+impl BackendProfile {
+    pub(crate) fn display_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&self.id)
+    }
+
+    pub(crate) fn root_dir(&self) -> Option<&str> {
+        non_empty(self.root.as_deref())
+    }
+
+    pub(crate) fn backend_cmd(&self) -> Option<&str> {
+        non_empty(self.backend_cmd.as_deref())
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// Parses the `profiles` array, dropping entries without a usable id and duplicate ids so a
+/// hand-edited state file cannot break startup.
+pub(crate) fn parse_backend_profiles(raw: Option<Value>) -> Vec<BackendProfile> {
+    let Some(Value::Array(entries)) = raw else {
+        return Vec::new();
+    };
+
+    let mut seen = HashSet::new();
+    entries
+        .into_iter()
+        .filter_map(|entry| serde_json::from_value::<BackendProfile>(entry).ok())
+        .filter_map(|mut profile| {
+            profile.id = profile.id.trim().to_string();
+            if profile.id.is_empty() || !seen.insert(profile.id.clone()) {
+                return None;
+            }
+            Some(profile)
+        })
+        .collect()
+}
+
+pub(crate) fn find_backend_profile(
+    profiles: &[BackendProfile],
+    profile_id: Option<&str>,
+) -> Option<BackendProfile> {
+    let profile_id = non_empty(profile_id)?;
+    profiles
+        .iter()
+        .find(|profile| profile.id == profile_id)
+        .cloned()
+}
+
+pub(crate) fn load_backend_profiles(packaged_root_dir: Option<&Path>) -> Vec<BackendProfile> {
+    parse_backend_profiles(desktop_state::read_shared_desktop_state_field(
+        PROFILES_FIELD,
+        packaged_root_dir,
+    ))
+}
+
+pub(crate) fn read_active_profile_id(packaged_root_dir: Option<&Path>) -> Option<String> {
+    desktop_state::read_shared_desktop_state_field(ACTIVE_PROFILE_FIELD, packaged_root_dir)?
+        .as_str()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+pub(crate) fn write_active_profile_id(
+    profile_id: Option<&str>,
+    packaged_root_dir: Option<&Path>,
+) -> Result<(), String> {
+    desktop_state::write_shared_desktop_state_field(
+        ACTIVE_PROFILE_FIELD,
+        non_empty(profile_id).map(|value| Value::String(value.to_string())),
+        packaged_root_dir,
+    )
+}
+
+/// The profile persisted as `activeProfile`, selected when the shell starts.
+pub(crate) fn load_active_profile() -> Option<BackendProfile> {
+    let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+    let profiles = load_backend_profiles(packaged_root_dir.as_deref());
+    find_backend_profile(
+        &profiles,
+        read_active_profile_id(packaged_root_dir.as_deref()).as_deref(),
+    )
+}
+
+/// Root directory for `profile`, falling back to `ASTRBOT_ROOT`.
+pub(crate) fn profile_root_override(profile: Option<&BackendProfile>) -> Option<String> {
+    if let Some(root) = profile.and_then(BackendProfile::root_dir) {
+        return Some(root.to_string());
+    }
+    env::var(crate::ASTRBOT_ROOT_ENV).ok()
+}
+
+pub(crate) fn profile_backend_cmd_override(profile: Option<&BackendProfile>) -> Option<String> {
+    if let Some(cmd) = profile.and_then(BackendProfile::backend_cmd) {
+        return Some(cmd.to_string());
+    }
+    env::var("ASTRBOT_BACKEND_CMD")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Root the backend of `profile` ends up in: its own root or `ASTRBOT_ROOT`, else the packaged
+/// root the launch plan falls back to.
+pub(crate) fn resolved_profile_root_dir(profile: Option<&BackendProfile>) -> Option<PathBuf> {
+    profile_root_dir(profile).or_else(runtime_paths::default_packaged_root_dir)
+}
+
+fn profile_root_dir(profile: Option<&BackendProfile>) -> Option<PathBuf> {
+    profile_root_override(profile)
+        .map(|root| root.trim().to_string())
+        .filter(|root| !root.is_empty())
+        .map(PathBuf::from)
+}
+
+impl BackendState {
+    pub(crate) fn astrbot_root_dir(&self) -> Option<PathBuf> {
+        profile_root_dir(self.profile.as_ref())
+    }
+
+    /// Desktop state file of this instance; profiles with their own root keep their own file.
+    pub(crate) fn desktop_state_path(&self) -> Option<PathBuf> {
+        let root_dir = self.astrbot_root_dir();
+        let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+        desktop_state::resolve_desktop_state_path_with_root(
+            root_dir.as_deref().and_then(Path::to_str),
+            packaged_root_dir.as_deref(),
+        )
+    }
+
+    pub(crate) fn crash_history_path(&self) -> Option<PathBuf> {
+        self.desktop_state_path()
+            .map(|path| crate::backend::crash_history::resolve_crash_history_path(&path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_backend_profiles_skips_invalid_and_duplicate_entries() {
+        let profiles = parse_backend_profiles(Some(json!([
+            { "id": "test", "name": "Test", "root": "/tmp/test", "dashboardPort": 6190 },
+            { "id": " " },
+            { "name": "missing id" },
+            { "id": "test", "root": "/tmp/duplicate" },
+            { "id": "prod", "backendCmd": "uv run main.py", "env": { "FOO": "bar" } }
+        ])));
+
+        assert_eq!(
+            profiles
+                .iter()
+                .map(|profile| profile.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["test", "prod"]
+        );
+        assert_eq!(profiles[0].dashboard_port, Some(6190));
+        assert_eq!(profiles[0].root_dir(), Some("/tmp/test"));
+        assert_eq!(profiles[1].backend_cmd(), Some("uv run main.py"));
+        assert_eq!(profiles[1].env.get("FOO").map(String::as_str), Some("bar"));
+    }
+
+    #[test]
+    fn parse_backend_profiles_returns_empty_for_non_array() {
+        assert!(parse_backend_profiles(None).is_empty());
+        assert!(parse_backend_profiles(Some(json!({ "id": "test" }))).is_empty());
+    }
+
+    #[test]
+    fn find_backend_profile_matches_trimmed_id() {
+        let profiles = parse_backend_profiles(Some(json!([{ "id": "staging" }])));
+
+        assert_eq!(
+            find_backend_profile(&profiles, Some(" staging ")).map(|profile| profile.id),
+            Some("staging".to_string())
+        );
+        assert_eq!(find_backend_profile(&profiles, Some("missing")), None);
+        assert_eq!(find_backend_profile(&profiles, None), None);
+    }
+
+    #[test]
+    fn display_name_falls_back_to_id() {
+        let profile = BackendProfile {
+            id: "prod".to_string(),
+            name: Some("  ".to_string()),
+            ..BackendProfile::default()
+        };
+        assert_eq!(profile.display_name(), "prod");
+    }
+
+    #[test]
+    fn resolved_profile_root_dir_prefers_the_profile_root() {
+        let profile = BackendProfile {
+            id: "staging".to_string(),
+            root: Some(" /srv/astrbot-staging ".to_string()),
+            ..BackendProfile::default()
+        };
+        assert_eq!(
+            resolved_profile_root_dir(Some(&profile)),
+            Some(PathBuf::from("/srv/astrbot-staging"))
+        );
+    }
+}
@@ -1,24 +1,77 @@
 use std::{
     env, fs,
     path::Path,
+    sync::atomic::Ordering,
     thread,
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter, Manager};
 
 use crate::{
-    append_desktop_log, backend, AtomicFlagGuard, BackendState, BACKEND_TIMEOUT_ENV,
-    PACKAGED_BACKEND_TIMEOUT_FALLBACK_MS,
+    append_desktop_log, append_desktop_log_at, backend, backend::lifecycle::LifecycleEvent,
+    logging, logging::DesktopLogLevel, AtomicFlagGuard, BackendRegistry, BackendState,
+    BACKEND_STARTUP_PROGRESS_EVENT, BACKEND_TIMEOUT_ENV, PACKAGED_BACKEND_TIMEOUT_FALLBACK_MS,
 };
 
+const STARTUP_CANCELLED_REASON: &str = "Backend startup was cancelled.";
+
 impl BackendState {
     pub(crate) fn ensure_backend_ready(&self, app: &AppHandle) -> Result<(), String> {
-        if self.ping_backend(backend::runtime::backend_ping_timeout_ms(
+        self.attach_app_handle(app);
+        if self.current_child_pid().is_some() {
+            append_desktop_log(&format!("backend instance {} is already running", self.id));
+            return Ok(());
+        }
+
+        // A packaged backend whose port we pick ourselves must never attach to whatever
+        // happens to be listening there; it moves to a free port instead.
+        let port_managed_plan = if backend::dashboard_port::is_dashboard_port_selection_enabled(
+            self.profile.as_ref(),
+        ) {
+            self.resolve_launch_plan(app)
+                .ok()
+                .filter(|plan| plan.packaged_mode)
+        } else {
+            None
+        };
+        if port_managed_plan.is_some() {
+            self.restore_persisted_dashboard_port();
+        }
+        if self.take_over_orphan_backend(app)? {
+            return Ok(());
+        }
+
+        if let Some(holder) = app
+            .state::<BackendRegistry>()
+            .instance_using_backend_url(&self.id, &self.backend_url())
+        {
+            if port_managed_plan.is_none() {
+                return Err(format!(
+                    "Backend URL {} is already used by backend instance {}; give profile {} its own dashboardPort.",
+                    self.backend_url(),
+                    holder.id,
+                    self.id
+                ));
+            }
+            append_desktop_log(&format!(
+                "backend url {} is used by backend instance {}; relocating managed backend",
+                self.backend_url(),
+                holder.id
+            ));
+        } else if self.ping_backend(backend::runtime::backend_ping_timeout_ms(
             append_desktop_log,
         )) {
-            append_desktop_log("backend already reachable, skip spawn");
-            return Ok(());
+            if port_managed_plan.is_none() {
+                self.verify_backend_identity()?;
+                append_desktop_log("backend already reachable, skip spawn");
+                self.apply_lifecycle_event(LifecycleEvent::Ready);
+                return Ok(());
+            }
+            append_desktop_log(&format!(
+                "backend url {} is occupied by another process; relocating managed backend",
+                self.backend_url()
+            ));
         }
 
         if env::var("ASTRBOT_BACKEND_AUTO_START").unwrap_or_else(|_| "1".to_string()) == "0" {
@@ -30,9 +83,21 @@ impl BackendState {
 
         let _spawn_guard = AtomicFlagGuard::try_set(&self.is_spawning)
             .ok_or_else(|| "Backend action already in progress.".to_string())?;
-        let plan = self.resolve_launch_plan(app)?;
-        self.start_backend_process(app, &plan)?;
-        self.wait_for_backend(&plan)
+        let plan = match port_managed_plan {
+            Some(plan) => plan,
+            None => self.resolve_launch_plan(app)?,
+        };
+        self.launch_supervised_backend(app, &plan)
+    }
+
+    /// Asks the readiness wait in progress to give up; the child is stopped by the launch path.
+    pub(crate) fn request_startup_cancel(&self) -> Result<(), String> {
+        if !self.is_spawning.load(Ordering::Relaxed) {
+            return Err("No backend startup is in progress.".to_string());
+        }
+        append_desktop_log("backend startup cancellation requested");
+        self.startup_cancel_requested.store(true, Ordering::Relaxed);
+        Ok(())
     }
 
     pub(crate) fn wait_for_backend(&self, plan: &crate::LaunchPlan) -> Result<(), String> {
@@ -48,6 +113,7 @@ impl BackendState {
         let mut tcp_ready_logged = false;
         let mut ever_tcp_reachable = false;
         let mut startup_heartbeat_state = StartupHeartbeatTracker::new();
+        let mut reported_progress = StartupProgress::default();
 
         loop {
             let (http_status, tcp_reachable) =
@@ -55,10 +121,25 @@ impl BackendState {
             if matches!(http_status, Some(status_code) if (200..400).contains(&status_code)) {
                 return Ok(());
             }
+            if self.startup_cancel_requested.swap(false, Ordering::Relaxed) {
+                append_desktop_log("backend startup wait cancelled");
+                return Err(STARTUP_CANCELLED_REASON.to_string());
+            }
             let wall_now = SystemTime::now();
             let monotonic_now = Instant::now();
 
             let child_pid = self.live_child_pid()?;
+            append_desktop_log_at(
+                DesktopLogLevel::Trace,
+                &format!(
+                    "backend readiness probe: pid={}, path={}, http_status={}, tcp_reachable={}, elapsed_ms={}",
+                    child_pid,
+                    readiness.path,
+                    http_status.map_or_else(|| "none".to_string(), |status| status.to_string()),
+                    tcp_reachable,
+                    start_time.elapsed().as_millis()
+                ),
+            );
 
             if let Some(heartbeat_path) = readiness.startup_heartbeat_path.as_deref() {
                 step_startup_heartbeat(
@@ -69,12 +150,28 @@ impl BackendState {
                     startup_idle_timeout,
                     &mut startup_heartbeat_state,
                 )?;
+                if let Some(updated_at_ms) = startup_heartbeat_state
+                    .last_seen_at
+                    .and_then(|seen_at| seen_at.duration_since(UNIX_EPOCH).ok())
+                    .and_then(|since_epoch| u64::try_from(since_epoch.as_millis()).ok())
+                {
+                    self.apply_lifecycle_event(LifecycleEvent::StartupHeartbeat {
+                        updated_at_ms,
+                        progress: startup_heartbeat_state.progress.clone(),
+                    });
+                }
+                if startup_heartbeat_state.progress != reported_progress {
+                    reported_progress = startup_heartbeat_state.progress.clone();
+                    self.report_startup_progress(&reported_progress);
+                }
             }
 
             if tcp_reachable {
                 ever_tcp_reachable = true;
+                self.apply_lifecycle_event(LifecycleEvent::TcpUp);
                 if !tcp_ready_logged {
-                    append_desktop_log(
+                    append_desktop_log_at(
+                        DesktopLogLevel::Debug,
                         "backend TCP port is reachable but HTTP dashboard is not ready yet; waiting",
                     );
                     tcp_ready_logged = true;
@@ -102,6 +199,33 @@ impl BackendState {
         }
     }
 
+    fn report_startup_progress(&self, progress: &StartupProgress) {
+        append_desktop_log_at(
+            DesktopLogLevel::Debug,
+            &format!(
+                "backend startup progress: phase={}, message={}, percent={}",
+                progress.phase.as_deref().unwrap_or("none"),
+                progress.message.as_deref().unwrap_or("none"),
+                progress
+                    .percent
+                    .map_or_else(|| "none".to_string(), |percent| format!("{percent:.0}"))
+            ),
+        );
+        // The loading page only ever shows the selected instance.
+        let Some(app) = self.app_handle.get() else {
+            return;
+        };
+        if app.state::<BackendRegistry>().selected_id() != self.id {
+            return;
+        }
+        if let Err(error) = app.emit(BACKEND_STARTUP_PROGRESS_EVENT, progress) {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to emit backend startup progress event: {error}"),
+            );
+        }
+    }
+
     fn probe_backend_readiness(
         &self,
         ready_http_path: &str,
@@ -125,6 +249,10 @@ impl BackendState {
             match child.try_wait() {
                 Ok(Some(status)) => {
                     *guard = None;
+                    drop(guard);
+                    self.clear_backend_pidfile(pid);
+                    logging::clear_logged_backend_pid(pid);
+                    self.record_backend_crash(pid, Some(&status));
                     Err(format!(
                         "Backend process exited before becoming reachable: {status}"
                     ))
@@ -150,16 +278,19 @@ impl BackendState {
             .map(|status| status.to_string())
             .unwrap_or_else(|| "none".to_string());
         let startup_heartbeat_age_ms = describe_heartbeat_age(last_startup_heartbeat_at, now);
-        append_desktop_log(&format!(
-            "backend HTTP readiness check timed out after {}ms: backend_url={}, path={}, probe_timeout_ms={}, tcp_reachable={}, last_http_status={}, startup_heartbeat_age_ms={}",
-            timeout.as_millis(),
-            self.backend_url,
-            readiness.path,
-            readiness.probe_timeout_ms,
-            tcp_reachable,
-            last_http_status_text,
-            startup_heartbeat_age_ms
-        ));
+        append_desktop_log_at(
+            DesktopLogLevel::Error,
+            &format!(
+                "backend HTTP readiness check timed out after {}ms: backend_url={}, path={}, probe_timeout_ms={}, tcp_reachable={}, last_http_status={}, startup_heartbeat_age_ms={}",
+                timeout.as_millis(),
+                self.backend_url(),
+                readiness.path,
+                readiness.probe_timeout_ms,
+                tcp_reachable,
+                last_http_status_text,
+                startup_heartbeat_age_ms
+            ),
+        );
     }
 }
 
@@ -169,6 +300,56 @@ struct StartupHeartbeatFile {
     pid: u32,
     state: StartupHeartbeatState,
     updated_at_ms: u64,
+    // Progress is best effort: a value of the wrong type is dropped instead of invalidating
+    // the heartbeat itself.
+    #[serde(default)]
+    phase: Option<serde_json::Value>,
+    #[serde(default)]
+    message: Option<serde_json::Value>,
+    #[serde(default)]
+    percent: Option<serde_json::Value>,
+}
+
+/// Optional progress the backend reports through its startup heartbeat, e.g.
+/// `{ "phase": "plugins", "message": "loading plugins 12/40", "percent": 30 }`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StartupProgress {
+    pub(crate) phase: Option<String>,
+    pub(crate) message: Option<String>,
+    pub(crate) percent: Option<f64>,
+}
+
+impl StartupProgress {
+    fn from_heartbeat(heartbeat: &StartupHeartbeatFile) -> Self {
+        let text = |value: &Option<serde_json::Value>| {
+            value
+                .as_ref()
+                .and_then(serde_json::Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            phase: text(&heartbeat.phase),
+            message: text(&heartbeat.message),
+            percent: heartbeat
+                .percent
+                .as_ref()
+                .and_then(serde_json::Value::as_f64)
+                .filter(|percent| percent.is_finite())
+                .map(|percent| percent.clamp(0.0, 100.0)),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.phase.is_none() && self.message.is_none() && self.percent.is_none()
+    }
+}
+
+struct StartupHeartbeat {
+    updated_at: SystemTime,
+    progress: StartupProgress,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
@@ -178,12 +359,13 @@ enum StartupHeartbeatState {
     Stopping,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 struct StartupHeartbeatTracker {
     last_seen_at: Option<SystemTime>,
     last_progress_at: Option<Instant>,
     consecutive_invalid_reads: u8,
     logged_fresh: bool,
+    progress: StartupProgress,
 }
 
 impl StartupHeartbeatTracker {
@@ -193,19 +375,33 @@ impl StartupHeartbeatTracker {
             last_progress_at: None,
             consecutive_invalid_reads: 0,
             logged_fresh: false,
+            progress: StartupProgress::default(),
         }
     }
 }
 
 const STARTUP_HEARTBEAT_INVALID_READ_THRESHOLD: u8 = 2;
 
-fn read_startup_heartbeat_updated_at(path: &Path, expected_pid: u32) -> Option<SystemTime> {
+fn read_startup_heartbeat(path: &Path, expected_pid: u32) -> Option<StartupHeartbeat> {
     let payload = fs::read_to_string(path).ok()?;
     let heartbeat: StartupHeartbeatFile = serde_json::from_str(&payload).ok()?;
     if heartbeat.pid != expected_pid || heartbeat.state != StartupHeartbeatState::Starting {
         return None;
     }
-    UNIX_EPOCH.checked_add(Duration::from_millis(heartbeat.updated_at_ms))
+    Some(StartupHeartbeat {
+        updated_at: UNIX_EPOCH.checked_add(Duration::from_millis(heartbeat.updated_at_ms))?,
+        progress: StartupProgress::from_heartbeat(&heartbeat),
+    })
+}
+
+/// Whether the backend `expected_pid` has announced through its heartbeat that it is exiting.
+pub(crate) fn startup_heartbeat_reports_stopping(path: &Path, expected_pid: u32) -> bool {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|payload| serde_json::from_str::<StartupHeartbeatFile>(&payload).ok())
+        .is_some_and(|heartbeat| {
+            heartbeat.pid == expected_pid && heartbeat.state == StartupHeartbeatState::Stopping
+        })
 }
 
 fn startup_heartbeat_progress_is_fresh(
@@ -244,7 +440,8 @@ fn step_startup_heartbeat(
     state: &mut StartupHeartbeatTracker,
 ) -> Result<(), String> {
     let previous = state.last_seen_at;
-    let current = read_startup_heartbeat_updated_at(heartbeat_path, child_pid);
+    let heartbeat = read_startup_heartbeat(heartbeat_path, child_pid);
+    let current = heartbeat.as_ref().map(|heartbeat| heartbeat.updated_at);
 
     match (previous, current) {
         (Some(previous), None) => {
@@ -254,9 +451,12 @@ fn step_startup_heartbeat(
             }
 
             let heartbeat_age_ms = describe_heartbeat_age(Some(previous), wall_now);
-            append_desktop_log(&format!(
-                "backend startup heartbeat disappeared or became invalid before HTTP dashboard became ready: last_valid_age_ms={heartbeat_age_ms}"
-            ));
+            append_desktop_log_at(
+                DesktopLogLevel::Error,
+                &format!(
+                    "backend startup heartbeat disappeared or became invalid before HTTP dashboard became ready: last_valid_age_ms={heartbeat_age_ms}"
+                ),
+            );
             Err(
                 "Backend startup heartbeat disappeared or became invalid before HTTP readiness."
                     .to_string(),
@@ -268,6 +468,9 @@ fn step_startup_heartbeat(
         }
         (_, Some(current)) => {
             state.consecutive_invalid_reads = 0;
+            if let Some(heartbeat) = heartbeat {
+                state.progress = heartbeat.progress;
+            }
             let updated_at = match previous {
                 Some(previous) if current <= previous => previous,
                 _ => current,
@@ -287,14 +490,16 @@ fn step_startup_heartbeat(
                 idle_timeout,
             ) {
                 if !state.logged_fresh {
-                    append_desktop_log(
+                    append_desktop_log_at(
+                        DesktopLogLevel::Debug,
                         "backend startup heartbeat is fresh while HTTP dashboard is not ready yet; waiting",
                     );
                     state.logged_fresh = true;
                 }
                 Ok(())
             } else {
-                append_desktop_log(
+                append_desktop_log_at(
+                    DesktopLogLevel::Error,
                     "backend startup heartbeat went stale before HTTP dashboard became ready",
                 );
                 Err(format!(
@@ -342,7 +547,7 @@ mod tests {
         )
         .expect("write heartbeat file");
 
-        assert_eq!(read_startup_heartbeat_updated_at(&heartbeat_path, 42), None);
+        assert!(read_startup_heartbeat(&heartbeat_path, 42).is_none());
     }
 
     #[test]
@@ -355,6 +560,7 @@ mod tests {
             last_progress_at: Some(monotonic_now),
             consecutive_invalid_reads: 0,
             logged_fresh: false,
+            progress: StartupProgress::default(),
         };
 
         let first_result = step_startup_heartbeat(
@@ -395,6 +601,7 @@ mod tests {
             last_progress_at: Some(monotonic_now),
             consecutive_invalid_reads: 0,
             logged_fresh: false,
+            progress: StartupProgress::default(),
         };
 
         let result = step_startup_heartbeat(
@@ -418,6 +625,56 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn startup_heartbeat_reads_optional_progress() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let heartbeat_path = temp_dir.path().join("startup-heartbeat.json");
+        std::fs::write(
+            &heartbeat_path,
+            r#"{"pid":42,"state":"starting","updated_at_ms":5000,"phase":"plugins","message":" loading plugins 12/40 ","percent":130}"#,
+        )
+        .expect("write heartbeat file");
+
+        let heartbeat = read_startup_heartbeat(&heartbeat_path, 42).expect("heartbeat");
+        assert_eq!(
+            heartbeat.progress,
+            StartupProgress {
+                phase: Some("plugins".to_string()),
+                message: Some("loading plugins 12/40".to_string()),
+                percent: Some(100.0),
+            }
+        );
+
+        std::fs::write(
+            &heartbeat_path,
+            r#"{"pid":42,"state":"starting","updated_at_ms":6000,"message":""}"#,
+        )
+        .expect("write heartbeat file");
+        let heartbeat = read_startup_heartbeat(&heartbeat_path, 42).expect("heartbeat");
+        assert!(heartbeat.progress.is_empty());
+    }
+
+    #[test]
+    fn startup_heartbeat_drops_malformed_progress_fields() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let heartbeat_path = temp_dir.path().join("startup-heartbeat.json");
+        std::fs::write(
+            &heartbeat_path,
+            r#"{"pid":42,"state":"starting","updated_at_ms":5000,"phase":3,"message":"loading","percent":"30"}"#,
+        )
+        .expect("write heartbeat file");
+
+        let heartbeat = read_startup_heartbeat(&heartbeat_path, 42).expect("heartbeat");
+        assert_eq!(
+            heartbeat.progress,
+            StartupProgress {
+                phase: None,
+                message: Some("loading".to_string()),
+                percent: None,
+            }
+        );
+    }
+
     #[test]
     fn startup_heartbeat_file_rejects_unknown_fields() {
         assert!(serde_json::from_str::<StartupHeartbeatFile>(
@@ -427,7 +684,7 @@ mod tests {
     }
 
     #[test]
-    fn read_startup_heartbeat_updated_at_handles_large_timestamp_without_panic() {
+    fn read_startup_heartbeat_handles_large_timestamp_without_panic() {
         let temp_dir = TempDir::new().expect("create temp dir");
         let heartbeat_path = temp_dir.path().join("startup-heartbeat.json");
         std::fs::write(
@@ -440,7 +697,7 @@ mod tests {
         .expect("write heartbeat file");
 
         assert_eq!(
-            read_startup_heartbeat_updated_at(&heartbeat_path, 42),
+            read_startup_heartbeat(&heartbeat_path, 42).map(|heartbeat| heartbeat.updated_at),
             UNIX_EPOCH.checked_add(Duration::from_millis(u64::MAX))
         );
     }
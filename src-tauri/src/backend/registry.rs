@@ -0,0 +1,265 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+use crate::{
+    append_desktop_log_at, backend,
+    backend::{lifecycle::BackendLifecycleState, profile::BackendProfile},
+    logging::{self, DesktopLogLevel},
+    runtime_paths, BackendRegistry, BackendState,
+};
+
+impl BackendRegistry {
+    pub(crate) fn new(initial: BackendState) -> Self {
+        let selected_id = initial.id.clone();
+        let exit_state = Arc::clone(&initial.exit_state);
+        let mut instances = BTreeMap::new();
+        instances.insert(selected_id.clone(), Arc::new(initial));
+        Self {
+            instances: Mutex::new(instances),
+            selected_id: Mutex::new(selected_id),
+            exit_state,
+        }
+    }
+
+    fn lock_instances(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Arc<BackendState>>> {
+        match self.instances.lock() {
+            Ok(guard) => guard,
+            Err(error) => {
+                append_desktop_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("backend registry lock poisoned: {error}"),
+                );
+                error.into_inner()
+            }
+        }
+    }
+
+    pub(crate) fn selected_id(&self) -> String {
+        match self.selected_id.lock() {
+            Ok(guard) => guard.clone(),
+            Err(error) => error.into_inner().clone(),
+        }
+    }
+
+    /// The instance shown in the main window. `remove` never drops the selected entry; should
+    /// it go missing anyway, the default instance is selected instead.
+    pub(crate) fn selected(&self) -> Arc<BackendState> {
+        let selected_id = self.selected_id();
+        if let Some(instance) = self.get(&selected_id) {
+            return instance;
+        }
+        append_desktop_log_at(
+            DesktopLogLevel::Warn,
+            &format!("selected backend instance {selected_id} is missing, falling back to default"),
+        );
+        let instance = self.get_or_insert(None);
+        match self.selected_id.lock() {
+            Ok(mut guard) => *guard = instance.id.clone(),
+            Err(error) => *error.into_inner() = instance.id.clone(),
+        }
+        instance
+    }
+
+    pub(crate) fn get(&self, id: &str) -> Option<Arc<BackendState>> {
+        self.lock_instances().get(id).cloned()
+    }
+
+    pub(crate) fn instances(&self) -> Vec<Arc<BackendState>> {
+        self.lock_instances().values().cloned().collect()
+    }
+
+    /// Returns the instance for `profile`, registering a fresh one that shares the shell-wide
+    /// exit state when it is not running yet.
+    pub(crate) fn get_or_insert(&self, profile: Option<BackendProfile>) -> Arc<BackendState> {
+        let id = profile
+            .as_ref()
+            .map(|profile| profile.id.clone())
+            .unwrap_or_else(|| backend::profile::DEFAULT_PROFILE_ID.to_string());
+        let exit_state = Arc::clone(&self.exit_state);
+        let mut instances = self.lock_instances();
+        Arc::clone(
+            instances
+                .entry(id)
+                .or_insert_with(|| Arc::new(BackendState::for_profile(profile, exit_state))),
+        )
+    }
+
+    /// Running instances whose backend resolves to `root_dir`. Two backends on one root would
+    /// share data, pidfile and startup heartbeat, so a second one must not be started there.
+    pub(crate) fn running_instances_with_root(
+        &self,
+        root_dir: Option<&Path>,
+    ) -> Vec<Arc<BackendState>> {
+        self.instances()
+            .into_iter()
+            .filter(|instance| instance.current_child_pid().is_some())
+            .filter(|instance| {
+                backend::profile::resolved_profile_root_dir(instance.profile.as_ref()).as_deref()
+                    == root_dir
+            })
+            .collect()
+    }
+
+    /// An instance other than `id`, not stopped, whose backend answers on the same origin as
+    /// `backend_url`; reaching that URL would reach the other instance's backend.
+    pub(crate) fn instance_using_backend_url(
+        &self,
+        id: &str,
+        backend_url: &str,
+    ) -> Option<Arc<BackendState>> {
+        self.instances().into_iter().find(|instance| {
+            instance.id != id
+                && instance.lifecycle_snapshot().state != BackendLifecycleState::Stopped
+                && same_backend_origin(&instance.backend_url(), backend_url)
+        })
+    }
+
+    pub(crate) fn select(&self, id: &str) -> Option<Arc<BackendState>> {
+        let instance = self.get(id)?;
+        match self.selected_id.lock() {
+            Ok(mut guard) => *guard = id.to_string(),
+            Err(error) => *error.into_inner() = id.to_string(),
+        }
+
+        logging::set_logged_backend_pid(instance.current_child_pid());
+        let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+        if let Err(error) = backend::profile::write_active_profile_id(
+            instance.profile.as_ref().map(|profile| profile.id.as_str()),
+            packaged_root_dir.as_deref(),
+        ) {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to persist active backend profile: {error}"),
+            );
+        }
+        Some(instance)
+    }
+
+    /// Drops a stopped instance from the registry. The selected instance is kept.
+    pub(crate) fn remove(&self, id: &str) -> Option<Arc<BackendState>> {
+        if id == self.selected_id() {
+            return None;
+        }
+        let removed = self.lock_instances().remove(id)?;
+        backend::dashboard_port::release_dashboard_port_claims(id);
+        Some(removed)
+    }
+
+    pub(crate) fn mark_all_quitting(&self) {
+        // Instances share one exit state, so marking any of them is enough.
+        self.selected().mark_quitting();
+    }
+
+    /// Stops every managed backend concurrently and reports each failure through `on_error`.
+    pub(crate) fn stop_all<F>(&self, on_error: F)
+    where
+        F: Fn(&str, &str) + Sync,
+    {
+        let instances = self.instances();
+        thread::scope(|scope| {
+            for instance in &instances {
+                let on_error = &on_error;
+                scope.spawn(move || {
+                    if let Err(error) = instance.stop_backend() {
+                        on_error(&instance.id, &error);
+                    }
+                });
+            }
+        });
+    }
+}
+
+fn same_backend_origin(left: &str, right: &str) -> bool {
+    match (Url::parse(left), Url::parse(right)) {
+        (Ok(left), Ok(right)) => left.origin() == right.origin(),
+        _ => left.trim() == right.trim(),
+    }
+}
+
+impl BackendState {
+    /// Whether this instance is the one shown in the main window.
+    pub(crate) fn is_selected(&self, app: &AppHandle) -> bool {
+        app.state::<BackendRegistry>().selected_id() == self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(id: &str) -> BackendProfile {
+        BackendProfile {
+            id: id.to_string(),
+            ..BackendProfile::default()
+        }
+    }
+
+    #[test]
+    fn get_or_insert_reuses_instances_and_shares_exit_state() {
+        let registry = BackendRegistry::new(BackendState::for_profile(
+            None,
+            Arc::new(Mutex::new(Default::default())),
+        ));
+        let staging = registry.get_or_insert(Some(profile("staging")));
+
+        assert!(Arc::ptr_eq(
+            &staging,
+            &registry.get_or_insert(Some(profile("staging")))
+        ));
+        assert!(Arc::ptr_eq(
+            &staging.exit_state,
+            &registry.selected().exit_state
+        ));
+        assert_eq!(registry.instances().len(), 2);
+    }
+
+    #[test]
+    fn remove_keeps_the_selected_instance() {
+        let registry = BackendRegistry::new(BackendState::for_profile(
+            None,
+            Arc::new(Mutex::new(Default::default())),
+        ));
+        registry.get_or_insert(Some(profile("staging")));
+
+        assert!(registry
+            .remove(backend::profile::DEFAULT_PROFILE_ID)
+            .is_none());
+        assert!(registry.remove("staging").is_some());
+        assert_eq!(registry.selected().id, backend::profile::DEFAULT_PROFILE_ID);
+    }
+
+    #[test]
+    fn same_backend_origin_ignores_paths_and_default_ports() {
+        assert!(same_backend_origin(
+            "http://127.0.0.1:6185/",
+            "http://127.0.0.1:6185/dashboard"
+        ));
+        assert!(same_backend_origin(
+            "http://localhost/",
+            "http://localhost:80"
+        ));
+        assert!(!same_backend_origin(
+            "http://127.0.0.1:6185/",
+            "http://127.0.0.1:6186/"
+        ));
+    }
+
+    #[test]
+    fn selected_falls_back_to_the_default_instance() {
+        let registry = BackendRegistry::new(BackendState::for_profile(
+            Some(profile("staging")),
+            Arc::new(Mutex::new(Default::default())),
+        ));
+        registry.lock_instances().clear();
+
+        assert_eq!(registry.selected().id, backend::profile::DEFAULT_PROFILE_ID);
+        assert_eq!(registry.selected_id(), backend::profile::DEFAULT_PROFILE_ID);
+    }
+}
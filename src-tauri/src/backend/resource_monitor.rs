@@ -0,0 +1,292 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    append_desktop_log, append_desktop_log_at, backend,
+    backend::{log_stream::now_ms, resource_watchdog::ResourceWatchdogTracker},
+    logging::DesktopLogLevel,
+    BackendRegistry, BackendState, BACKEND_METRICS_EVENT, DEFAULT_BACKEND_METRICS_INTERVAL_MS,
+};
+
+/// `USER_HZ`, the unit of the CPU times in `/proc/<pid>/stat`; 100 on every mainstream kernel.
+const CLOCK_TICKS_PER_SECOND: f64 = 100.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendMetrics {
+    pub(crate) instance_id: String,
+    pub(crate) pid: u32,
+    pub(crate) sampled_at_ms: u64,
+    pub(crate) uptime_ms: Option<u64>,
+    /// Share of one CPU core used by the process tree since the previous sample; `None` for
+    /// the first sample of a process.
+    pub(crate) cpu_percent: Option<f64>,
+    pub(crate) rss_bytes: u64,
+    pub(crate) thread_count: u64,
+    pub(crate) open_fds: u64,
+    pub(crate) process_count: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CpuSample {
+    pid: u32,
+    cpu_ticks: u64,
+    at: Instant,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ResourceMonitorState {
+    previous: Option<CpuSample>,
+    latest: Option<BackendMetrics>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ProcessTreeUsage {
+    pub(crate) process_count: usize,
+    pub(crate) cpu_ticks: u64,
+    pub(crate) rss_bytes: u64,
+    pub(crate) threads: u64,
+    pub(crate) open_fds: u64,
+}
+
+/// Resident set size from `/proc/<pid>/status`, in bytes.
+pub(crate) fn parse_status_rss_bytes(raw: &str) -> Option<u64> {
+    let value = raw
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(value * 1024)
+}
+
+pub(crate) fn cpu_percent(
+    previous_ticks: u64,
+    previous_at: Instant,
+    ticks: u64,
+    at: Instant,
+) -> Option<f64> {
+    let elapsed = at.checked_duration_since(previous_at)?.as_secs_f64();
+    if elapsed <= 0.0 {
+        return None;
+    }
+    // Descendants that exited take their ticks with them; report 0 rather than a negative.
+    let used = ticks.saturating_sub(previous_ticks) as f64 / CLOCK_TICKS_PER_SECOND;
+    Some((used / elapsed * 1000.0).round() / 10.0)
+}
+
+#[cfg(target_os = "linux")]
+fn read_process_tree_usage(root_pid: u32) -> Result<ProcessTreeUsage, String> {
+    use std::{fs, path::Path};
+
+    use crate::process_control::{collect_process_tree, read_process_stats};
+
+    let proc_dir = Path::new("/proc");
+    if !proc_dir.join(root_pid.to_string()).exists() {
+        return Err(format!("Backend process {root_pid} is not running."));
+    }
+    let stats = read_process_stats()?;
+
+    let mut usage = ProcessTreeUsage::default();
+    for pid in collect_process_tree(root_pid, &stats) {
+        // Processes can exit between listing /proc and reading their files.
+        let Some(stat) = stats.iter().find(|stat| stat.pid == pid) else {
+            continue;
+        };
+        let process_dir = proc_dir.join(pid.to_string());
+        usage.process_count += 1;
+        usage.cpu_ticks += stat.cpu_ticks;
+        usage.threads += stat.threads;
+        usage.rss_bytes += fs::read_to_string(process_dir.join("status"))
+            .ok()
+            .and_then(|raw| parse_status_rss_bytes(&raw))
+            .unwrap_or(0);
+        usage.open_fds += fs::read_dir(process_dir.join("fd"))
+            .map(|entries| entries.count() as u64)
+            .unwrap_or(0);
+    }
+    if usage.process_count == 0 {
+        return Err(format!("Backend process {root_pid} is not running."));
+    }
+    Ok(usage)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_process_tree_usage(_root_pid: u32) -> Result<ProcessTreeUsage, String> {
+    Err("Backend resource metrics are only supported on Linux.".to_string())
+}
+
+impl BackendState {
+    /// Samples the backend process tree and remembers the result for CPU deltas and
+    /// `latest_backend_metrics`.
+    pub(crate) fn sample_backend_metrics(&self) -> Result<BackendMetrics, String> {
+        let pid = self
+            .current_child_pid()
+            .ok_or_else(|| "Backend process is not running.".to_string())?;
+        let usage = read_process_tree_usage(pid)?;
+        let at = Instant::now();
+        let uptime_ms = self
+            .spawn_info_for_pid(pid)
+            .map(|info| info.started_at.elapsed().as_millis() as u64);
+
+        let mut monitor = match self.resource_monitor.lock() {
+            Ok(guard) => guard,
+            Err(error) => error.into_inner(),
+        };
+        let cpu_percent = monitor
+            .previous
+            .filter(|previous| previous.pid == pid)
+            .and_then(|previous| cpu_percent(previous.cpu_ticks, previous.at, usage.cpu_ticks, at));
+        let metrics = BackendMetrics {
+            instance_id: self.id.clone(),
+            pid,
+            sampled_at_ms: now_ms(),
+            uptime_ms,
+            cpu_percent,
+            rss_bytes: usage.rss_bytes,
+            thread_count: usage.threads,
+            open_fds: usage.open_fds,
+            process_count: usage.process_count,
+        };
+        monitor.previous = Some(CpuSample {
+            pid,
+            cpu_ticks: usage.cpu_ticks,
+            at,
+        });
+        monitor.latest = Some(metrics.clone());
+        Ok(metrics)
+    }
+
+    pub(crate) fn latest_backend_metrics(&self) -> Option<BackendMetrics> {
+        match self.resource_monitor.lock() {
+            Ok(guard) => guard.latest.clone(),
+            Err(error) => error.into_inner().latest.clone(),
+        }
+    }
+
+    /// Starts a sampler thread for the child that was just spawned; it ends on its own once
+    /// that child is gone or replaced. The watchdog shares the sampler, which keeps running
+    /// without metrics events while limits are set; sampling reads `/proc`, so neither works
+    /// outside Linux.
+    pub(crate) fn start_resource_monitor(&self, app: &AppHandle, child_pid: u32) {
+        let limits = self.resource_limits();
+        if !cfg!(target_os = "linux") {
+            if limits.is_some() {
+                append_desktop_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!(
+                        "backend resource watchdog configured for instance={} but inactive: resource sampling is only supported on Linux",
+                        self.id
+                    ),
+                );
+            }
+            return;
+        }
+        let metrics_interval = backend::runtime::backend_metrics_interval(append_desktop_log);
+        let emit_metrics = metrics_interval.is_some();
+        let interval = match (metrics_interval, limits.is_some()) {
+            (Some(interval), _) => interval,
+            (None, true) => Duration::from_millis(DEFAULT_BACKEND_METRICS_INTERVAL_MS),
+            (None, false) => return,
+        };
+        match self.resource_monitor.lock() {
+            Ok(mut guard) => *guard = ResourceMonitorState::default(),
+            Err(error) => *error.into_inner() = ResourceMonitorState::default(),
+        }
+
+        if let Some(limits) = limits.as_ref() {
+            append_desktop_log(&format!(
+                "backend resource watchdog enabled for instance={}: {limits:?}",
+                self.id
+            ));
+        }
+
+        let app_handle = app.clone();
+        let instance_id = self.id.clone();
+        let mut watchdog = ResourceWatchdogTracker::default();
+        let spawn_result = thread::Builder::new()
+            .name(format!("backend-metrics-{instance_id}"))
+            .spawn(move || loop {
+                thread::sleep(interval);
+                let Some(state) = app_handle.state::<BackendRegistry>().get(&instance_id) else {
+                    return;
+                };
+                if state.current_child_pid() != Some(child_pid) {
+                    return;
+                }
+                match state.sample_backend_metrics() {
+                    Ok(metrics) => {
+                        if emit_metrics {
+                            if let Err(error) = app_handle.emit(BACKEND_METRICS_EVENT, &metrics) {
+                                append_desktop_log_at(
+                                    DesktopLogLevel::Warn,
+                                    &format!("failed to emit backend metrics event: {error}"),
+                                );
+                            }
+                        }
+                        if let Some(limits) = limits.as_ref() {
+                            state.enforce_resource_limits(
+                                &app_handle,
+                                limits,
+                                &mut watchdog,
+                                &metrics,
+                            );
+                        }
+                    }
+                    Err(error) => {
+                        append_desktop_log_at(
+                            DesktopLogLevel::Warn,
+                            &format!(
+                                "backend resource sampling stopped for pid={child_pid}: {error}"
+                            ),
+                        );
+                        return;
+                    }
+                }
+            });
+        if let Err(error) = spawn_result {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to spawn backend resource monitor: {error}"),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_rss_is_reported_in_bytes() {
+        let status = "Name:\tpython3\nVmPeak:\t  9000 kB\nVmRSS:\t    2048 kB\nThreads:\t7\n";
+        assert_eq!(parse_status_rss_bytes(status), Some(2048 * 1024));
+        assert_eq!(parse_status_rss_bytes("Name:\tkthreadd\n"), None);
+    }
+
+    #[test]
+    fn cpu_percent_uses_tick_delta_over_wall_time() {
+        let start = Instant::now();
+        let later = start + Duration::from_secs(2);
+        assert_eq!(cpu_percent(100, start, 250, later), Some(75.0));
+        assert_eq!(cpu_percent(300, start, 250, later), Some(0.0));
+        assert_eq!(cpu_percent(100, later, 250, start), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn current_process_tree_usage_is_readable_from_proc() {
+        let usage = read_process_tree_usage(std::process::id()).expect("read /proc");
+        assert!(usage.process_count >= 1);
+        assert!(usage.rss_bytes > 0);
+        assert!(usage.threads >= 1);
+        assert!(usage.open_fds > 0);
+    }
+}
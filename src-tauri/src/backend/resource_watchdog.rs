@@ -0,0 +1,261 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::{
+    append_restart_log_at, backend::resource_monitor::BackendMetrics, desktop_state,
+    logging::DesktopLogLevel, restart_backend_flow, runtime_paths, shell_locale, BackendState,
+    BACKEND_WATCHDOG_EVENT, DEFAULT_SHELL_LOCALE, RESOURCE_WATCHDOG_STATE_FIELD,
+};
+
+const DEFAULT_RSS_GRACE_SECS: u64 = 5 * 60;
+const DEFAULT_CPU_GRACE_SECS: u64 = 10 * 60;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Soft limits from the `resourceWatchdog` object of an instance's `desktop_state.json`, e.g.
+/// `{ "enabled": true, "maxRssMb": 2048, "rssGraceSeconds": 300, "maxCpuPercent": 100,
+/// "cpuGraceSeconds": 600 }`. CPU is measured against one core, as in `BackendMetrics`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ResourceLimits {
+    pub(crate) max_rss_bytes: Option<u64>,
+    pub(crate) rss_grace: Duration,
+    pub(crate) max_cpu_percent: Option<f64>,
+    pub(crate) cpu_grace: Duration,
+}
+
+/// `None` unless the watchdog is explicitly enabled with at least one limit.
+pub(crate) fn resolve_resource_limits(value: Option<&Value>) -> Option<ResourceLimits> {
+    let config = value?.as_object()?;
+    if !config
+        .get("enabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+    {
+        return None;
+    }
+    let seconds = |key: &str, fallback: u64| {
+        Duration::from_secs(config.get(key).and_then(Value::as_u64).unwrap_or(fallback))
+    };
+    let limits = ResourceLimits {
+        max_rss_bytes: config
+            .get("maxRssMb")
+            .and_then(Value::as_u64)
+            .filter(|mb| *mb > 0)
+            .map(|mb| mb.saturating_mul(BYTES_PER_MB)),
+        rss_grace: seconds("rssGraceSeconds", DEFAULT_RSS_GRACE_SECS),
+        max_cpu_percent: config
+            .get("maxCpuPercent")
+            .and_then(Value::as_f64)
+            .filter(|percent| *percent > 0.0),
+        cpu_grace: seconds("cpuGraceSeconds", DEFAULT_CPU_GRACE_SECS),
+    };
+    (limits.max_rss_bytes.is_some() || limits.max_cpu_percent.is_some()).then_some(limits)
+}
+
+/// Tracks how long each limit has been exceeded without interruption.
+#[derive(Debug, Default)]
+pub(crate) struct ResourceWatchdogTracker {
+    rss_over_since: Option<Instant>,
+    cpu_over_since: Option<Instant>,
+}
+
+impl ResourceWatchdogTracker {
+    /// Returns the restart reason once a limit has been exceeded for its whole grace period,
+    /// and starts over afterwards. Samples without a CPU reading leave the CPU streak as is.
+    pub(crate) fn observe(
+        &mut self,
+        limits: &ResourceLimits,
+        rss_bytes: u64,
+        cpu_percent: Option<f64>,
+        now: Instant,
+    ) -> Option<String> {
+        if let Some(max_rss_bytes) = limits.max_rss_bytes {
+            if exceeded_for(
+                &mut self.rss_over_since,
+                rss_bytes > max_rss_bytes,
+                limits.rss_grace,
+                now,
+            ) {
+                *self = Self::default();
+                return Some(format!(
+                    "backend memory stayed above {} MB for {}s (rss={} MB)",
+                    max_rss_bytes / BYTES_PER_MB,
+                    limits.rss_grace.as_secs(),
+                    rss_bytes / BYTES_PER_MB
+                ));
+            }
+        }
+        if let (Some(max_cpu_percent), Some(cpu_percent)) = (limits.max_cpu_percent, cpu_percent) {
+            if exceeded_for(
+                &mut self.cpu_over_since,
+                cpu_percent >= max_cpu_percent,
+                limits.cpu_grace,
+                now,
+            ) {
+                *self = Self::default();
+                return Some(format!(
+                    "backend CPU stayed at or above {max_cpu_percent}% for {}s (cpu={cpu_percent}%)",
+                    limits.cpu_grace.as_secs()
+                ));
+            }
+        }
+        None
+    }
+}
+
+fn exceeded_for(
+    over_since: &mut Option<Instant>,
+    over: bool,
+    grace: Duration,
+    now: Instant,
+) -> bool {
+    if !over {
+        *over_since = None;
+        return false;
+    }
+    let since = *over_since.get_or_insert(now);
+    now.saturating_duration_since(since) >= grace
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackendWatchdogPayload<'a> {
+    instance_id: &'a str,
+    reason: &'a str,
+}
+
+impl BackendState {
+    pub(crate) fn resource_limits(&self) -> Option<ResourceLimits> {
+        let state_path = self.desktop_state_path()?;
+        resolve_resource_limits(
+            desktop_state::read_desktop_state_field_at(&state_path, RESOURCE_WATCHDOG_STATE_FIELD)
+                .as_ref(),
+        )
+    }
+
+    /// Feeds one sample to the watchdog and restarts the backend through the regular restart
+    /// flow once a limit has been exceeded for its grace period.
+    pub(crate) fn enforce_resource_limits(
+        &self,
+        app: &AppHandle,
+        limits: &ResourceLimits,
+        tracker: &mut ResourceWatchdogTracker,
+        metrics: &BackendMetrics,
+    ) {
+        let Some(reason) = tracker.observe(
+            limits,
+            metrics.rss_bytes,
+            metrics.cpu_percent,
+            Instant::now(),
+        ) else {
+            return;
+        };
+        if restart_backend_flow::is_backend_action_in_progress(self) {
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("resource watchdog restart skipped, backend action in progress: {reason}"),
+            );
+            return;
+        }
+
+        append_restart_log_at(
+            DesktopLogLevel::Warn,
+            &format!(
+                "resource watchdog restarting backend instance={} pid={}: {reason}",
+                self.id, metrics.pid
+            ),
+        );
+        if let Err(error) = app.emit(
+            BACKEND_WATCHDOG_EVENT,
+            BackendWatchdogPayload {
+                instance_id: &self.id,
+                reason: &reason,
+            },
+        ) {
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to emit backend watchdog event: {error}"),
+            );
+        }
+        let shell_texts = shell_locale::shell_texts_for_locale(shell_locale::resolve_shell_locale(
+            DEFAULT_SHELL_LOCALE,
+            runtime_paths::default_packaged_root_dir(),
+        ));
+        if let Err(error) = app
+            .notification()
+            .builder()
+            .title(shell_texts.watchdog_notification_title)
+            .body(format!("[{}] {reason}", self.id))
+            .show()
+        {
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to show watchdog notification: {error}"),
+            );
+        }
+        if let Err(error) =
+            restart_backend_flow::restart_backend_instance(app, Some(&self.id), None)
+        {
+            append_restart_log_at(
+                DesktopLogLevel::Error,
+                &format!("resource watchdog restart failed: {error}"),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn limits_are_off_unless_enabled_with_a_limit() {
+        assert_eq!(resolve_resource_limits(None), None);
+        assert_eq!(
+            resolve_resource_limits(Some(&json!({ "maxRssMb": 2048 }))),
+            None
+        );
+        assert_eq!(
+            resolve_resource_limits(Some(&json!({ "enabled": true }))),
+            None
+        );
+
+        let limits = resolve_resource_limits(Some(&json!({ "enabled": true, "maxRssMb": 2048 })))
+            .expect("limits");
+        assert_eq!(limits.max_rss_bytes, Some(2048 * BYTES_PER_MB));
+        assert_eq!(
+            limits.rss_grace,
+            Duration::from_secs(DEFAULT_RSS_GRACE_SECS)
+        );
+        assert_eq!(limits.max_cpu_percent, None);
+    }
+
+    #[test]
+    fn tracker_fires_only_after_an_uninterrupted_grace_period() {
+        let limits = ResourceLimits {
+            max_rss_bytes: Some(100),
+            rss_grace: Duration::from_secs(60),
+            max_cpu_percent: Some(90.0),
+            cpu_grace: Duration::from_secs(30),
+        };
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut tracker = ResourceWatchdogTracker::default();
+
+        assert_eq!(tracker.observe(&limits, 200, None, at(0)), None);
+        assert_eq!(tracker.observe(&limits, 50, None, at(30)), None);
+        assert_eq!(tracker.observe(&limits, 200, None, at(40)), None);
+        let reason = tracker.observe(&limits, 200, None, at(100));
+        assert!(reason.is_some_and(|reason| reason.contains("memory")));
+        assert_eq!(tracker.observe(&limits, 200, None, at(101)), None);
+
+        assert_eq!(tracker.observe(&limits, 0, Some(95.0), at(200)), None);
+        assert_eq!(tracker.observe(&limits, 0, None, at(215)), None);
+        let reason = tracker.observe(&limits, 0, Some(100.0), at(230));
+        assert!(reason.is_some_and(|reason| reason.contains("CPU")));
+    }
+}
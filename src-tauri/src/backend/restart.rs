@@ -7,8 +7,9 @@ use std::{
 use tauri::AppHandle;
 
 use crate::{
-    append_desktop_log, append_restart_log, backend, AtomicFlagGuard, BackendBridgeState,
-    BackendState, LaunchPlan, GRACEFUL_RESTART_POLL_INTERVAL_MS,
+    append_desktop_log, append_desktop_log_at, append_restart_log, append_restart_log_at, backend,
+    backend::lifecycle::LifecycleEvent, logging::DesktopLogLevel, AtomicFlagGuard,
+    BackendBridgeState, BackendState, LaunchPlan, GRACEFUL_RESTART_POLL_INTERVAL_MS,
     GRACEFUL_RESTART_REQUEST_TIMEOUT_MS,
 };
 
@@ -25,13 +26,14 @@ impl BackendState {
         Some(token.to_string())
     }
 
-    fn get_restart_auth_token(&self) -> Option<String> {
+    pub(crate) fn get_restart_auth_token(&self) -> Option<String> {
         match self.restart_auth_token.lock() {
             Ok(guard) => guard.clone(),
             Err(error) => {
-                append_restart_log(&format!(
-                    "restart auth token lock poisoned when reading: {error}"
-                ));
+                append_restart_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("restart auth token lock poisoned when reading: {error}"),
+                );
                 None
             }
         }
@@ -43,9 +45,10 @@ impl BackendState {
             Ok(mut guard) => {
                 *guard = normalized;
             }
-            Err(error) => append_restart_log(&format!(
-                "restart auth token lock poisoned when writing: {error}"
-            )),
+            Err(error) => append_restart_log_at(
+                DesktopLogLevel::Error,
+                &format!("restart auth token lock poisoned when writing: {error}"),
+            ),
         }
     }
 
@@ -60,9 +63,10 @@ impl BackendState {
         match status_code {
             Some(code) if (200..300).contains(&code) => true,
             Some(code) => {
-                append_restart_log(&format!(
-                    "graceful restart request rejected with HTTP status {code}"
-                ));
+                append_restart_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!("graceful restart request rejected with HTTP status {code}"),
+                );
                 false
             }
             None => {
@@ -115,7 +119,7 @@ impl BackendState {
             .lock()
             .map_err(|_| "Backend process lock poisoned.".to_string())?
             .is_some();
-        if has_managed_child {
+        if has_managed_child || self.adopted_backend_pid().is_some() {
             return self.stop_backend();
         }
 
@@ -130,7 +134,7 @@ impl BackendState {
     fn has_managed_child(&self) -> Result<bool, String> {
         self.child
             .lock()
-            .map(|guard| guard.is_some())
+            .map(|guard| guard.is_some() || self.adopted_backend_pid().is_some())
             .map_err(|error| {
                 let message = format!(
                     "backend child lock poisoned while resolving restart strategy: {error}"
@@ -205,16 +209,20 @@ impl BackendState {
                 backend::restart_strategy::RestartStrategy::ManagedWithGracefulFallback,
                 backend::restart_strategy::GracefulRestartOutcome::WaitFailed(error),
             ) => {
-                append_restart_log(&format!(
-                    "graceful restart did not complete, fallback to managed restart: {error}"
-                ));
+                append_restart_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!(
+                        "graceful restart did not complete, fallback to managed restart: {error}"
+                    ),
+                );
                 self.stop_backend_for_restart_flow()
             }
             (
                 backend::restart_strategy::RestartStrategy::ManagedWithGracefulFallback,
                 backend::restart_strategy::GracefulRestartOutcome::RequestRejected,
             ) => {
-                append_restart_log(
+                append_restart_log_at(
+                    DesktopLogLevel::Warn,
                     "graceful restart request was rejected, fallback to managed restart",
                 );
                 self.stop_backend_for_restart_flow()
@@ -223,9 +231,12 @@ impl BackendState {
                 backend::restart_strategy::RestartStrategy::UnmanagedWithGracefulProbe,
                 backend::restart_strategy::GracefulRestartOutcome::WaitFailed(error),
             ) => {
-                append_restart_log(&format!(
-                    "graceful restart did not complete for unmanaged backend, bootstrap managed restart: {error}"
-                ));
+                append_restart_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!(
+                        "graceful restart did not complete for unmanaged backend, bootstrap managed restart: {error}"
+                    ),
+                );
                 self.stop_backend_for_restart_flow()
             }
             (
@@ -248,8 +259,7 @@ impl BackendState {
         plan: &LaunchPlan,
     ) -> Result<(), String> {
         let _spawn_guard = AtomicFlagGuard::set(&self.is_spawning);
-        self.start_backend_process(app, plan)?;
-        self.wait_for_backend(plan)
+        self.launch_supervised_backend(app, plan)
     }
 
     pub(crate) fn restart_backend(
@@ -259,8 +269,23 @@ impl BackendState {
     ) -> Result<(), String> {
         append_restart_log("backend restart requested");
 
+        self.attach_app_handle(app);
         let _restart_guard = AtomicFlagGuard::try_set(&self.is_restarting)
             .ok_or_else(|| "Backend action already in progress.".to_string())?;
+        self.apply_lifecycle_event(LifecycleEvent::RestartBegan);
+        let result = self.restart_backend_with_guard(app, auth_token);
+        self.apply_lifecycle_event(match &result {
+            Ok(()) => LifecycleEvent::Ready,
+            Err(error) => LifecycleEvent::RestartFailed(error.clone()),
+        });
+        result
+    }
+
+    fn restart_backend_with_guard(
+        &self,
+        app: &AppHandle,
+        auth_token: Option<&str>,
+    ) -> Result<(), String> {
         let plan = self.resolve_launch_plan(app)?;
         let has_managed_child = self.has_managed_child()?;
         let strategy = self.restart_strategy(&plan, has_managed_child);
@@ -294,12 +319,16 @@ impl BackendState {
             .lock()
             .map(|guard| guard.is_some())
             .unwrap_or_else(|error| {
-                append_desktop_log(&format!(
-                    "backend bridge: child process mutex poisoned in bridge_state: {error}"
-                ));
+                append_desktop_log_at(
+                    DesktopLogLevel::Error,
+                    &format!(
+                        "backend bridge: child process mutex poisoned in bridge_state: {error}"
+                    ),
+                );
                 false
             });
-        let can_manage = has_managed_child || self.resolve_launch_plan(app).is_ok();
+        let adopted = self.adopted_backend_pid().is_some();
+        let can_manage = has_managed_child || adopted || self.resolve_launch_plan(app).is_ok();
         BackendBridgeState {
             running: self.ping_backend(backend::runtime::bridge_backend_ping_timeout_ms(
                 append_desktop_log,
@@ -307,6 +336,10 @@ impl BackendState {
             spawning: self.is_spawning.load(Ordering::Relaxed),
             restarting: self.is_restarting.load(Ordering::Relaxed),
             can_manage,
+            adopted,
+            logs_available: !adopted,
+            crash_loop_error: self.crash_loop_error(),
+            lifecycle: self.lifecycle_snapshot(),
         }
     }
 }
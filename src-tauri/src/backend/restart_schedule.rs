@@ -0,0 +1,320 @@
+use std::thread;
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    append_restart_log, append_restart_log_at, desktop_state, logging::DesktopLogLevel,
+    restart_backend_flow, BackendRegistry, BackendState, BACKEND_RESTART_SCHEDULE_POLL_INTERVAL,
+    RESTART_SCHEDULE_STATE_FIELD,
+};
+
+const DAILY_TIME_FORMAT: &str = "%H:%M";
+
+/// The `restartSchedule` object of an instance's `desktop_state.json`, e.g.
+/// `{ "dailyAt": ["04:30"], "everyHours": 12 }`. Both rules may be combined; the earliest
+/// upcoming one wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct RestartSchedule {
+    pub(crate) daily_at: Vec<String>,
+    pub(crate) every_hours: Option<u32>,
+}
+
+impl RestartSchedule {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.daily_at.is_empty() && self.every_hours.is_none()
+    }
+
+    /// Checks the rules and normalizes daily times to `HH:MM`.
+    pub(crate) fn validated(self) -> Result<Self, String> {
+        let mut daily_at = self
+            .daily_at
+            .iter()
+            .map(|raw| {
+                parse_daily_time(raw)
+                    .map(|time| time.format(DAILY_TIME_FORMAT).to_string())
+                    .ok_or_else(|| format!("Invalid daily restart time '{raw}', expected HH:MM."))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        daily_at.sort();
+        daily_at.dedup();
+        if self.every_hours == Some(0) {
+            return Err("Restart interval must be at least 1 hour.".to_string());
+        }
+        Ok(Self {
+            daily_at,
+            every_hours: self.every_hours,
+        })
+    }
+}
+
+fn parse_daily_time(raw: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(raw.trim(), DAILY_TIME_FORMAT).ok()
+}
+
+/// A missing field means no schedule.
+pub(crate) fn parse_restart_schedule(value: Option<&Value>) -> Result<RestartSchedule, String> {
+    let Some(value) = value.filter(|value| !value.is_null()) else {
+        return Ok(RestartSchedule::default());
+    };
+    // Accept a bare string for a single daily time.
+    let value = match value.get("dailyAt") {
+        Some(Value::String(time)) => {
+            let mut value = value.clone();
+            value["dailyAt"] = json!([time]);
+            value
+        }
+        _ => value.clone(),
+    };
+    serde_json::from_value::<RestartSchedule>(value)
+        .map_err(|error| format!("Malformed restart schedule: {error}"))?
+        .validated()
+}
+
+/// Earliest scheduled restart after `now`; `every_hours` counts from `anchor`, the last time
+/// the backend was started or restarted.
+pub(crate) fn next_scheduled_restart(
+    schedule: &RestartSchedule,
+    now: DateTime<Local>,
+    anchor: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    let daily = schedule
+        .daily_at
+        .iter()
+        .filter_map(|raw| parse_daily_time(raw))
+        .filter_map(|time| next_daily_occurrence(time, now));
+    let interval = schedule
+        .every_hours
+        .map(|hours| anchor + ChronoDuration::hours(i64::from(hours)))
+        .map(|due| due.max(now));
+    daily.chain(interval).min()
+}
+
+fn next_daily_occurrence(time: NaiveTime, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let today = now.date_naive();
+    [today, today.succ_opt()?]
+        .into_iter()
+        .filter_map(|day| {
+            let local = day.and_time(time);
+            // Times skipped by a DST jump fire an hour later instead of being dropped.
+            Local.from_local_datetime(&local).earliest().or_else(|| {
+                Local
+                    .from_local_datetime(&(local + ChronoDuration::hours(1)))
+                    .earliest()
+            })
+        })
+        .find(|candidate| *candidate > now)
+}
+
+impl BackendState {
+    pub(crate) fn restart_schedule(&self) -> Result<RestartSchedule, String> {
+        let value = self.desktop_state_path().and_then(|path| {
+            desktop_state::read_desktop_state_field_at(&path, RESTART_SCHEDULE_STATE_FIELD)
+        });
+        parse_restart_schedule(value.as_ref())
+    }
+
+    pub(crate) fn write_restart_schedule(&self, schedule: &RestartSchedule) -> Result<(), String> {
+        let path = self
+            .desktop_state_path()
+            .ok_or_else(|| "Desktop state path is unavailable.".to_string())?;
+        let value = if schedule.is_empty() {
+            None
+        } else {
+            Some(
+                serde_json::to_value(schedule)
+                    .map_err(|error| format!("Failed to serialize restart schedule: {error}"))?,
+            )
+        };
+        desktop_state::write_desktop_state_field_at(&path, RESTART_SCHEDULE_STATE_FIELD, value)
+    }
+
+    pub(crate) fn next_scheduled_restart_ms(&self) -> Option<i64> {
+        match self.next_scheduled_restart.lock() {
+            Ok(guard) => *guard,
+            Err(error) => *error.into_inner(),
+        }
+    }
+
+    fn set_next_scheduled_restart(&self, due: Option<DateTime<Local>>) {
+        let value = due.map(|due| due.timestamp_millis());
+        match self.next_scheduled_restart.lock() {
+            Ok(mut guard) => *guard = value,
+            Err(error) => *error.into_inner() = value,
+        }
+    }
+
+    /// Watches the schedule for the child that was just spawned. The schedule is re-read on
+    /// every tick so edits to `desktop_state.json` apply without restarting; the thread ends
+    /// once that child is gone or replaced.
+    pub(crate) fn start_restart_scheduler(&self, app: &AppHandle, child_pid: u32) {
+        let app_handle = app.clone();
+        let instance_id = self.id.clone();
+        let spawn_result = thread::Builder::new()
+            .name(format!("backend-restart-schedule-{instance_id}"))
+            .spawn(move || {
+                let mut anchor = Local::now();
+                let mut schedule: Option<RestartSchedule> = None;
+                let mut due = None;
+                let mut schedule_error: Option<String> = None;
+                loop {
+                    let Some(state) = app_handle.state::<BackendRegistry>().get(&instance_id)
+                    else {
+                        return;
+                    };
+                    match state.current_child_pid() {
+                        Some(pid) if pid == child_pid => {}
+                        Some(_) => return,
+                        None => {
+                            state.set_next_scheduled_restart(None);
+                            return;
+                        }
+                    }
+
+                    let now = Local::now();
+                    // An invalid schedule counts as none, so a typo never restarts a bot at
+                    // an unexpected time; it is logged once per distinct error.
+                    let current = match state.restart_schedule() {
+                        Ok(current) => {
+                            schedule_error = None;
+                            current
+                        }
+                        Err(error) => {
+                            if schedule_error.as_ref() != Some(&error) {
+                                append_restart_log_at(
+                                    DesktopLogLevel::Warn,
+                                    &format!(
+                                    "ignoring restart schedule for instance={instance_id}: {error}"
+                                ),
+                                );
+                                schedule_error = Some(error);
+                            }
+                            RestartSchedule::default()
+                        }
+                    };
+                    if schedule.as_ref() != Some(&current) {
+                        if schedule.is_some() {
+                            // An interval added while running counts from the edit, not from
+                            // the original start.
+                            anchor = now;
+                        }
+                        due = next_scheduled_restart(&current, now, anchor);
+                        if let Some(due) = due {
+                            append_restart_log(&format!(
+                                "next scheduled backend restart for instance={instance_id}: {}",
+                                due.format("%Y-%m-%d %H:%M:%S %z")
+                            ));
+                        }
+                        state.set_next_scheduled_restart(due);
+                        schedule = Some(current);
+                    }
+
+                    if due.is_some_and(|due| now >= due) {
+                        state.run_scheduled_restart(&app_handle);
+                        if state.current_child_pid() != Some(child_pid) {
+                            // The replacement child runs its own scheduler.
+                            return;
+                        }
+                        anchor = Local::now();
+                        due = schedule
+                            .as_ref()
+                            .and_then(|schedule| next_scheduled_restart(schedule, anchor, anchor));
+                        state.set_next_scheduled_restart(due);
+                    }
+                    drop(state);
+                    thread::sleep(BACKEND_RESTART_SCHEDULE_POLL_INTERVAL);
+                }
+            });
+        if let Err(error) = spawn_result {
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to spawn backend restart scheduler: {error}"),
+            );
+        }
+    }
+
+    fn run_scheduled_restart(&self, app: &AppHandle) {
+        if restart_backend_flow::is_backend_action_in_progress(self) {
+            append_restart_log(&format!(
+                "scheduled backend restart skipped for instance={}: backend action in progress",
+                self.id
+            ));
+            return;
+        }
+        append_restart_log(&format!(
+            "scheduled backend restart triggered for instance={}",
+            self.id
+        ));
+        if let Err(error) =
+            restart_backend_flow::restart_backend_instance(app, Some(&self.id), None)
+        {
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("scheduled backend restart failed: {error}"),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(raw: &str) -> DateTime<Local> {
+        Local
+            .from_local_datetime(
+                &chrono::NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M").expect("datetime"),
+            )
+            .earliest()
+            .expect("local time")
+    }
+
+    #[test]
+    fn schedule_parsing_accepts_a_single_daily_time_and_rejects_bad_input() {
+        assert_eq!(
+            parse_restart_schedule(Some(&json!({ "dailyAt": "4:30" }))),
+            Ok(RestartSchedule {
+                daily_at: vec!["04:30".to_string()],
+                every_hours: None,
+            })
+        );
+        assert!(parse_restart_schedule(Some(&json!({ "dailyAt": ["25:00"] }))).is_err());
+        assert!(parse_restart_schedule(Some(&json!({ "everyHours": 0 }))).is_err());
+        assert!(parse_restart_schedule(Some(&json!({ "everyHours": "6" }))).is_err());
+        assert_eq!(parse_restart_schedule(None), Ok(RestartSchedule::default()));
+    }
+
+    #[test]
+    fn next_restart_picks_the_earliest_rule() {
+        let schedule = RestartSchedule {
+            daily_at: vec!["04:30".to_string(), "16:00".to_string()],
+            every_hours: None,
+        };
+        let now = local("2024-05-01 10:00");
+        assert_eq!(
+            next_scheduled_restart(&schedule, now, now),
+            Some(local("2024-05-01 16:00"))
+        );
+        let late = local("2024-05-01 17:00");
+        assert_eq!(
+            next_scheduled_restart(&schedule, late, late),
+            Some(local("2024-05-02 04:30"))
+        );
+
+        let interval = RestartSchedule {
+            every_hours: Some(2),
+            ..schedule
+        };
+        assert_eq!(
+            next_scheduled_restart(&interval, now, local("2024-05-01 09:00")),
+            Some(local("2024-05-01 11:00"))
+        );
+        assert_eq!(
+            next_scheduled_restart(&RestartSchedule::default(), now, now),
+            None
+        );
+    }
+}
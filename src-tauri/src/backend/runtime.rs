@@ -4,6 +4,9 @@ use crate::backend;
 
 static BACKEND_PING_TIMEOUT_MS: OnceLock<u64> = OnceLock::new();
 static BRIDGE_BACKEND_PING_TIMEOUT_MS: OnceLock<u64> = OnceLock::new();
+static BACKEND_METRICS_INTERVAL_MS: OnceLock<Option<u64>> = OnceLock::new();
+static BACKEND_STOP_CONFIG: OnceLock<backend::process_lifecycle::BackendStopConfig> =
+    OnceLock::new();
 
 pub fn backend_wait_timeout(packaged_mode: bool) -> Duration {
     backend::config::resolve_backend_timeout_ms(
@@ -51,6 +54,18 @@ where
     readiness
 }
 
+/// The path `backend_readiness_config` waits on, for probes that run without a launch plan.
+pub fn backend_ready_http_path<F>(log: F) -> String
+where
+    F: Fn(&str) + Copy,
+{
+    backend::config::resolve_backend_ready_http_path(
+        crate::BACKEND_READY_HTTP_PATH_ENV,
+        crate::DEFAULT_BACKEND_READY_HTTP_PATH,
+        |message| log(&message),
+    )
+}
+
 pub fn backend_ping_timeout_ms<F>(log: F) -> u64
 where
     F: Fn(&str) + Copy,
@@ -87,3 +102,35 @@ where
         }
     })
 }
+
+/// Sampling interval of the backend resource monitor; `0` or `off` disables sampling.
+pub fn backend_metrics_interval<F>(log: F) -> Option<Duration>
+where
+    F: Fn(&str) + Copy,
+{
+    BACKEND_METRICS_INTERVAL_MS
+        .get_or_init(|| match env::var(crate::BACKEND_METRICS_INTERVAL_ENV) {
+            Ok(raw) if matches!(raw.trim().to_ascii_lowercase().as_str(), "0" | "off") => None,
+            Ok(raw) => Some(backend::config::parse_clamped_timeout_env(
+                &raw,
+                crate::BACKEND_METRICS_INTERVAL_ENV,
+                crate::DEFAULT_BACKEND_METRICS_INTERVAL_MS,
+                crate::BACKEND_METRICS_INTERVAL_MIN_MS,
+                crate::BACKEND_METRICS_INTERVAL_MAX_MS,
+                |message| log(&message),
+            )),
+            Err(_) => Some(crate::DEFAULT_BACKEND_METRICS_INTERVAL_MS),
+        })
+        .map(Duration::from_millis)
+}
+
+pub fn backend_stop_config<F>(log: F) -> backend::process_lifecycle::BackendStopConfig
+where
+    F: Fn(&str) + Copy,
+{
+    BACKEND_STOP_CONFIG
+        .get_or_init(|| {
+            backend::process_lifecycle::resolve_stop_config_with(|name| env::var(name).ok(), log)
+        })
+        .clone()
+}
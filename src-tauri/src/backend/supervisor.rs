@@ -0,0 +1,526 @@
+use std::{
+    env,
+    process::ExitStatus,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    append_restart_log, append_restart_log_at,
+    backend::crash_history,
+    backend::lifecycle::LifecycleEvent,
+    backend::supervisor_policy::{
+        is_auto_restart_enabled, CrashRestartDecision, CrashRestartPolicy,
+    },
+    logging::{self, DesktopLogLevel},
+    process_control, restart_backend_flow, AtomicFlagGuard, BackendRegistry, BackendSpawnInfo,
+    BackendState, LaunchPlan, BACKEND_AUTO_RESTART_ENV, BACKEND_CRASH_HISTORY_MAX_RECORDS,
+    BACKEND_CRASH_LOG_TAIL_LINES, BACKEND_CRASH_LOOP_EVENT, BACKEND_CRASH_RESTART_INITIAL_BACKOFF,
+    BACKEND_CRASH_RESTART_MAX_BACKOFF, BACKEND_CRASH_RESTART_MAX_IN_WINDOW,
+    BACKEND_CRASH_RESTART_WINDOW, BACKEND_SUPERVISOR_POLL_INTERVAL,
+};
+
+const CRASH_RESTART_BACKOFF_SLICE: Duration = Duration::from_millis(200);
+
+fn crash_restart_policy() -> CrashRestartPolicy {
+    CrashRestartPolicy {
+        max_restarts_in_window: BACKEND_CRASH_RESTART_MAX_IN_WINDOW,
+        window: BACKEND_CRASH_RESTART_WINDOW,
+        initial_backoff: BACKEND_CRASH_RESTART_INITIAL_BACKOFF,
+        max_backoff: BACKEND_CRASH_RESTART_MAX_BACKOFF,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CrashRestartAttempt {
+    Recovered,
+    Skipped,
+}
+
+impl BackendState {
+    pub(crate) fn launch_supervised_backend(
+        &self,
+        app: &AppHandle,
+        plan: &LaunchPlan,
+    ) -> Result<(), String> {
+        self.start_backend_process(app, plan)?;
+        let wait_result = self.wait_for_backend(plan);
+        // A cancel that arrived after the wait ended must not leak into the next launch.
+        self.startup_cancel_requested
+            .store(false, Ordering::Relaxed);
+        if let Err(error) = wait_result {
+            // Leave nothing half-started behind; a retry spawns from scratch.
+            if let Err(stop_error) = self.stop_managed_child() {
+                append_restart_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!("failed to stop backend after failed startup: {stop_error}"),
+                );
+            }
+            self.apply_lifecycle_event(LifecycleEvent::StartFailed(error.clone()));
+            return Err(error);
+        }
+        self.apply_lifecycle_event(LifecycleEvent::Ready);
+        self.start_backend_supervisor(app);
+        self.start_liveness_monitor(app, plan);
+        Ok(())
+    }
+
+    pub(crate) fn stop_backend_supervisor(&self) {
+        match self.supervisor_stop.lock() {
+            Ok(mut guard) => {
+                if let Some(flag) = guard.take() {
+                    flag.store(true, Ordering::Relaxed);
+                }
+            }
+            Err(error) => {
+                append_restart_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("backend supervisor stop flag lock poisoned: {error}"),
+                );
+            }
+        }
+    }
+
+    pub(crate) fn reset_crash_restart_tracker(&self) {
+        match self.crash_restart_tracker.lock() {
+            Ok(mut tracker) => tracker.reset(),
+            Err(error) => append_restart_log_at(
+                DesktopLogLevel::Error,
+                &format!("backend crash restart tracker lock poisoned on reset: {error}"),
+            ),
+        }
+        self.set_crash_loop_error(None);
+    }
+
+    pub(crate) fn last_exit_status(&self) -> Option<String> {
+        match self.last_exit_status.lock() {
+            Ok(guard) => guard.clone(),
+            Err(error) => {
+                append_restart_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("backend last exit status lock poisoned when reading: {error}"),
+                );
+                None
+            }
+        }
+    }
+
+    pub(crate) fn crash_loop_error(&self) -> Option<String> {
+        match self.crash_loop_error.lock() {
+            Ok(guard) => guard.clone(),
+            Err(error) => {
+                append_restart_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("backend crash loop error lock poisoned when reading: {error}"),
+                );
+                None
+            }
+        }
+    }
+
+    pub(crate) fn set_spawn_info(&self, spawn_info: BackendSpawnInfo) {
+        match self.spawn_info.lock() {
+            Ok(mut guard) => *guard = Some(spawn_info),
+            Err(error) => append_restart_log_at(
+                DesktopLogLevel::Error,
+                &format!("backend spawn info lock poisoned when writing: {error}"),
+            ),
+        }
+    }
+
+    pub(crate) fn spawn_info_for_pid(&self, pid: u32) -> Option<BackendSpawnInfo> {
+        match self.spawn_info.lock() {
+            Ok(guard) => guard.as_ref().filter(|info| info.pid == pid).cloned(),
+            Err(error) => {
+                append_restart_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("backend spawn info lock poisoned when reading: {error}"),
+                );
+                None
+            }
+        }
+    }
+
+    /// `status` is `None` for an adopted backend, whose exit can only be noticed by pid.
+    pub(crate) fn record_backend_crash(&self, pid: u32, status: Option<&ExitStatus>) {
+        self.set_last_exit_status(status.map(ToString::to_string));
+        self.apply_lifecycle_event(LifecycleEvent::Exited);
+        let spawn_info = self.spawn_info_for_pid(pid);
+        let log_tail = spawn_info
+            .as_ref()
+            .and_then(|info| info.log_path.as_deref())
+            .map(|path| crash_history::read_log_tail(path, BACKEND_CRASH_LOG_TAIL_LINES))
+            .unwrap_or_default();
+        let record = crash_history::BackendCrashRecord::new(
+            pid,
+            status,
+            spawn_info
+                .as_ref()
+                .map(|info| info.command.clone())
+                .unwrap_or_default(),
+            spawn_info.as_ref().map(|info| info.started_at.elapsed()),
+            log_tail,
+            SystemTime::now(),
+        );
+
+        let Some(history_path) = self.crash_history_path() else {
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                "backend crash history path is unavailable; skip crash record",
+            );
+            return;
+        };
+        if let Err(error) = crash_history::append_crash_record(
+            &history_path,
+            &record,
+            BACKEND_CRASH_HISTORY_MAX_RECORDS,
+        ) {
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to persist backend crash record: {error}"),
+            );
+        }
+    }
+
+    fn set_last_exit_status(&self, status: Option<String>) {
+        match self.last_exit_status.lock() {
+            Ok(mut guard) => *guard = status,
+            Err(error) => append_restart_log_at(
+                DesktopLogLevel::Error,
+                &format!("backend last exit status lock poisoned when writing: {error}"),
+            ),
+        }
+    }
+
+    fn set_crash_loop_error(&self, error_message: Option<String>) {
+        match self.crash_loop_error.lock() {
+            Ok(mut guard) => *guard = error_message,
+            Err(error) => append_restart_log_at(
+                DesktopLogLevel::Error,
+                &format!("backend crash loop error lock poisoned when writing: {error}"),
+            ),
+        }
+    }
+
+    pub(crate) fn current_child_pid(&self) -> Option<u32> {
+        match self.child.lock() {
+            Ok(guard) => guard
+                .as_ref()
+                .map(|child| child.id())
+                .or_else(|| self.adopted_backend_pid()),
+            Err(error) => {
+                append_restart_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("backend child lock poisoned while starting supervisor: {error}"),
+                );
+                None
+            }
+        }
+    }
+
+    pub(crate) fn start_backend_supervisor(&self, app: &AppHandle) {
+        self.stop_backend_supervisor();
+        if !is_auto_restart_enabled(env::var(BACKEND_AUTO_RESTART_ENV).ok().as_deref()) {
+            append_restart_log(&format!(
+                "backend crash supervisor disabled by {BACKEND_AUTO_RESTART_ENV}"
+            ));
+            return;
+        }
+        let Some(child_pid) = self.current_child_pid() else {
+            return;
+        };
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        match self.supervisor_stop.lock() {
+            Ok(mut guard) => {
+                *guard = Some(stop_flag.clone());
+            }
+            Err(error) => {
+                append_restart_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("backend supervisor stop flag lock poisoned on start: {error}"),
+                );
+                return;
+            }
+        }
+
+        let adopted = self.adopted_backend_pid() == Some(child_pid);
+        let app_handle = app.clone();
+        let instance_id = self.id.clone();
+        thread::spawn(move || {
+            let Some(state) = app_handle.state::<BackendRegistry>().get(&instance_id) else {
+                return;
+            };
+            let status = if adopted {
+                if !state.wait_for_adopted_exit(child_pid, &stop_flag) {
+                    return;
+                }
+                None
+            } else {
+                let Some(status) = state.wait_for_unexpected_exit(child_pid, &stop_flag) else {
+                    return;
+                };
+                Some(status)
+            };
+            state.handle_backend_crash(&app_handle, child_pid, status.as_ref(), &stop_flag);
+        });
+    }
+
+    /// Polls an adopted backend by pid, since its `Child` handle died with the previous desktop
+    /// session. Returns `true` once it is gone without the desktop having stopped it.
+    fn wait_for_adopted_exit(&self, pid: u32, stop_flag: &AtomicBool) -> bool {
+        loop {
+            thread::sleep(BACKEND_SUPERVISOR_POLL_INTERVAL);
+            if stop_flag.load(Ordering::Relaxed) || self.adopted_backend_pid() != Some(pid) {
+                return false;
+            }
+            if process_control::is_process_alive(pid) {
+                continue;
+            }
+            if stop_flag.load(Ordering::Relaxed) {
+                return false;
+            }
+            self.forget_adopted_backend(pid);
+            return true;
+        }
+    }
+
+    fn wait_for_unexpected_exit(
+        &self,
+        child_pid: u32,
+        stop_flag: &AtomicBool,
+    ) -> Option<ExitStatus> {
+        loop {
+            thread::sleep(BACKEND_SUPERVISOR_POLL_INTERVAL);
+            if stop_flag.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            let mut guard = match self.child.lock() {
+                Ok(guard) => guard,
+                Err(error) => {
+                    append_restart_log_at(
+                        DesktopLogLevel::Error,
+                        &format!(
+                            "backend child lock poisoned in supervisor pid={child_pid}: {error}"
+                        ),
+                    );
+                    return None;
+                }
+            };
+            // stop_backend raises the flag before taking the child lock, so re-check it here to
+            // avoid reporting a desktop-initiated stop as a crash.
+            if stop_flag.load(Ordering::Relaxed) {
+                return None;
+            }
+            let child = guard.as_mut()?;
+            if child.id() != child_pid {
+                return None;
+            }
+
+            match child.try_wait() {
+                Ok(None) => {}
+                Ok(Some(status)) => {
+                    *guard = None;
+                    drop(guard);
+                    self.clear_backend_pidfile(child_pid);
+                    logging::clear_logged_backend_pid(child_pid);
+                    return Some(status);
+                }
+                Err(error) => {
+                    append_restart_log_at(
+                        DesktopLogLevel::Warn,
+                        &format!(
+                            "failed to poll backend process status in supervisor pid={child_pid}: {error}"
+                        ),
+                    );
+                    return None;
+                }
+            }
+        }
+    }
+
+    fn should_abandon_crash_restart(&self, stop_flag: &AtomicBool) -> bool {
+        stop_flag.load(Ordering::Relaxed) || self.is_quitting()
+    }
+
+    fn sleep_unless_abandoned(&self, delay: Duration, stop_flag: &AtomicBool) -> bool {
+        let deadline = Instant::now() + delay;
+        loop {
+            if self.should_abandon_crash_restart(stop_flag) {
+                return false;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return true;
+            }
+            thread::sleep(remaining.min(CRASH_RESTART_BACKOFF_SLICE));
+        }
+    }
+
+    fn handle_backend_crash(
+        &self,
+        app: &AppHandle,
+        child_pid: u32,
+        status: Option<&ExitStatus>,
+        stop_flag: &AtomicBool,
+    ) {
+        let status_label = status.map_or_else(|| "unknown".to_string(), ToString::to_string);
+        append_restart_log_at(
+            DesktopLogLevel::Warn,
+            &format!("backend process exited unexpectedly: pid={child_pid}, status={status_label}"),
+        );
+        self.record_backend_crash(child_pid, status);
+
+        if restart_backend_flow::is_backend_action_in_progress(self) {
+            append_restart_log(
+                "backend exited during a desktop-initiated backend action; leaving recovery to that action",
+            );
+            return;
+        }
+
+        let policy = crash_restart_policy();
+        loop {
+            if self.should_abandon_crash_restart(stop_flag) {
+                append_restart_log("backend crash restart abandoned: stop or quit requested");
+                return;
+            }
+
+            let decision = match self.crash_restart_tracker.lock() {
+                Ok(mut tracker) => tracker.record_crash(&policy, Instant::now()),
+                Err(error) => {
+                    append_restart_log_at(
+                        DesktopLogLevel::Error,
+                        &format!("backend crash restart tracker lock poisoned: {error}"),
+                    );
+                    return;
+                }
+            };
+            let delay = match decision {
+                CrashRestartDecision::RestartAfter(delay) => delay,
+                CrashRestartDecision::GiveUp { crashes_in_window } => {
+                    self.report_crash_loop(app, crashes_in_window, &policy);
+                    return;
+                }
+            };
+
+            append_restart_log(&format!(
+                "scheduling backend crash restart in {}ms",
+                delay.as_millis()
+            ));
+            if !self.sleep_unless_abandoned(delay, stop_flag) {
+                append_restart_log("backend crash restart abandoned: stop or quit requested");
+                return;
+            }
+
+            match self.try_restart_after_crash(app) {
+                Ok(CrashRestartAttempt::Recovered) => {
+                    append_restart_log("backend recovered after unexpected exit");
+                    self.set_crash_loop_error(None);
+                    return;
+                }
+                Ok(CrashRestartAttempt::Skipped) => return,
+                Err(error) => {
+                    append_restart_log_at(
+                        DesktopLogLevel::Error,
+                        &format!("backend crash restart attempt failed: {error}"),
+                    );
+                }
+            }
+        }
+    }
+
+    fn try_restart_after_crash(&self, app: &AppHandle) -> Result<CrashRestartAttempt, String> {
+        let Some(_restart_guard) = AtomicFlagGuard::try_set(&self.is_restarting) else {
+            append_restart_log("backend crash restart skipped: backend action already in progress");
+            return Ok(CrashRestartAttempt::Skipped);
+        };
+        let Some(_spawn_guard) = AtomicFlagGuard::try_set(&self.is_spawning) else {
+            append_restart_log("backend crash restart skipped: backend spawn already in progress");
+            return Ok(CrashRestartAttempt::Skipped);
+        };
+        if self.current_child_pid().is_some() {
+            append_restart_log(
+                "backend crash restart skipped: a managed backend is already running",
+            );
+            return Ok(CrashRestartAttempt::Skipped);
+        }
+
+        self.apply_lifecycle_event(LifecycleEvent::RestartBegan);
+        let result = self.restart_after_crash(app);
+        match &result {
+            Ok(CrashRestartAttempt::Recovered) => {
+                self.apply_lifecycle_event(LifecycleEvent::Ready);
+            }
+            Ok(CrashRestartAttempt::Skipped) => {}
+            Err(error) => {
+                self.apply_lifecycle_event(LifecycleEvent::RestartFailed(error.clone()));
+            }
+        }
+        result
+    }
+
+    fn restart_after_crash(&self, app: &AppHandle) -> Result<CrashRestartAttempt, String> {
+        let plan = self.resolve_launch_plan(app)?;
+        let previous_backend_url = self.backend_url();
+        self.start_backend_process(app, &plan)?;
+        if self.is_quitting() {
+            append_restart_log("desktop is quitting after crash restart spawn; stopping backend");
+            self.stop_backend()?;
+            return Ok(CrashRestartAttempt::Skipped);
+        }
+        if let Err(error) = self.wait_for_backend(&plan) {
+            if let Err(stop_error) = self.stop_managed_child() {
+                append_restart_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!("failed to stop backend after crash restart attempt: {stop_error}"),
+                );
+            }
+            return Err(error);
+        }
+
+        self.start_backend_supervisor(app);
+        self.start_liveness_monitor(app, &plan);
+        restart_backend_flow::follow_backend_url_change(app, self, &previous_backend_url);
+        Ok(CrashRestartAttempt::Recovered)
+    }
+
+    fn report_crash_loop(
+        &self,
+        app: &AppHandle,
+        crashes_in_window: usize,
+        policy: &CrashRestartPolicy,
+    ) {
+        let message = format!(
+            "Backend crashed {crashes_in_window} times within {}s; automatic restart stopped.",
+            policy.window.as_secs()
+        );
+        append_restart_log(&message);
+        self.set_crash_loop_error(Some(message.clone()));
+        self.apply_lifecycle_event(LifecycleEvent::CrashLoop(message.clone()));
+        if app.state::<BackendRegistry>().selected_id() != self.id {
+            // The page only reflects the selected instance; others surface through the tray.
+            return;
+        }
+
+        let Some(window) = app.get_webview_window("main") else {
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                "backend crash loop event skipped: main window not found",
+            );
+            return;
+        };
+        if let Err(error) = window.emit(BACKEND_CRASH_LOOP_EVENT, message) {
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to emit backend crash loop event: {error}"),
+            );
+        }
+    }
+}
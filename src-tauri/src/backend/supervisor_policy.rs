@@ -0,0 +1,165 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CrashRestartPolicy {
+    pub(crate) max_restarts_in_window: usize,
+    pub(crate) window: Duration,
+    pub(crate) initial_backoff: Duration,
+    pub(crate) max_backoff: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CrashRestartDecision {
+    RestartAfter(Duration),
+    GiveUp { crashes_in_window: usize },
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct CrashRestartTracker {
+    crashes: VecDeque<Instant>,
+}
+
+impl CrashRestartTracker {
+    pub(crate) fn record_crash(
+        &mut self,
+        policy: &CrashRestartPolicy,
+        now: Instant,
+    ) -> CrashRestartDecision {
+        while let Some(oldest) = self.crashes.front() {
+            if now.saturating_duration_since(*oldest) > policy.window {
+                self.crashes.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.crashes.push_back(now);
+
+        let crashes_in_window = self.crashes.len();
+        if crashes_in_window > policy.max_restarts_in_window {
+            return CrashRestartDecision::GiveUp { crashes_in_window };
+        }
+        CrashRestartDecision::RestartAfter(compute_crash_restart_backoff(policy, crashes_in_window))
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.crashes.clear();
+    }
+}
+
+pub(crate) fn compute_crash_restart_backoff(
+    policy: &CrashRestartPolicy,
+    crashes_in_window: usize,
+) -> Duration {
+    let exponent = crashes_in_window.saturating_sub(1).min(16) as u32;
+    policy
+        .initial_backoff
+        .saturating_mul(2_u32.saturating_pow(exponent))
+        .min(policy.max_backoff)
+}
+
+pub(crate) fn is_auto_restart_enabled(raw: Option<&str>) -> bool {
+    !matches!(
+        raw.map(|value| value.trim().to_ascii_lowercase())
+            .as_deref(),
+        Some("0" | "false" | "no" | "off")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> CrashRestartPolicy {
+        CrashRestartPolicy {
+            max_restarts_in_window: 3,
+            window: Duration::from_secs(60),
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_millis(1_500),
+        }
+    }
+
+    #[test]
+    fn compute_crash_restart_backoff_doubles_until_cap() {
+        let policy = policy();
+        assert_eq!(
+            compute_crash_restart_backoff(&policy, 1),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            compute_crash_restart_backoff(&policy, 2),
+            Duration::from_millis(1_000)
+        );
+        assert_eq!(
+            compute_crash_restart_backoff(&policy, 3),
+            Duration::from_millis(1_500)
+        );
+        assert_eq!(
+            compute_crash_restart_backoff(&policy, usize::MAX),
+            Duration::from_millis(1_500)
+        );
+    }
+
+    #[test]
+    fn record_crash_gives_up_after_too_many_crashes_in_window() {
+        let policy = policy();
+        let mut tracker = CrashRestartTracker::default();
+        let start = Instant::now();
+
+        for offset in 0..3 {
+            assert!(matches!(
+                tracker.record_crash(&policy, start + Duration::from_secs(offset)),
+                CrashRestartDecision::RestartAfter(_)
+            ));
+        }
+        assert_eq!(
+            tracker.record_crash(&policy, start + Duration::from_secs(3)),
+            CrashRestartDecision::GiveUp {
+                crashes_in_window: 4
+            }
+        );
+    }
+
+    #[test]
+    fn record_crash_forgets_crashes_outside_window() {
+        let policy = policy();
+        let mut tracker = CrashRestartTracker::default();
+        let start = Instant::now();
+
+        for offset in 0..3 {
+            tracker.record_crash(&policy, start + Duration::from_secs(offset));
+        }
+        assert_eq!(
+            tracker.record_crash(&policy, start + Duration::from_secs(120)),
+            CrashRestartDecision::RestartAfter(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn reset_clears_crash_history() {
+        let policy = policy();
+        let mut tracker = CrashRestartTracker::default();
+        let start = Instant::now();
+        for offset in 0..4 {
+            tracker.record_crash(&policy, start + Duration::from_secs(offset));
+        }
+
+        tracker.reset();
+
+        assert_eq!(
+            tracker.record_crash(&policy, start + Duration::from_secs(5)),
+            CrashRestartDecision::RestartAfter(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn is_auto_restart_enabled_only_rejects_explicit_opt_out() {
+        assert!(is_auto_restart_enabled(None));
+        assert!(is_auto_restart_enabled(Some("1")));
+        assert!(is_auto_restart_enabled(Some("")));
+        assert!(!is_auto_restart_enabled(Some("0")));
+        assert!(!is_auto_restart_enabled(Some(" OFF ")));
+    }
+}
@@ -1,4 +1,7 @@
-use std::process::{Command, Stdio};
+use std::{
+    path::PathBuf,
+    process::{Command, Stdio},
+};
 use tauri::{AppHandle, Manager};
 use tauri_plugin_updater::UpdaterExt;
 use url::Url;
@@ -14,8 +17,18 @@ use crate::bridge::updater_types::{
     DesktopAppUpdateChannelResult, DesktopAppUpdateCheckResult, DesktopAppUpdateResult,
 };
 use crate::{
-    append_desktop_log, restart_backend_flow, runtime_paths, shell_locale, tray, update_channel,
-    BackendBridgeResult, BackendBridgeState, BackendState, DEFAULT_SHELL_LOCALE,
+    app_helpers::{configured_desktop_log_level, effective_desktop_log_level},
+    append_desktop_log, append_desktop_log_at, append_restart_log, append_shutdown_log, backend,
+    diagnostics, log_query,
+    logging::{self, DesktopLogLevel},
+    proxy_config, restart_backend_flow, runtime_paths, shell_locale, startup_task, tray,
+    ui_dispatch, update_channel, window, BackendBridgeResult, BackendBridgeState,
+    BackendCrashHistoryResult, BackendEnvResult, BackendLogsResult, BackendMetricsResult,
+    BackendRegistry, BackendRestartScheduleResult, BackendState, DesktopLogLevelResult,
+    DesktopProxyConfigResult, DiagnosticsExportResult, LogQueryResult,
+    BACKEND_CRASH_HISTORY_DEFAULT_LIMIT, BACKEND_CRASH_HISTORY_MAX_RECORDS,
+    BACKEND_LOG_BUFFER_CAPACITY, BACKEND_LOG_DEFAULT_LIMIT, DEFAULT_SHELL_LOCALE, DESKTOP_LOG_FILE,
+    LOG_QUERY_DEFAULT_LIMIT, LOG_QUERY_MAX_LIMIT,
 };
 
 fn resolve_update_channel(app_handle: &AppHandle) -> update_channel::UpdateChannel {
@@ -46,8 +59,24 @@ fn build_channel_aware_updater(
         Url::parse(&raw_endpoint).map_err(|error| format!("Invalid updater endpoint: {error}"))?;
     append_desktop_log(&updater_manifest_log_message(preferred_channel, &endpoint));
 
-    app_handle
-        .updater_builder()
+    let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+    let proxy = proxy_config::read_proxy_config(packaged_root_dir.as_deref())
+        .unwrap_or_else(|error| {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("ignoring proxy configuration for updater: {error}"),
+            );
+            proxy_config::ProxyConfig::default()
+        })
+        .updater_proxy(&endpoint)?;
+    let mut builder = app_handle.updater_builder();
+    match proxy {
+        proxy_config::UpdaterProxy::System => {}
+        proxy_config::UpdaterProxy::Direct => builder = builder.no_proxy(),
+        proxy_config::UpdaterProxy::Proxy(url) => builder = builder.proxy(url),
+    }
+
+    builder
         .endpoints(vec![endpoint])
         .map_err(|error| format!("Failed to configure updater endpoint: {error}"))?
         .version_comparator(move |current_version, remote_release| {
@@ -98,7 +127,8 @@ fn has_managed_backend_child(state: &BackendState) -> bool {
     match state.child.lock() {
         Ok(guard) => guard.is_some(),
         Err(_) => {
-            append_desktop_log(
+            append_desktop_log_at(
+                DesktopLogLevel::Error,
                 "backend child lock poisoned while checking managed backend state; assuming no managed backend child",
             );
             false
@@ -138,10 +168,12 @@ fn build_restart_backend_after_failed_install(
     restart_plan: crate::LaunchPlan,
 ) -> impl FnOnce() -> Result<(), String> {
     move || {
-        let state = app_handle.state::<BackendState>();
-        append_desktop_log("update install failed before exit, restarting managed backend");
-        state.start_backend_process(&app_handle, &restart_plan)?;
-        state.wait_for_backend(&restart_plan)
+        let state = app_handle.state::<BackendRegistry>().selected();
+        append_desktop_log_at(
+            DesktopLogLevel::Warn,
+            "update install failed before exit, restarting managed backend",
+        );
+        state.launch_supervised_backend(&app_handle, &restart_plan)
     }
 }
 
@@ -208,7 +240,7 @@ pub(crate) fn desktop_bridge_is_desktop_runtime() -> bool {
 
 #[tauri::command]
 pub(crate) fn desktop_bridge_get_backend_state(app_handle: AppHandle) -> BackendBridgeState {
-    let state = app_handle.state::<BackendState>();
+    let state = app_handle.state::<BackendRegistry>().selected();
     state.bridge_state(&app_handle)
 }
 
@@ -217,7 +249,7 @@ pub(crate) fn desktop_bridge_set_auth_token(
     app_handle: AppHandle,
     auth_token: Option<String>,
 ) -> BackendBridgeResult {
-    let state = app_handle.state::<BackendState>();
+    let state = app_handle.state::<BackendRegistry>().selected();
     state.set_restart_auth_token(auth_token.as_deref());
     BackendBridgeResult {
         ok: true,
@@ -230,7 +262,7 @@ pub(crate) async fn desktop_bridge_restart_backend(
     app_handle: AppHandle,
     auth_token: Option<String>,
 ) -> BackendBridgeResult {
-    let state = app_handle.state::<BackendState>();
+    let state = app_handle.state::<BackendRegistry>().selected();
     if restart_backend_flow::is_backend_action_in_progress(&state) {
         return BackendBridgeResult {
             ok: false,
@@ -243,7 +275,7 @@ pub(crate) async fn desktop_bridge_restart_backend(
 
 #[tauri::command]
 pub(crate) fn desktop_bridge_stop_backend(app_handle: AppHandle) -> BackendBridgeResult {
-    let state = app_handle.state::<BackendState>();
+    let state = app_handle.state::<BackendRegistry>().selected();
     if restart_backend_flow::is_backend_action_in_progress(&state) {
         return BackendBridgeResult {
             ok: false,
@@ -263,6 +295,468 @@ pub(crate) fn desktop_bridge_stop_backend(app_handle: AppHandle) -> BackendBridg
     }
 }
 
+#[tauri::command]
+pub(crate) fn desktop_bridge_cancel_startup(app_handle: AppHandle) -> BackendBridgeResult {
+    let state = app_handle.state::<BackendRegistry>().selected();
+    match state.request_startup_cancel() {
+        Ok(()) => BackendBridgeResult {
+            ok: true,
+            reason: None,
+        },
+        Err(error) => BackendBridgeResult {
+            ok: false,
+            reason: Some(error),
+        },
+    }
+}
+
+#[tauri::command]
+pub(crate) fn desktop_bridge_retry_startup(app_handle: AppHandle) -> BackendBridgeResult {
+    let state = app_handle.state::<BackendRegistry>().selected();
+    if restart_backend_flow::is_backend_action_in_progress(&state) {
+        return BackendBridgeResult {
+            ok: false,
+            reason: Some("Backend action already in progress.".to_string()),
+        };
+    }
+
+    append_desktop_log("startup retry requested from startup page");
+    window::startup_loading::clear_startup_error(&state);
+    if !startup_task::spawn_startup_task(app_handle, append_desktop_log) {
+        return BackendBridgeResult {
+            ok: false,
+            reason: Some("Backend startup is already running.".to_string()),
+        };
+    }
+    BackendBridgeResult {
+        ok: true,
+        reason: None,
+    }
+}
+
+#[tauri::command]
+pub(crate) fn desktop_bridge_resolve_orphan_backend(
+    app_handle: AppHandle,
+    action: String,
+) -> BackendBridgeResult {
+    let Some(action) = backend::orphan::OrphanAction::parse(&action) else {
+        return BackendBridgeResult {
+            ok: false,
+            reason: Some(format!("Unknown orphaned backend action: {action}")),
+        };
+    };
+    let state = app_handle.state::<BackendRegistry>().selected();
+    match state.choose_orphan_action(action) {
+        Ok(()) => BackendBridgeResult {
+            ok: true,
+            reason: None,
+        },
+        Err(error) => BackendBridgeResult {
+            ok: false,
+            reason: Some(error),
+        },
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn desktop_bridge_open_log_viewer(app_handle: AppHandle) -> BackendBridgeResult {
+    match ui_dispatch::run_on_main_thread_dispatch(&app_handle, "open log viewer", |app_handle| {
+        window::log_viewer::open_log_viewer_window(
+            app_handle,
+            DEFAULT_SHELL_LOCALE,
+            append_desktop_log,
+        )
+    }) {
+        Ok(()) => BackendBridgeResult {
+            ok: true,
+            reason: None,
+        },
+        Err(error) => BackendBridgeResult {
+            ok: false,
+            reason: Some(error),
+        },
+    }
+}
+
+#[tauri::command]
+pub(crate) fn desktop_bridge_quit_app(app_handle: AppHandle) -> BackendBridgeResult {
+    app_handle.state::<BackendRegistry>().mark_all_quitting();
+    append_shutdown_log("quit requested from startup page, exiting desktop process");
+    app_handle.exit(0);
+    BackendBridgeResult {
+        ok: true,
+        reason: None,
+    }
+}
+
+#[tauri::command]
+pub(crate) fn desktop_bridge_get_backend_crash_history(
+    app_handle: AppHandle,
+    limit: Option<usize>,
+) -> BackendCrashHistoryResult {
+    let state = app_handle.state::<BackendRegistry>().selected();
+    let Some(history_path) = state.crash_history_path() else {
+        return BackendCrashHistoryResult {
+            ok: true,
+            reason: None,
+            records: Vec::new(),
+        };
+    };
+
+    let limit = limit
+        .unwrap_or(BACKEND_CRASH_HISTORY_DEFAULT_LIMIT)
+        .min(BACKEND_CRASH_HISTORY_MAX_RECORDS);
+    match backend::crash_history::read_crash_history(&history_path, limit) {
+        Ok(records) => BackendCrashHistoryResult {
+            ok: true,
+            reason: None,
+            records,
+        },
+        Err(error) => {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to read backend crash history: {error}"),
+            );
+            BackendCrashHistoryResult {
+                ok: false,
+                reason: Some(error),
+                records: Vec::new(),
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) fn desktop_bridge_get_backend_logs(
+    app_handle: AppHandle,
+    limit: Option<usize>,
+) -> BackendLogsResult {
+    let state = app_handle.state::<BackendRegistry>().selected();
+    let limit = limit
+        .unwrap_or(BACKEND_LOG_DEFAULT_LIMIT)
+        .min(BACKEND_LOG_BUFFER_CAPACITY);
+    BackendLogsResult {
+        ok: true,
+        reason: None,
+        lines: state.recent_backend_logs(limit),
+    }
+}
+
+/// Takes a fresh sample so callers always see current numbers; when sampling fails the last
+/// successful sample is still returned alongside the reason.
+#[tauri::command]
+pub(crate) fn desktop_bridge_get_backend_metrics(app_handle: AppHandle) -> BackendMetricsResult {
+    let state = app_handle.state::<BackendRegistry>().selected();
+    match state.sample_backend_metrics() {
+        Ok(metrics) => BackendMetricsResult {
+            ok: true,
+            reason: None,
+            metrics: Some(metrics),
+        },
+        Err(reason) => BackendMetricsResult {
+            ok: false,
+            reason: Some(reason),
+            metrics: state.latest_backend_metrics(),
+        },
+    }
+}
+
+fn restart_schedule_result(
+    state: &BackendState,
+    schedule: Result<backend::restart_schedule::RestartSchedule, String>,
+) -> BackendRestartScheduleResult {
+    match schedule {
+        Ok(schedule) => BackendRestartScheduleResult {
+            ok: true,
+            reason: None,
+            schedule: Some(schedule),
+            next_restart_at_ms: state.next_scheduled_restart_ms(),
+        },
+        Err(reason) => BackendRestartScheduleResult {
+            ok: false,
+            reason: Some(reason),
+            schedule: None,
+            next_restart_at_ms: state.next_scheduled_restart_ms(),
+        },
+    }
+}
+
+#[tauri::command]
+pub(crate) fn desktop_bridge_get_restart_schedule(
+    app_handle: AppHandle,
+) -> BackendRestartScheduleResult {
+    let state = app_handle.state::<BackendRegistry>().selected();
+    restart_schedule_result(&state, state.restart_schedule())
+}
+
+/// Persists the selected instance's restart schedule; `null` clears it. The running scheduler
+/// picks the change up on its next tick.
+#[tauri::command]
+pub(crate) fn desktop_bridge_set_restart_schedule(
+    app_handle: AppHandle,
+    schedule: Option<backend::restart_schedule::RestartSchedule>,
+) -> BackendRestartScheduleResult {
+    let state = app_handle.state::<BackendRegistry>().selected();
+    let result = schedule
+        .unwrap_or_default()
+        .validated()
+        .and_then(|schedule| {
+            state.write_restart_schedule(&schedule)?;
+            append_restart_log(&format!(
+                "backend restart schedule updated for instance={}: {:?}",
+                state.id, schedule
+            ));
+            Ok(schedule)
+        });
+    restart_schedule_result(&state, result)
+}
+
+fn proxy_config_result(
+    config: Result<proxy_config::ProxyConfig, String>,
+) -> DesktopProxyConfigResult {
+    match config {
+        Ok(config) => DesktopProxyConfigResult {
+            ok: true,
+            reason: None,
+            proxy: Some(config.masked()),
+        },
+        Err(reason) => DesktopProxyConfigResult {
+            ok: false,
+            reason: Some(reason),
+            proxy: None,
+        },
+    }
+}
+
+#[tauri::command]
+pub(crate) fn desktop_bridge_get_proxy_config() -> DesktopProxyConfigResult {
+    let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+    proxy_config_result(proxy_config::read_proxy_config(
+        packaged_root_dir.as_deref(),
+    ))
+}
+
+/// Persists the shared proxy setting; `null` goes back to the system proxy. The updater uses it
+/// on its next check, backends on their next start.
+#[tauri::command]
+pub(crate) fn desktop_bridge_set_proxy_config(
+    proxy: Option<proxy_config::ProxyConfig>,
+) -> DesktopProxyConfigResult {
+    let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+    let result = proxy
+        .unwrap_or_default()
+        .validated()
+        .and_then(|mut config| {
+            if let Ok(stored) = proxy_config::read_proxy_config(packaged_root_dir.as_deref()) {
+                config.restore_masked_password(&stored);
+            }
+            proxy_config::write_proxy_config(&config, packaged_root_dir.as_deref())?;
+            append_desktop_log(&format!(
+                "proxy configuration updated: mode={:?}, no_proxy={:?}",
+                config.mode, config.no_proxy
+            ));
+            Ok(config)
+        });
+    proxy_config_result(result)
+}
+
+fn backend_env_result(
+    state: &BackendState,
+    restart_required: Result<bool, String>,
+) -> BackendEnvResult {
+    let overrides = state.backend_env_overrides();
+    let variables = overrides
+        .as_ref()
+        .map(|overrides| overrides.masked_view())
+        .unwrap_or_default();
+    match restart_required.and_then(|restart_required| overrides.map(|_| restart_required)) {
+        Ok(restart_required) => BackendEnvResult {
+            ok: true,
+            reason: None,
+            variables,
+            restart_required,
+        },
+        Err(reason) => BackendEnvResult {
+            ok: false,
+            reason: Some(reason),
+            variables,
+            restart_required: false,
+        },
+    }
+}
+
+/// Lists the selected instance's extra backend environment; secret-looking values and proxy
+/// URL passwords are masked.
+#[tauri::command]
+pub(crate) fn desktop_bridge_get_backend_env(app_handle: AppHandle) -> BackendEnvResult {
+    let state = app_handle.state::<BackendRegistry>().selected();
+    let restart_required = state
+        .backend_env_overrides()
+        .map(|overrides| state.backend_env_restart_required(&overrides));
+    backend_env_result(&state, restart_required)
+}
+
+/// Persists one variable for the next backend start; `null` removes it from the inherited
+/// environment instead.
+#[tauri::command]
+pub(crate) fn desktop_bridge_set_backend_env(
+    app_handle: AppHandle,
+    name: String,
+    value: Option<String>,
+) -> BackendEnvResult {
+    let state = app_handle.state::<BackendRegistry>().selected();
+    let result = state.update_backend_env_overrides(|overrides| overrides.upsert(&name, value));
+    backend_env_result(&state, result)
+}
+
+/// Drops the override for `name`, so the backend inherits it from the desktop app again.
+#[tauri::command]
+pub(crate) fn desktop_bridge_remove_backend_env(
+    app_handle: AppHandle,
+    name: String,
+) -> BackendEnvResult {
+    let state = app_handle.state::<BackendRegistry>().selected();
+    let result = state.update_backend_env_overrides(|overrides| {
+        if overrides.remove(&name) {
+            Ok(())
+        } else {
+            Err(format!("No environment override named '{}'.", name.trim()))
+        }
+    });
+    backend_env_result(&state, result)
+}
+
+fn resolve_query_log_path(
+    app_handle: &AppHandle,
+    query: &log_query::LogQuery,
+) -> Result<PathBuf, String> {
+    if query.source == log_query::LogSource::Desktop {
+        return Ok(logging::resolve_desktop_log_path(
+            runtime_paths::default_packaged_root_dir(),
+            DESKTOP_LOG_FILE,
+        ));
+    }
+
+    let registry = app_handle.state::<BackendRegistry>();
+    let state = match query.instance_id.as_deref() {
+        Some(instance_id) => registry
+            .get(instance_id)
+            .ok_or_else(|| format!("Unknown backend instance: {instance_id}"))?,
+        None => registry.selected(),
+    };
+    Ok(state.backend_log_path())
+}
+
+#[tauri::command]
+pub(crate) async fn desktop_bridge_query_logs(
+    app_handle: AppHandle,
+    query: Option<log_query::LogQuery>,
+) -> LogQueryResult {
+    let query = query.unwrap_or_default();
+    let limit = query
+        .limit
+        .unwrap_or(LOG_QUERY_DEFAULT_LIMIT)
+        .clamp(1, LOG_QUERY_MAX_LIMIT);
+    let log_path = match resolve_query_log_path(&app_handle, &query) {
+        Ok(path) => path,
+        Err(error) => {
+            return LogQueryResult {
+                ok: false,
+                reason: Some(error),
+                entries: Vec::new(),
+                has_more: false,
+                next_offset: None,
+                cursor: None,
+            };
+        }
+    };
+
+    let offset = query.offset;
+    let following = query.after.is_some();
+    let page = tauri::async_runtime::spawn_blocking(move || {
+        log_query::query_log_files(&log_path, query.source, &query, limit)
+    })
+    .await
+    .map_err(|error| format!("Log query task failed: {error}"))
+    .and_then(|result| result);
+    match page {
+        Ok(page) => LogQueryResult {
+            ok: true,
+            reason: None,
+            next_offset: (page.has_more && !following).then_some(offset + page.entries.len()),
+            has_more: page.has_more,
+            entries: page.entries,
+            cursor: Some(page.cursor),
+        },
+        Err(error) => {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to query logs: {error}"),
+            );
+            LogQueryResult {
+                ok: false,
+                reason: Some(error),
+                entries: Vec::new(),
+                has_more: false,
+                next_offset: None,
+                cursor: None,
+            }
+        }
+    }
+}
+
+fn desktop_log_level_result(reason: Option<String>) -> DesktopLogLevelResult {
+    DesktopLogLevelResult {
+        ok: reason.is_none(),
+        reason,
+        level: effective_desktop_log_level().as_label(),
+        configured_level: configured_desktop_log_level().as_label(),
+        overridden: logging::runtime_min_log_level().is_some(),
+    }
+}
+
+#[tauri::command]
+pub(crate) fn desktop_bridge_get_log_level() -> DesktopLogLevelResult {
+    desktop_log_level_result(None)
+}
+
+/// Changes the desktop log threshold until the app exits; an empty level drops the override.
+#[tauri::command]
+pub(crate) fn desktop_bridge_set_log_level(level: Option<String>) -> DesktopLogLevelResult {
+    let requested = level
+        .as_deref()
+        .map(str::trim)
+        .filter(|level| !level.is_empty());
+    let Some(requested) = requested else {
+        logging::set_runtime_min_log_level(None);
+        append_desktop_log("desktop log level override cleared");
+        return desktop_log_level_result(None);
+    };
+    match logging::DesktopLogLevel::parse(requested) {
+        Some(parsed) => {
+            logging::set_runtime_min_log_level(Some(parsed));
+            append_desktop_log(&format!(
+                "desktop log level overridden to {}",
+                parsed.as_label()
+            ));
+            desktop_log_level_result(None)
+        }
+        None => desktop_log_level_result(Some(format!("Unknown log level: {requested}"))),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn desktop_bridge_export_diagnostics(
+    app_handle: AppHandle,
+    output_dir: Option<String>,
+) -> DiagnosticsExportResult {
+    let output_dir = output_dir
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+    diagnostics::run_export_diagnostics_task(app_handle, output_dir).await
+}
+
 #[tauri::command]
 pub(crate) fn desktop_bridge_open_external_url(url: String) -> BackendBridgeResult {
     let parsed = match parse_openable_url(&url) {
@@ -306,7 +800,10 @@ pub(crate) fn desktop_bridge_set_shell_locale(
             }
         }
         Err(error) => {
-            append_desktop_log(&format!("failed to persist shell locale: {error}"));
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to persist shell locale: {error}"),
+            );
             BackendBridgeResult {
                 ok: false,
                 reason: Some(error),
@@ -339,7 +836,10 @@ pub(crate) fn desktop_bridge_set_app_update_channel(
             map_update_channel_ok(channel)
         }
         Err(error) => {
-            append_desktop_log(&format!("failed to persist update channel: {error}"));
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to persist update channel: {error}"),
+            );
             map_update_channel_error(error)
         }
     }
@@ -414,15 +914,18 @@ pub(crate) async fn desktop_bridge_install_app_update(
         }
     };
 
-    let state = app_handle.state::<BackendState>();
+    let state = app_handle.state::<BackendRegistry>().selected();
     let stop_managed_backend = cfg!(target_os = "windows") && has_managed_backend_child(&state);
     let restart_backend_after_failed_install = if stop_managed_backend {
         let restart_plan = match state.resolve_launch_plan(&app_handle) {
             Ok(plan) => plan,
             Err(error) => {
-                append_desktop_log(&format!(
-                    "failed to resolve managed backend relaunch plan for update install recovery: {error}"
-                ));
+                append_desktop_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!(
+                        "failed to resolve managed backend relaunch plan for update install recovery: {error}"
+                    ),
+                );
                 return map_update_install_error(error);
             }
         };
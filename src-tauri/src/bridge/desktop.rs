@@ -3,7 +3,10 @@ use std::sync::OnceLock;
 use serde::Deserialize;
 use url::Url;
 
-use crate::{bridge::origin_policy, TRAY_RESTART_BACKEND_EVENT};
+use crate::{
+    bridge::origin_policy, BACKEND_ENV_CHANGED_EVENT, BACKEND_HEALTH_EVENT, BACKEND_LOG_EVENT,
+    BACKEND_METRICS_EVENT, BACKEND_STATE_EVENT, BACKEND_WATCHDOG_EVENT, TRAY_RESTART_BACKEND_EVENT,
+};
 
 static DESKTOP_BRIDGE_BOOTSTRAP_TEMPLATE: &str = include_str!("../bridge_bootstrap.js");
 static DESKTOP_BRIDGE_CHAT_TRANSPORT_CONTRACT_TEMPLATE: &str =
@@ -44,6 +47,12 @@ fn desktop_bridge_bootstrap_script() -> &'static str {
             let contract = desktop_bridge_chat_transport_contract();
             DESKTOP_BRIDGE_BOOTSTRAP_TEMPLATE
                 .replace("{TRAY_RESTART_BACKEND_EVENT}", TRAY_RESTART_BACKEND_EVENT)
+                .replace("{BACKEND_LOG_EVENT}", BACKEND_LOG_EVENT)
+                .replace("{BACKEND_METRICS_EVENT}", BACKEND_METRICS_EVENT)
+                .replace("{BACKEND_WATCHDOG_EVENT}", BACKEND_WATCHDOG_EVENT)
+                .replace("{BACKEND_HEALTH_EVENT}", BACKEND_HEALTH_EVENT)
+                .replace("{BACKEND_STATE_EVENT}", BACKEND_STATE_EVENT)
+                .replace("{BACKEND_ENV_CHANGED_EVENT}", BACKEND_ENV_CHANGED_EVENT)
                 .replace("{CHAT_TRANSPORT_MODE_STORAGE_KEY}", &contract.storage_key)
                 .replace("{CHAT_TRANSPORT_MODE_WEBSOCKET}", &contract.websocket_value)
         })
@@ -25,11 +25,30 @@
     SET_APP_UPDATE_CHANNEL: 'desktop_bridge_set_app_update_channel',
     RESTART_BACKEND: 'desktop_bridge_restart_backend',
     STOP_BACKEND: 'desktop_bridge_stop_backend',
+    GET_BACKEND_CRASH_HISTORY: 'desktop_bridge_get_backend_crash_history',
+    GET_BACKEND_LOGS: 'desktop_bridge_get_backend_logs',
+    EXPORT_DIAGNOSTICS: 'desktop_bridge_export_diagnostics',
+    GET_BACKEND_METRICS: 'desktop_bridge_get_backend_metrics',
+    GET_RESTART_SCHEDULE: 'desktop_bridge_get_restart_schedule',
+    SET_RESTART_SCHEDULE: 'desktop_bridge_set_restart_schedule',
+    GET_PROXY_CONFIG: 'desktop_bridge_get_proxy_config',
+    SET_PROXY_CONFIG: 'desktop_bridge_set_proxy_config',
+    GET_BACKEND_ENV: 'desktop_bridge_get_backend_env',
+    SET_BACKEND_ENV: 'desktop_bridge_set_backend_env',
+    REMOVE_BACKEND_ENV: 'desktop_bridge_remove_backend_env',
+    GET_LOG_LEVEL: 'desktop_bridge_get_log_level',
+    SET_LOG_LEVEL: 'desktop_bridge_set_log_level',
     OPEN_EXTERNAL_URL: 'desktop_bridge_open_external_url',
     CHECK_APP_UPDATE: 'desktop_bridge_check_app_update',
     INSTALL_APP_UPDATE: 'desktop_bridge_install_app_update',
   });
   const TRAY_RESTART_BACKEND_EVENT = '{TRAY_RESTART_BACKEND_EVENT}';
+  const BACKEND_LOG_EVENT = '{BACKEND_LOG_EVENT}';
+  const BACKEND_METRICS_EVENT = '{BACKEND_METRICS_EVENT}';
+  const BACKEND_WATCHDOG_EVENT = '{BACKEND_WATCHDOG_EVENT}';
+  const BACKEND_HEALTH_EVENT = '{BACKEND_HEALTH_EVENT}';
+  const BACKEND_STATE_EVENT = '{BACKEND_STATE_EVENT}';
+  const BACKEND_ENV_CHANGED_EVENT = '{BACKEND_ENV_CHANGED_EVENT}';
 
   const invokeBridge = async (command, payload = {}) => {
     try {
@@ -148,6 +167,31 @@
     }
   };
 
+  const createPayloadListener = (eventName, description) => async (callback) => {
+    if (typeof callback !== 'function') return () => {};
+    try {
+      const unlisten = await createEventListener(eventName, (event) => {
+        try {
+          callback(event?.payload);
+        } catch {}
+      });
+      return typeof unlisten === 'function' ? unlisten : () => {};
+    } catch (error) {
+      console.warn(`Failed to listen for ${description} event`, error);
+      return () => {};
+    }
+  };
+
+  const onBackendLog = createPayloadListener(BACKEND_LOG_EVENT, 'backend log');
+  const onBackendMetrics = createPayloadListener(BACKEND_METRICS_EVENT, 'backend metrics');
+  const onBackendWatchdog = createPayloadListener(BACKEND_WATCHDOG_EVENT, 'backend watchdog');
+  const onBackendHealth = createPayloadListener(BACKEND_HEALTH_EVENT, 'backend health');
+  const onBackendState = createPayloadListener(BACKEND_STATE_EVENT, 'backend state');
+  const onBackendEnvChanged = createPayloadListener(
+    BACKEND_ENV_CHANGED_EVENT,
+    'backend env changed'
+  );
+
   const TOKEN_STORAGE_KEY = 'token';
   const SHELL_LOCALE_STORAGE_KEY = 'astrbot-locale';
   // Values are injected from the shared desktop bridge transport contract.
@@ -751,6 +795,50 @@
       });
     },
     stopBackend: () => invokeBridge(BRIDGE_COMMANDS.STOP_BACKEND),
+    getBackendCrashHistory: (limit = null) =>
+      invokeBridge(BRIDGE_COMMANDS.GET_BACKEND_CRASH_HISTORY, {
+        limit: Number.isInteger(limit) && limit > 0 ? limit : null,
+      }),
+    getBackendLogs: (limit = null) =>
+      invokeBridge(BRIDGE_COMMANDS.GET_BACKEND_LOGS, {
+        limit: Number.isInteger(limit) && limit > 0 ? limit : null,
+      }),
+    onBackendLog,
+    getBackendMetrics: () => invokeBridge(BRIDGE_COMMANDS.GET_BACKEND_METRICS),
+    onBackendMetrics,
+    onBackendWatchdog,
+    onBackendHealth,
+    onBackendState,
+    getRestartSchedule: () => invokeBridge(BRIDGE_COMMANDS.GET_RESTART_SCHEDULE),
+    setRestartSchedule: (schedule = null) =>
+      invokeBridge(BRIDGE_COMMANDS.SET_RESTART_SCHEDULE, {
+        schedule: schedule && typeof schedule === 'object' ? schedule : null,
+      }),
+    getProxyConfig: () => invokeBridge(BRIDGE_COMMANDS.GET_PROXY_CONFIG),
+    setProxyConfig: (proxy = null) =>
+      invokeBridge(BRIDGE_COMMANDS.SET_PROXY_CONFIG, {
+        proxy: proxy && typeof proxy === 'object' ? proxy : null,
+      }),
+    getBackendEnv: () => invokeBridge(BRIDGE_COMMANDS.GET_BACKEND_ENV),
+    setBackendEnv: (name, value = null) =>
+      invokeBridge(BRIDGE_COMMANDS.SET_BACKEND_ENV, {
+        name: typeof name === 'string' ? name : String(name ?? ''),
+        value: value === null || value === undefined ? null : String(value),
+      }),
+    removeBackendEnv: (name) =>
+      invokeBridge(BRIDGE_COMMANDS.REMOVE_BACKEND_ENV, {
+        name: typeof name === 'string' ? name : String(name ?? ''),
+      }),
+    onBackendEnvChanged,
+    getLogLevel: () => invokeBridge(BRIDGE_COMMANDS.GET_LOG_LEVEL),
+    setLogLevel: (level = null) =>
+      invokeBridge(BRIDGE_COMMANDS.SET_LOG_LEVEL, {
+        level: typeof level === 'string' && level.trim() ? level : null,
+      }),
+    exportDiagnostics: (outputDir = null) =>
+      invokeBridge(BRIDGE_COMMANDS.EXPORT_DIAGNOSTICS, {
+        outputDir: typeof outputDir === 'string' && outputDir.trim() ? outputDir : null,
+      }),
     openExternalUrl: (url) => {
       const rawUrl = typeof url === 'string' ? url : String(url ?? '');
       if (!rawUrl.trim()) {
@@ -1,9 +1,16 @@
 use std::{
-    env,
+    env, fs,
+    io::Write,
     path::{Path, PathBuf},
 };
 
-pub(crate) fn resolve_desktop_state_path(packaged_root_dir: Option<&Path>) -> Option<PathBuf> {
+use serde_json::{Map, Value};
+
+/// Shell-wide state file that holds the profile registry and other settings shared by all
+/// profiles.
+pub(crate) fn resolve_shared_desktop_state_path(
+    packaged_root_dir: Option<&Path>,
+) -> Option<PathBuf> {
     resolve_desktop_state_path_with_root(
         env::var(crate::ASTRBOT_ROOT_ENV).ok().as_deref(),
         packaged_root_dir,
@@ -28,9 +35,123 @@ pub(crate) fn resolve_desktop_state_path_with_root(
     packaged_root_dir.map(|root| root.join("data").join("desktop_state.json"))
 }
 
+pub(crate) fn read_shared_desktop_state_field(
+    field: &str,
+    packaged_root_dir: Option<&Path>,
+) -> Option<Value> {
+    let state_path = resolve_shared_desktop_state_path(packaged_root_dir)?;
+    read_desktop_state_field_at(&state_path, field)
+}
+
+pub(crate) fn write_shared_desktop_state_field(
+    field: &str,
+    value: Option<Value>,
+    packaged_root_dir: Option<&Path>,
+) -> Result<(), String> {
+    let Some(state_path) = resolve_shared_desktop_state_path(packaged_root_dir) else {
+        return Err(format!(
+            "Desktop state path is unavailable; cannot persist {field}."
+        ));
+    };
+    write_desktop_state_field_at(&state_path, field, value)
+}
+
+pub(crate) fn read_desktop_state_field_at(path: &Path, field: &str) -> Option<Value> {
+    let raw = fs::read_to_string(path).ok()?;
+    let parsed: Value = serde_json::from_str(&raw).ok()?;
+    parsed.get(field).cloned()
+}
+
+fn load_state_object(path: &Path) -> Result<Map<String, Value>, String> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(error) => {
+            return Err(format!(
+                "Failed to read desktop state {}: {}",
+                path.display(),
+                error
+            ));
+        }
+    };
+
+    match serde_json::from_str::<Value>(&raw) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) | Err(_) => {
+            crate::append_desktop_log(&format!(
+                "desktop state {} is not a JSON object; resetting state file",
+                path.display()
+            ));
+            Ok(Map::new())
+        }
+    }
+}
+
+pub(crate) fn write_desktop_state_field_at(
+    path: &Path,
+    field: &str,
+    value: Option<Value>,
+) -> Result<(), String> {
+    let mut state = load_state_object(path)?;
+    match value {
+        Some(value) => {
+            state.insert(field.to_string(), value);
+        }
+        None => {
+            state.remove(field);
+        }
+    }
+
+    if let Some(parent_dir) = path.parent() {
+        fs::create_dir_all(parent_dir).map_err(|error| {
+            format!(
+                "Failed to create desktop state directory {}: {}",
+                parent_dir.display(),
+                error
+            )
+        })?;
+    }
+    let serialized = serde_json::to_string_pretty(&Value::Object(state))
+        .map_err(|error| format!("Failed to serialize desktop state: {error}"))?;
+    let tmp_name = format!(
+        "{}.tmp",
+        path.file_name()
+            .map(|value| value.to_string_lossy())
+            .unwrap_or_default()
+    );
+    let tmp_path = path.with_file_name(tmp_name);
+    let mut file = fs::File::create(&tmp_path).map_err(|error| {
+        format!(
+            "Failed to create temporary desktop state file {}: {}",
+            tmp_path.display(),
+            error
+        )
+    })?;
+    file.write_all(serialized.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|error| {
+            format!(
+                "Failed to write temporary desktop state file {}: {}",
+                tmp_path.display(),
+                error
+            )
+        })?;
+    fs::rename(&tmp_path, path).map_err(|error| {
+        format!(
+            "Failed to atomically replace desktop state file {}: {}",
+            path.display(),
+            error
+        )
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use super::resolve_desktop_state_path_with_root;
+    use super::{
+        read_desktop_state_field_at, resolve_desktop_state_path_with_root,
+        write_desktop_state_field_at,
+    };
+    use serde_json::json;
     use std::path::PathBuf;
 
     #[test]
@@ -65,4 +186,30 @@ mod tests {
             Some(PathBuf::from("/tmp/packaged-root/data/desktop_state.json"))
         );
     }
+
+    #[test]
+    fn write_state_field_preserves_unrelated_fields_and_removes_cleared_field() {
+        let temp_dir = tempfile::TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join("data").join("desktop_state.json");
+        std::fs::create_dir_all(path.parent().unwrap()).expect("create data dir");
+        std::fs::write(&path, r#"{"locale":"zh-CN"}"#).expect("seed state");
+
+        write_desktop_state_field_at(&path, "dashboardPort", Some(json!(6200)))
+            .expect("write field");
+        assert_eq!(
+            read_desktop_state_field_at(&path, "dashboardPort"),
+            Some(json!(6200))
+        );
+        assert_eq!(
+            read_desktop_state_field_at(&path, "locale"),
+            Some(json!("zh-CN"))
+        );
+
+        write_desktop_state_field_at(&path, "dashboardPort", None).expect("clear field");
+        assert_eq!(read_desktop_state_field_at(&path, "dashboardPort"), None);
+        assert_eq!(
+            read_desktop_state_field_at(&path, "locale"),
+            Some(json!("zh-CN"))
+        );
+    }
 }
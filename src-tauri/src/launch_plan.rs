@@ -27,7 +27,10 @@ fn resolve_launch_startup_heartbeat_path(
     )
 }
 
-pub fn resolve_custom_launch(custom_cmd: String) -> Result<LaunchPlan, String> {
+pub fn resolve_custom_launch(
+    custom_cmd: String,
+    root_dir: Option<PathBuf>,
+) -> Result<LaunchPlan, String> {
     let mut pieces = shlex::split(&custom_cmd)
         .ok_or_else(|| format!("Invalid ASTRBOT_BACKEND_CMD: {custom_cmd}"))?;
     if pieces.is_empty() {
@@ -40,7 +43,6 @@ pub fn resolve_custom_launch(custom_cmd: String) -> Result<LaunchPlan, String> {
         .ok()
         .or_else(runtime_paths::detect_astrbot_source_root)
         .unwrap_or_else(runtime_paths::workspace_root_dir);
-    let root_dir = env::var(crate::ASTRBOT_ROOT_ENV).ok().map(PathBuf::from);
     let webui_dir = env::var("ASTRBOT_WEBUI_DIR").ok().map(PathBuf::from);
     let startup_heartbeat_path = resolve_launch_startup_heartbeat_path(root_dir.as_deref(), false);
 
@@ -55,22 +57,30 @@ pub fn resolve_custom_launch(custom_cmd: String) -> Result<LaunchPlan, String> {
     })
 }
 
+/// Location of the bundled backend `runtime-manifest.json`, if this build ships one.
+pub fn resolve_packaged_manifest_path<F>(app: &AppHandle, log: F) -> Option<PathBuf>
+where
+    F: Fn(&str),
+{
+    let manifest_relative_path =
+        build_packaged_resource_relative_path(BACKEND_RESOURCE_ALIAS, "runtime-manifest.json");
+    let manifest_relative_path_string = manifest_relative_path.to_string_lossy().to_string();
+    runtime_paths::resolve_resource_path(app, &manifest_relative_path_string, log)
+        .filter(|path| path.is_file())
+}
+
 pub fn resolve_packaged_launch<F>(
     app: &AppHandle,
+    root_dir_override: Option<PathBuf>,
     default_shell_locale: &'static str,
     log: F,
 ) -> Result<Option<LaunchPlan>, String>
 where
     F: Fn(&str) + Copy,
 {
-    let manifest_relative_path =
-        build_packaged_resource_relative_path(BACKEND_RESOURCE_ALIAS, "runtime-manifest.json");
-    let manifest_relative_path_string = manifest_relative_path.to_string_lossy().to_string();
-    let manifest_path =
-        match runtime_paths::resolve_resource_path(app, &manifest_relative_path_string, log) {
-            Some(path) if path.is_file() => path,
-            _ => return Ok(None),
-        };
+    let Some(manifest_path) = resolve_packaged_manifest_path(app, log) else {
+        return Ok(None);
+    };
     let backend_dir = manifest_path
         .parent()
         .ok_or_else(|| format!("Invalid backend manifest path: {}", manifest_path.display()))?;
@@ -122,10 +132,7 @@ where
         ));
     }
 
-    let root_dir = env::var(crate::ASTRBOT_ROOT_ENV)
-        .map(PathBuf::from)
-        .ok()
-        .or_else(runtime_paths::default_packaged_root_dir);
+    let root_dir = root_dir_override.or_else(runtime_paths::default_packaged_root_dir);
     let cwd = env::var("ASTRBOT_BACKEND_CWD")
         .map(PathBuf::from)
         .unwrap_or_else(|_| {
@@ -170,7 +177,7 @@ where
     Ok(Some(plan))
 }
 
-pub fn resolve_dev_launch() -> Result<LaunchPlan, String> {
+pub fn resolve_dev_launch(root_dir: Option<PathBuf>) -> Result<LaunchPlan, String> {
     let source_root = runtime_paths::detect_astrbot_source_root().ok_or_else(|| {
         "Cannot locate AstrBot source directory. Set ASTRBOT_SOURCE_DIR, or configure ASTRBOT_SOURCE_GIT_URL/ASTRBOT_SOURCE_GIT_REF and run resource prepare.".to_string()
     })?;
@@ -191,7 +198,6 @@ pub fn resolve_dev_launch() -> Result<LaunchPlan, String> {
         args.push("--webui-dir".to_string());
         args.push(path.to_string_lossy().to_string());
     }
-    let root_dir = env::var(crate::ASTRBOT_ROOT_ENV).ok().map(PathBuf::from);
     let startup_heartbeat_path = resolve_launch_startup_heartbeat_path(root_dir.as_deref(), false);
 
     Ok(LaunchPlan {
@@ -211,28 +217,6 @@ pub fn resolve_dev_launch() -> Result<LaunchPlan, String> {
 mod tests {
     use super::*;
 
-    struct EnvVarGuard {
-        key: &'static str,
-        previous: Option<String>,
-    }
-
-    impl EnvVarGuard {
-        fn set(key: &'static str, value: &str) -> Self {
-            let previous = env::var(key).ok();
-            env::set_var(key, value);
-            Self { key, previous }
-        }
-    }
-
-    impl Drop for EnvVarGuard {
-        fn drop(&mut self) {
-            match &self.previous {
-                Some(value) => env::set_var(self.key, value),
-                None => env::remove_var(self.key),
-            }
-        }
-    }
-
     #[test]
     fn build_packaged_resource_relative_path_joins_alias_and_leaf_name() {
         assert_eq!(
@@ -247,9 +231,11 @@ mod tests {
 
     #[test]
     fn resolve_custom_launch_sets_startup_heartbeat_path_from_root_dir() {
-        let _root_guard = EnvVarGuard::set(crate::ASTRBOT_ROOT_ENV, "/tmp/astrbot-root");
-
-        let plan = resolve_custom_launch("python main.py".to_string()).expect("custom plan");
+        let plan = resolve_custom_launch(
+            "python main.py".to_string(),
+            Some(PathBuf::from("/tmp/astrbot-root")),
+        )
+        .expect("custom plan");
 
         assert_eq!(
             plan.startup_heartbeat_path,
@@ -1,13 +1,14 @@
-use crate::{append_desktop_log, BackendState};
+use crate::{append_desktop_log_at, logging::DesktopLogLevel, BackendState};
 
 impl BackendState {
     pub(crate) fn mark_quitting(&self) {
         match self.exit_state.lock() {
             Ok(mut guard) => guard.mark_quitting(),
             Err(error) => {
-                append_desktop_log(&format!(
-                    "exit state lock poisoned when marking quitting: {error}"
-                ));
+                append_desktop_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("exit state lock poisoned when marking quitting: {error}"),
+                );
                 error.into_inner().mark_quitting();
             }
         }
@@ -17,9 +18,10 @@ impl BackendState {
         match self.exit_state.lock() {
             Ok(guard) => guard.is_quitting(),
             Err(error) => {
-                append_desktop_log(&format!(
-                    "exit state lock poisoned when reading quitting state: {error}"
-                ));
+                append_desktop_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("exit state lock poisoned when reading quitting state: {error}"),
+                );
                 error.into_inner().is_quitting()
             }
         }
@@ -29,9 +31,10 @@ impl BackendState {
         match self.exit_state.lock() {
             Ok(mut guard) => guard.try_begin_cleanup(),
             Err(error) => {
-                append_desktop_log(&format!(
-                    "exit state lock poisoned when beginning cleanup: {error}"
-                ));
+                append_desktop_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("exit state lock poisoned when beginning cleanup: {error}"),
+                );
                 error.into_inner().try_begin_cleanup()
             }
         }
@@ -41,9 +44,10 @@ impl BackendState {
         match self.exit_state.lock() {
             Ok(mut guard) => guard.allow_next_exit_request(),
             Err(error) => {
-                append_desktop_log(&format!(
-                    "exit state lock poisoned when allowing next exit request: {error}"
-                ));
+                append_desktop_log_at(
+                    DesktopLogLevel::Error,
+                    &format!("exit state lock poisoned when allowing next exit request: {error}"),
+                );
                 error.into_inner().allow_next_exit_request();
             }
         }
@@ -53,9 +57,12 @@ impl BackendState {
         match self.exit_state.lock() {
             Ok(mut guard) => guard.take_exit_request_allowance(),
             Err(error) => {
-                append_desktop_log(&format!(
-                    "exit state lock poisoned when taking exit request allowance: {error}"
-                ));
+                append_desktop_log_at(
+                    DesktopLogLevel::Error,
+                    &format!(
+                        "exit state lock poisoned when taking exit request allowance: {error}"
+                    ),
+                );
                 error.into_inner().take_exit_request_allowance()
             }
         }
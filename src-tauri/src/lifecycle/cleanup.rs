@@ -1,4 +1,4 @@
-use crate::BackendState;
+use crate::{BackendRegistry, BackendState};
 
 #[derive(Debug, Clone, Copy)]
 pub enum ExitTrigger {
@@ -24,17 +24,20 @@ where
     false
 }
 
-pub fn stop_backend_for_exit<F>(state: &BackendState, trigger: ExitTrigger, log: F)
+pub fn stop_backend_for_exit<F>(registry: &BackendRegistry, trigger: ExitTrigger, log: F)
 where
-    F: Fn(&str),
+    F: Fn(&str) + Sync,
 {
     let stop_failure_prefix = match trigger {
         ExitTrigger::ExitRequested => "backend graceful stop on ExitRequested failed",
         ExitTrigger::ExitFallback => "backend fallback stop on Exit failed",
     };
-    if let Err(error) = state.stop_backend() {
-        log(&format!("{stop_failure_prefix}: {error}"));
-    }
+    registry.mark_all_quitting();
+    registry.stop_all(|instance_id, error| {
+        log(&format!(
+            "{stop_failure_prefix} for instance {instance_id}: {error}"
+        ));
+    });
 
     if matches!(trigger, ExitTrigger::ExitRequested) {
         log("backend stop finished, exiting desktop process");
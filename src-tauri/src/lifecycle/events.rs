@@ -1,6 +1,6 @@
 use tauri::{AppHandle, Manager};
 
-use crate::{append_shutdown_log, lifecycle::cleanup, BackendState};
+use crate::{append_shutdown_log, lifecycle::cleanup, BackendRegistry};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ExitRequestedDecision {
@@ -17,7 +17,8 @@ fn decide_exit_requested_flow(has_exit_request_allowance: bool) -> ExitRequested
 }
 
 pub fn handle_exit_requested(app_handle: &AppHandle, api: &tauri::ExitRequestApi) {
-    let state = app_handle.state::<BackendState>();
+    let registry = app_handle.state::<BackendRegistry>();
+    let state = registry.selected();
     match decide_exit_requested_flow(state.take_exit_request_allowance()) {
         ExitRequestedDecision::AllowImmediateExit => {
             append_shutdown_log("exit request allowed to pass through after backend cleanup");
@@ -34,24 +35,24 @@ pub fn handle_exit_requested(app_handle: &AppHandle, api: &tauri::ExitRequestApi
         return;
     }
 
-    append_shutdown_log("exit requested, stopping backend asynchronously");
+    append_shutdown_log("exit requested, stopping backend instances asynchronously");
     let app_handle_cloned = app_handle.clone();
     tauri::async_runtime::spawn_blocking(move || {
-        let state = app_handle_cloned.state::<BackendState>();
+        let registry = app_handle_cloned.state::<BackendRegistry>();
         cleanup::stop_backend_for_exit(
-            &state,
+            &registry,
             cleanup::ExitTrigger::ExitRequested,
             append_shutdown_log,
         );
-        state.allow_next_exit_request();
+        registry.selected().allow_next_exit_request();
         app_handle_cloned.exit(0);
     });
 }
 
 pub fn handle_exit_event(app_handle: &AppHandle) {
-    let state = app_handle.state::<BackendState>();
+    let registry = app_handle.state::<BackendRegistry>();
     if !cleanup::try_begin_exit_cleanup(
-        &state,
+        &registry.selected(),
         cleanup::ExitTrigger::ExitFallback,
         append_shutdown_log,
     ) {
@@ -60,7 +61,7 @@ pub fn handle_exit_event(app_handle: &AppHandle) {
 
     append_shutdown_log("exit event triggered fallback backend cleanup");
     cleanup::stop_backend_for_exit(
-        &state,
+        &registry,
         cleanup::ExitTrigger::ExitFallback,
         append_shutdown_log,
     );
@@ -0,0 +1,606 @@
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::log_retention;
+
+const DESKTOP_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f %z";
+const BACKEND_TIMESTAMP_FORMATS: [&str; 3] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S,%3f",
+    "%Y-%m-%d %H:%M:%S",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LogSource {
+    #[default]
+    Desktop,
+    Backend,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct LogQuery {
+    pub(crate) source: LogSource,
+    pub(crate) instance_id: Option<String>,
+    pub(crate) categories: Vec<String>,
+    pub(crate) levels: Vec<String>,
+    pub(crate) contains: Option<String>,
+    pub(crate) since_ms: Option<i64>,
+    pub(crate) until_ms: Option<i64>,
+    pub(crate) offset: usize,
+    pub(crate) limit: Option<usize>,
+    /// Set while following: only entries appended to the active file after this cursor are read.
+    pub(crate) after: Option<LogCursor>,
+}
+
+/// Byte offset just past the last complete line of the active log file, with the number of
+/// lines before it so appended entries keep their line numbers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogCursor {
+    pub(crate) offset: u64,
+    pub(crate) line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogEntry {
+    pub(crate) file: String,
+    pub(crate) line_number: usize,
+    pub(crate) timestamp_ms: Option<i64>,
+    pub(crate) category: Option<String>,
+    pub(crate) level: Option<String>,
+    pub(crate) message: String,
+}
+
+/// One page of matching entries in chronological order; `offset` counts entries back from the
+/// newest match, so page 0 is the tail of the log.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct LogPage {
+    pub(crate) entries: Vec<LogEntry>,
+    pub(crate) has_more: bool,
+    pub(crate) cursor: LogCursor,
+}
+
+struct LogFilter {
+    categories: Vec<String>,
+    levels: Vec<String>,
+    contains: Option<String>,
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+}
+
+impl LogFilter {
+    fn from_query(query: &LogQuery) -> Self {
+        let normalize = |values: &[String]| {
+            values
+                .iter()
+                .map(|value| value.trim().to_ascii_lowercase())
+                .filter(|value| !value.is_empty())
+                .collect::<Vec<_>>()
+        };
+        Self {
+            categories: normalize(&query.categories),
+            levels: normalize(&query.levels)
+                .iter()
+                .filter_map(|level| normalize_level(level))
+                .map(str::to_string)
+                .collect(),
+            contains: query
+                .contains
+                .as_deref()
+                .map(|value| value.trim().to_lowercase())
+                .filter(|value| !value.is_empty()),
+            since_ms: query.since_ms,
+            until_ms: query.until_ms,
+        }
+    }
+
+    fn matches(&self, entry: &LogEntry) -> bool {
+        if !self.categories.is_empty()
+            && !entry
+                .category
+                .as_ref()
+                .is_some_and(|category| self.categories.contains(category))
+        {
+            return false;
+        }
+        if !self.levels.is_empty() {
+            let level = entry.level.as_deref().unwrap_or("info");
+            if !self.levels.iter().any(|candidate| candidate == level) {
+                return false;
+            }
+        }
+        if self.since_ms.is_some() || self.until_ms.is_some() {
+            let Some(timestamp_ms) = entry.timestamp_ms else {
+                return false;
+            };
+            if self.since_ms.is_some_and(|since| timestamp_ms < since)
+                || self.until_ms.is_some_and(|until| timestamp_ms > until)
+            {
+                return false;
+            }
+        }
+        if let Some(needle) = self.contains.as_deref() {
+            if !entry.message.to_lowercase().contains(needle) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Pages through `path` and its rotated backups, newest entries first. With `query.after` set,
+/// only the entries appended to `path` since that cursor are returned instead.
+pub(crate) fn query_log_files(
+    path: &Path,
+    source: LogSource,
+    query: &LogQuery,
+    limit: usize,
+) -> Result<LogPage, String> {
+    let filter = LogFilter::from_query(query);
+    if let Some(after) = query.after {
+        return query_appended_entries(path, source, &filter, after, limit);
+    }
+    let wanted = query.offset.saturating_add(limit);
+    let mut newest_first = Vec::new();
+    let mut has_more = false;
+    let mut cursor = LogCursor::default();
+
+    'files: for file_path in log_retention::log_file_chain(path) {
+        let raw = log_retention::read_log_file(&file_path)
+            .map_err(|error| format!("Failed to read log {}: {}", file_path.display(), error))?;
+        let raw = if file_path == path {
+            // A trailing partial line is left for the next follow read to pick up whole.
+            let complete = complete_lines(&raw);
+            cursor = LogCursor {
+                offset: complete.len() as u64,
+                line: count_lines(complete),
+            };
+            complete
+        } else {
+            &raw
+        };
+        let text = String::from_utf8_lossy(raw);
+        let entries = parse_log_entries(&text, &display_file_name(&file_path), source, 0);
+        for entry in entries.into_iter().rev() {
+            if !filter.matches(&entry) {
+                continue;
+            }
+            if newest_first.len() == wanted {
+                has_more = true;
+                break 'files;
+            }
+            newest_first.push(entry);
+        }
+    }
+
+    let mut entries: Vec<LogEntry> = newest_first.into_iter().skip(query.offset).collect();
+    entries.reverse();
+    Ok(LogPage {
+        entries,
+        has_more,
+        cursor,
+    })
+}
+
+/// Reads the active file from `after` onwards; `has_more` reports that more than `limit`
+/// entries matched and only the newest were kept.
+fn query_appended_entries(
+    path: &Path,
+    source: LogSource,
+    filter: &LogFilter,
+    after: LogCursor,
+    limit: usize,
+) -> Result<LogPage, String> {
+    let read_error = |error: io::Error| format!("Failed to read log {}: {}", path.display(), error);
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(LogPage::default()),
+        Err(error) => return Err(read_error(error)),
+    };
+    // A file shorter than the cursor has been rotated since; all of it is new.
+    let after = if file.metadata().map_err(read_error)?.len() < after.offset {
+        LogCursor::default()
+    } else {
+        after
+    };
+    file.seek(SeekFrom::Start(after.offset))
+        .map_err(read_error)?;
+    let mut raw = Vec::new();
+    file.read_to_end(&mut raw).map_err(read_error)?;
+
+    let complete = complete_lines(&raw);
+    let text = String::from_utf8_lossy(complete);
+    let mut entries: Vec<LogEntry> =
+        parse_log_entries(&text, &display_file_name(path), source, after.line)
+            .into_iter()
+            .filter(|entry| filter.matches(entry))
+            .collect();
+    let has_more = entries.len() > limit;
+    if has_more {
+        entries.drain(..entries.len() - limit);
+    }
+    Ok(LogPage {
+        entries,
+        has_more,
+        cursor: LogCursor {
+            offset: after.offset + complete.len() as u64,
+            line: after.line + count_lines(complete),
+        },
+    })
+}
+
+fn complete_lines(raw: &[u8]) -> &[u8] {
+    match raw.iter().rposition(|byte| *byte == b'\n') {
+        Some(index) => &raw[..=index],
+        None => &[],
+    }
+}
+
+fn count_lines(raw: &[u8]) -> usize {
+    raw.iter().filter(|byte| **byte == b'\n').count()
+}
+
+fn display_file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Splits a log file into entries; lines without a recognizable header (tracebacks, wrapped
+/// output) are folded into the entry above them.
+fn parse_log_entries(
+    text: &str,
+    file: &str,
+    source: LogSource,
+    first_line: usize,
+) -> Vec<LogEntry> {
+    let mut entries: Vec<LogEntry> = Vec::new();
+    for (index, raw_line) in text.lines().enumerate() {
+        let line = strip_ansi_codes(raw_line);
+        let header = match source {
+            LogSource::Desktop => parse_desktop_header(&line),
+            LogSource::Backend => parse_backend_header(&line),
+        };
+        match (header, entries.last_mut()) {
+            (None, Some(previous)) => {
+                previous.message.push('\n');
+                previous.message.push_str(&line);
+            }
+            (header, _) => {
+                let header = header.unwrap_or_default();
+                entries.push(LogEntry {
+                    file: file.to_string(),
+                    line_number: first_line + index + 1,
+                    timestamp_ms: header.timestamp_ms,
+                    category: header.category,
+                    level: header.level,
+                    message: header.message.unwrap_or(line),
+                });
+            }
+        }
+    }
+    entries
+}
+
+#[derive(Debug, Default)]
+struct LogHeader {
+    timestamp_ms: Option<i64>,
+    category: Option<String>,
+    level: Option<String>,
+    /// Display text when it differs from the raw line (structured JSON records).
+    message: Option<String>,
+}
+
+/// `[2024-05-01 12:00:00.000 +0800] [startup] message`, as written by `append_desktop_log`,
+/// or one JSON record per line when the structured format is enabled.
+fn parse_desktop_header(line: &str) -> Option<LogHeader> {
+    if line.starts_with('{') {
+        return parse_desktop_json_record(line);
+    }
+    let (timestamp, rest) = take_bracketed(line)?;
+    let timestamp = DateTime::parse_from_str(timestamp, DESKTOP_TIMESTAMP_FORMAT).ok()?;
+    let (category, rest) = match take_bracketed(rest.trim_start()) {
+        Some((category, rest)) => (Some(category.to_string()), rest),
+        None => (None, rest),
+    };
+    // Lines written before levels existed carry no third bracket.
+    let level = take_bracketed(rest.trim_start())
+        .and_then(|(level, _)| normalize_level(level))
+        .map(str::to_string);
+    Some(LogHeader {
+        timestamp_ms: Some(timestamp.timestamp_millis()),
+        category,
+        level,
+        message: None,
+    })
+}
+
+fn parse_desktop_json_record(line: &str) -> Option<LogHeader> {
+    let record: Value = serde_json::from_str(line).ok()?;
+    let field = |name: &str| record.get(name).and_then(Value::as_str);
+    let timestamp = field("timestamp").and_then(|raw| DateTime::parse_from_rfc3339(raw).ok());
+    let category = field("category").map(str::to_string);
+    let message = field("message")?;
+    let level = field("level").and_then(normalize_level);
+    Some(LogHeader {
+        timestamp_ms: timestamp.map(|timestamp| timestamp.timestamp_millis()),
+        level: level.map(str::to_string),
+        message: Some(format!(
+            "[{}] [{}] [{}] {}",
+            field("timestamp").unwrap_or("-"),
+            category.as_deref().unwrap_or("-"),
+            level.unwrap_or("-"),
+            message
+        )),
+        category,
+    })
+}
+
+/// Backend lines count as a new entry when they open with a timestamp or carry a level tag
+/// such as `[INFO]` / `[ERRO]` among their leading bracketed tokens.
+fn parse_backend_header(line: &str) -> Option<LogHeader> {
+    let mut header = LogHeader::default();
+    let mut rest = line.trim_start();
+    for _ in 0..4 {
+        let Some((token, remainder)) = take_bracketed(rest) else {
+            break;
+        };
+        if header.timestamp_ms.is_none() {
+            header.timestamp_ms = parse_backend_timestamp(token);
+        }
+        if header.level.is_none() {
+            header.level = normalize_level(token).map(str::to_string);
+        }
+        rest = remainder.trim_start();
+    }
+    if header.level.is_none() {
+        header.level = rest
+            .split_whitespace()
+            .next()
+            .map(|token| token.trim_end_matches(':'))
+            .and_then(normalize_level)
+            .map(str::to_string);
+    }
+    (header.timestamp_ms.is_some() || header.level.is_some()).then_some(header)
+}
+
+fn parse_backend_timestamp(token: &str) -> Option<i64> {
+    BACKEND_TIMESTAMP_FORMATS.iter().find_map(|format| {
+        let naive = NaiveDateTime::parse_from_str(token, format).ok()?;
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|timestamp| timestamp.timestamp_millis())
+    })
+}
+
+fn normalize_level(token: &str) -> Option<&'static str> {
+    match token.trim().to_ascii_uppercase().as_str() {
+        "TRACE" => Some("trace"),
+        "DEBUG" | "DBUG" => Some("debug"),
+        "INFO" => Some("info"),
+        "WARN" | "WARNING" => Some("warn"),
+        "ERROR" | "ERRO" | "CRITICAL" | "CRIT" | "FATAL" => Some("error"),
+        _ => None,
+    }
+}
+
+fn take_bracketed(text: &str) -> Option<(&str, &str)> {
+    let inner = text.strip_prefix('[')?;
+    let end = inner.find(']')?;
+    Some((&inner[..end], &inner[end + 1..]))
+}
+
+fn strip_ansi_codes(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '\u{1b}' && chars.peek() == Some(&'[') {
+            chars.next();
+            for terminator in chars.by_ref() {
+                if terminator.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+            continue;
+        }
+        output.push(ch);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, io::Write};
+    use tempfile::TempDir;
+
+    fn desktop_line(minute: u32, category: &str, message: &str) -> String {
+        format!("[2024-05-01 12:{minute:02}:00.000 +0000] [{category}] {message}\n")
+    }
+
+    #[test]
+    fn desktop_entries_page_backwards_across_rotated_backups() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join("desktop.log");
+        fs::write(
+            path.with_file_name("desktop.log.1"),
+            desktop_line(1, "startup", "one") + &desktop_line(2, "runtime", "two"),
+        )
+        .expect("write backup");
+        fs::write(
+            &path,
+            desktop_line(3, "restart", "three") + &desktop_line(4, "startup", "four"),
+        )
+        .expect("write active log");
+
+        let query = LogQuery {
+            offset: 1,
+            ..LogQuery::default()
+        };
+        let page = query_log_files(&path, LogSource::Desktop, &query, 2).expect("query");
+
+        assert!(page.has_more);
+        assert_eq!(
+            page.entries
+                .iter()
+                .map(|entry| (entry.file.as_str(), entry.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "desktop.log.1",
+                    "[2024-05-01 12:02:00.000 +0000] [runtime] two"
+                ),
+                (
+                    "desktop.log",
+                    "[2024-05-01 12:03:00.000 +0000] [restart] three"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn follow_cursor_reads_only_appended_complete_lines() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join("desktop.log");
+        fs::write(
+            &path,
+            desktop_line(1, "startup", "one") + "[2024-05-01 12:02",
+        )
+        .expect("write log");
+
+        let page =
+            query_log_files(&path, LogSource::Desktop, &LogQuery::default(), 10).expect("query");
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.cursor.line, 1);
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open log");
+        file.write_all(b":00.000 +0000] [runtime] two\n")
+            .expect("append log");
+        let follow = LogQuery {
+            after: Some(page.cursor),
+            ..LogQuery::default()
+        };
+        let page = query_log_files(&path, LogSource::Desktop, &follow, 10).expect("follow");
+        assert_eq!(
+            page.entries
+                .iter()
+                .map(|entry| (entry.line_number, entry.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(2, "[2024-05-01 12:02:00.000 +0000] [runtime] two")]
+        );
+
+        fs::write(&path, desktop_line(3, "restart", "three")).expect("rotate log");
+        let follow = LogQuery {
+            after: Some(page.cursor),
+            ..LogQuery::default()
+        };
+        let page = query_log_files(&path, LogSource::Desktop, &follow, 10).expect("follow");
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].line_number, 1);
+    }
+
+    #[test]
+    fn desktop_filter_applies_category_substring_and_time_range() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join("desktop.log");
+        fs::write(
+            &path,
+            desktop_line(1, "startup", "backend READY")
+                + &desktop_line(2, "startup", "backend ready again")
+                + &desktop_line(3, "runtime", "backend ready")
+                + &desktop_line(9, "startup", "backend ready late"),
+        )
+        .expect("write log");
+        let since_ms = DateTime::parse_from_rfc3339("2024-05-01T12:02:00Z")
+            .unwrap()
+            .timestamp_millis();
+
+        let query = LogQuery {
+            categories: vec!["Startup".to_string()],
+            contains: Some("ready".to_string()),
+            since_ms: Some(since_ms),
+            until_ms: Some(since_ms + 5 * 60_000),
+            ..LogQuery::default()
+        };
+        let page = query_log_files(&path, LogSource::Desktop, &query, 10).expect("query");
+
+        assert!(!page.has_more);
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].category.as_deref(), Some("startup"));
+        assert_eq!(page.entries[0].timestamp_ms, Some(since_ms));
+        assert_eq!(page.entries[0].line_number, 2);
+    }
+
+    #[test]
+    fn desktop_json_records_are_parsed_alongside_text_lines() {
+        let text = format!(
+            "{}{}\n[2024-05-01 12:03:00.000 +0000] [shutdown] [error] stop failed\n",
+            desktop_line(1, "startup", "plain"),
+            r#"{"timestamp":"2024-05-01T12:02:00.000+00:00","category":"restart","level":"warn","pid":1,"backendPid":null,"message":"retrying"}"#
+        );
+        let entries = parse_log_entries(&text, "desktop.log", LogSource::Desktop, 0);
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].level, None);
+        assert_eq!(entries[1].category.as_deref(), Some("restart"));
+        assert_eq!(entries[1].level.as_deref(), Some("warn"));
+        assert_eq!(
+            entries[1].message,
+            "[2024-05-01T12:02:00.000+00:00] [restart] [warn] retrying"
+        );
+        assert_eq!(entries[2].category.as_deref(), Some("shutdown"));
+        assert_eq!(entries[2].level.as_deref(), Some("error"));
+        assert_eq!(
+            entries[1].timestamp_ms,
+            Some(entries[0].timestamp_ms.unwrap() + 60_000)
+        );
+    }
+
+    #[test]
+    fn backend_entries_fold_tracebacks_and_filter_by_level() {
+        let text =
+            "\u{1b}[32m[2024-05-01 12:00:00.123] [Core] [INFO] [main.py:10]: started\u{1b}[0m\n\
+                    [2024-05-01 12:00:01.000] [Core] [ERRO] [main.py:20]: boom\n\
+                    Traceback (most recent call last):\n\
+                    \x20 File \"main.py\", line 20\n\
+                    WARNING: pip is outdated\n";
+        let entries = parse_log_entries(text, "backend.log", LogSource::Backend, 0);
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.line_number, entry.level.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![(1, Some("info")), (2, Some("error")), (5, Some("warn"))]
+        );
+        assert_eq!(
+            entries[0].message,
+            "[2024-05-01 12:00:00.123] [Core] [INFO] [main.py:10]: started"
+        );
+        assert!(entries[0].timestamp_ms.is_some());
+        assert!(entries[1].message.ends_with("File \"main.py\", line 20"));
+
+        let filter = LogFilter::from_query(&LogQuery {
+            levels: vec!["error".to_string(), "warning".to_string()],
+            ..LogQuery::default()
+        });
+        assert_eq!(
+            entries.iter().filter(|entry| filter.matches(entry)).count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,602 @@
+use std::{
+    cmp::Reverse,
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Local, NaiveDate};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde_json::Value;
+
+const GZIP_SUFFIX: &str = ".gz";
+const DATE_SUFFIX_FORMAT: &str = "%Y-%m-%d";
+const DEFAULT_DAILY_MAX_AGE_DAYS: u64 = 30;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Held while backups are renamed, compressed or pruned, so a rotation never shifts a backup
+/// that a background compression is still reading.
+static BACKUP_MAINTENANCE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogRotation {
+    /// Numbered backups (`.1` … `.N`) once the active file outgrows its size cap.
+    #[default]
+    Size,
+    /// Date-stamped backups (`.YYYY-MM-DD`) at the first write of a new day, plus the size
+    /// cap within a day (`.YYYY-MM-DD.1`, …).
+    Daily,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogRetentionPolicy {
+    pub rotation: LogRotation,
+    /// Maximum number of backups kept; 0 leaves daily backups bounded by age only.
+    pub backup_count: usize,
+    pub max_age_days: Option<u64>,
+    pub compress: bool,
+}
+
+impl LogRetentionPolicy {
+    pub fn size_based(backup_count: usize) -> Self {
+        Self {
+            rotation: LogRotation::Size,
+            backup_count,
+            max_age_days: None,
+            compress: false,
+        }
+    }
+
+    pub fn is_daily(&self) -> bool {
+        self.rotation == LogRotation::Daily
+    }
+}
+
+/// Reads the `logRetention` object of `desktop_state.json`, e.g.
+/// `{ "rotation": "daily", "maxAgeDays": 30, "maxBackups": 0, "compress": true }`.
+/// Missing or invalid fields keep the size-based defaults.
+pub fn resolve_log_retention_policy<F>(
+    state_value: Option<&Value>,
+    default_backup_count: usize,
+    mut log: F,
+) -> LogRetentionPolicy
+where
+    F: FnMut(String),
+{
+    let mut policy = LogRetentionPolicy::size_based(default_backup_count);
+    let Some(config) = state_value.and_then(Value::as_object) else {
+        return policy;
+    };
+
+    match config
+        .get("rotation")
+        .and_then(Value::as_str)
+        .map(str::trim)
+    {
+        Some(raw) if raw.eq_ignore_ascii_case("daily") => {
+            policy.rotation = LogRotation::Daily;
+            policy.backup_count = 0;
+            policy.max_age_days = Some(DEFAULT_DAILY_MAX_AGE_DAYS);
+        }
+        Some(raw) if raw.eq_ignore_ascii_case("size") => {}
+        Some(raw) => log(format!(
+            "[log retention] ignoring unknown rotation mode: {raw}"
+        )),
+        None => {}
+    }
+    if let Some(backups) = config.get("maxBackups").and_then(Value::as_u64) {
+        policy.backup_count = usize::try_from(backups).unwrap_or(usize::MAX);
+    }
+    match config.get("maxAgeDays") {
+        Some(Value::Null) => policy.max_age_days = None,
+        Some(value) => match value.as_u64() {
+            Some(0) => policy.max_age_days = None,
+            Some(days) => policy.max_age_days = Some(days),
+            None => log(format!(
+                "[log retention] ignoring invalid maxAgeDays: {value}"
+            )),
+        },
+        None => {}
+    }
+    if let Some(compress) = config.get("compress").and_then(Value::as_bool) {
+        policy.compress = compress;
+    }
+    // Size rotation without a backup to rotate into would truncate nothing and grow forever.
+    if policy.rotation == LogRotation::Size && policy.backup_count == 0 {
+        policy.backup_count = default_backup_count;
+    }
+    policy
+}
+
+/// Rotates `path` when it outgrows `max_bytes` or, for daily rotation, when it was last
+/// written on an earlier day. Only the rename runs on the caller's thread; compressing the
+/// new backup and pruning old ones happen on the returned background thread.
+pub fn rotate_log_if_needed<F>(
+    path: &Path,
+    max_bytes: u64,
+    policy: &LogRetentionPolicy,
+    log_scope: &'static str,
+    copy_and_truncate: bool,
+    log: F,
+) -> Option<JoinHandle<()>>
+where
+    F: Fn(String) + Clone + Send + 'static,
+{
+    if !policy.is_daily() && (max_bytes == 0 || policy.backup_count == 0) {
+        return None;
+    }
+
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(error) => {
+            if error.kind() != io::ErrorKind::NotFound {
+                log(format!(
+                    "[log rotation:{log_scope}] failed to read metadata for {}: {}",
+                    path.display(),
+                    error
+                ));
+            }
+            return None;
+        }
+    };
+    let today = Local::now().date_naive();
+    let written_on = metadata
+        .modified()
+        .map(|modified| DateTime::<Local>::from(modified).date_naive())
+        .unwrap_or(today);
+    let size_due = max_bytes > 0 && metadata.len() >= max_bytes;
+    let day_due = policy.is_daily() && metadata.len() > 0 && written_on < today;
+    if !size_due && !day_due {
+        return None;
+    }
+
+    let maintenance_guard = lock_backup_maintenance();
+    let rotated = match policy.rotation {
+        LogRotation::Size => {
+            shift_numbered_backups(path, policy.backup_count, log_scope, &mut |message| {
+                log(message)
+            });
+            rotated_log_path(path, 1)
+        }
+        LogRotation::Daily => next_dated_backup_path(path, written_on),
+    };
+
+    if copy_and_truncate {
+        match fs::copy(path, &rotated) {
+            Ok(_) => {
+                if let Err(error) = OpenOptions::new().write(true).truncate(true).open(path) {
+                    log(format!(
+                        "[log rotation:{log_scope}] failed to truncate active log {}: {}",
+                        path.display(),
+                        error
+                    ));
+                }
+            }
+            Err(error) => {
+                log(format!(
+                    "[log rotation:{log_scope}] failed to copy {} to {}: {}",
+                    path.display(),
+                    rotated.display(),
+                    error
+                ));
+                return None;
+            }
+        }
+    } else if let Err(error) = fs::rename(path, &rotated) {
+        log(format!(
+            "[log rotation:{log_scope}] failed to rotate {} to {}: {}",
+            path.display(),
+            rotated.display(),
+            error
+        ));
+        return None;
+    }
+    drop(maintenance_guard);
+
+    let path = path.to_path_buf();
+    let policy = *policy;
+    let spawn_log = log.clone();
+    thread::Builder::new()
+        .name(format!("log-maintenance-{log_scope}"))
+        .spawn(move || {
+            // Reported once the lock is released: the desktop logger may be waiting for it
+            // while rotating desktop.log.
+            let mut warnings = Vec::new();
+            {
+                let _guard = lock_backup_maintenance();
+                if policy.compress {
+                    if let Err(error) = compress_log_file(&rotated) {
+                        warnings.push(format!("[log rotation:{log_scope}] {error}"));
+                    }
+                }
+                prune_log_backups(
+                    &path,
+                    &policy,
+                    SystemTime::now(),
+                    log_scope,
+                    &mut |message| warnings.push(message),
+                );
+            }
+            for warning in warnings {
+                log(warning);
+            }
+        })
+        .map_err(|error| {
+            spawn_log(format!(
+                "[log rotation:{log_scope}] failed to spawn backup maintenance: {error}"
+            ))
+        })
+        .ok()
+}
+
+fn lock_backup_maintenance() -> MutexGuard<'static, ()> {
+    match BACKUP_MAINTENANCE_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(error) => error.into_inner(),
+    }
+}
+
+fn shift_numbered_backups(
+    path: &Path,
+    backup_count: usize,
+    log_scope: &str,
+    log: &mut impl FnMut(String),
+) {
+    for suffix in ["", GZIP_SUFFIX] {
+        remove_if_exists(
+            &with_suffix(&rotated_log_path(path, backup_count), suffix),
+            log_scope,
+            log,
+        );
+    }
+    for index in (1..backup_count).rev() {
+        for suffix in ["", GZIP_SUFFIX] {
+            let source = with_suffix(&rotated_log_path(path, index), suffix);
+            if !source.exists() {
+                continue;
+            }
+            let target = with_suffix(&rotated_log_path(path, index + 1), suffix);
+            remove_if_exists(&target, log_scope, log);
+            if let Err(error) = fs::rename(&source, &target) {
+                log(format!(
+                    "[log rotation:{log_scope}] failed to rename {} to {}: {}",
+                    source.display(),
+                    target.display(),
+                    error
+                ));
+            }
+        }
+    }
+    for suffix in ["", GZIP_SUFFIX] {
+        remove_if_exists(
+            &with_suffix(&rotated_log_path(path, 1), suffix),
+            log_scope,
+            log,
+        );
+    }
+}
+
+fn next_dated_backup_path(path: &Path, day: NaiveDate) -> PathBuf {
+    let dated = with_suffix(path, &format!(".{}", day.format(DATE_SUFFIX_FORMAT)));
+    let taken =
+        |candidate: &Path| candidate.exists() || with_suffix(candidate, GZIP_SUFFIX).exists();
+    if !taken(&dated) {
+        return dated;
+    }
+    (1..)
+        .map(|index| rotated_log_path(&dated, index))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or(dated)
+}
+
+/// Replaces `path` with `path.gz`, keeping the original modification time so age-based
+/// pruning still sees when the backup was written.
+fn compress_log_file(path: &Path) -> Result<PathBuf, String> {
+    let target = with_suffix(path, GZIP_SUFFIX);
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    let result = File::open(path).and_then(|mut source| {
+        let mut encoder = GzEncoder::new(File::create(&target)?, Compression::default());
+        io::copy(&mut source, &mut encoder)?;
+        let file = encoder.finish()?;
+        if let Some(modified) = modified {
+            file.set_modified(modified)?;
+        }
+        Ok(())
+    });
+    match result {
+        Ok(()) => {
+            fs::remove_file(path).map_err(|error| {
+                format!(
+                    "failed to remove {} after compressing it: {}",
+                    path.display(),
+                    error
+                )
+            })?;
+            Ok(target)
+        }
+        Err(error) => {
+            let _ = fs::remove_file(&target);
+            Err(format!("failed to compress {}: {}", path.display(), error))
+        }
+    }
+}
+
+/// Drops backups beyond the policy's count cap and those last written more than
+/// `max_age_days` before `now`.
+fn prune_log_backups(
+    path: &Path,
+    policy: &LogRetentionPolicy,
+    now: SystemTime,
+    log_scope: &str,
+    log: &mut impl FnMut(String),
+) {
+    let max_age = policy
+        .max_age_days
+        .map(|days| Duration::from_secs(days.saturating_mul(SECONDS_PER_DAY)));
+    for (index, backup) in list_log_backups(path).into_iter().enumerate() {
+        let over_count = policy.backup_count > 0 && index >= policy.backup_count;
+        let too_old = max_age.is_some_and(|max_age| {
+            fs::metadata(&backup)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age > max_age)
+        });
+        if over_count || too_old {
+            remove_if_exists(&backup, log_scope, log);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum BackupKey {
+    Dated(Reverse<NaiveDate>, Reverse<usize>),
+    Numbered(usize),
+}
+
+/// Parses the part of a backup file name after `<log name>.`, without any `.gz` suffix.
+fn parse_backup_suffix(suffix: &str) -> Option<BackupKey> {
+    if let Ok(index) = suffix.parse::<usize>() {
+        return (index > 0).then_some(BackupKey::Numbered(index));
+    }
+    let (date, sequence) = match suffix.split_once('.') {
+        Some((date, sequence)) => (date, sequence.parse::<usize>().ok()?),
+        None => (suffix, 0),
+    };
+    let date = NaiveDate::parse_from_str(date, DATE_SUFFIX_FORMAT).ok()?;
+    Some(BackupKey::Dated(Reverse(date), Reverse(sequence)))
+}
+
+/// Rotated backups of `path`, newest first: date-stamped backups (latest day, then latest
+/// sequence) followed by numbered ones from older rotation settings.
+fn list_log_backups(path: &Path) -> Vec<PathBuf> {
+    let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", file_name.to_string_lossy());
+    let Ok(read_dir) = fs::read_dir(if parent.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        parent
+    }) else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<(BackupKey, bool, PathBuf)> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let suffix = name.strip_prefix(&prefix)?;
+            let (suffix, compressed) = match suffix.strip_suffix(GZIP_SUFFIX) {
+                Some(stripped) => (stripped, true),
+                None => (suffix, false),
+            };
+            let key = parse_backup_suffix(suffix)?;
+            let candidate = path.with_file_name(&name);
+            candidate.is_file().then_some((key, compressed, candidate))
+        })
+        .collect();
+    backups.sort();
+    backups.into_iter().map(|(_, _, path)| path).collect()
+}
+
+/// Active log file followed by its rotated backups, newest first. Missing files are skipped.
+pub fn log_file_chain(path: &Path) -> Vec<PathBuf> {
+    std::iter::once(path.to_path_buf())
+        .filter(|candidate| candidate.is_file())
+        .chain(list_log_backups(path))
+        .collect()
+}
+
+/// Reads a log file, transparently decompressing gzip backups.
+pub fn read_log_file(path: &Path) -> io::Result<Vec<u8>> {
+    if !is_compressed_log(path) {
+        return fs::read(path);
+    }
+    let mut raw = Vec::new();
+    GzDecoder::new(File::open(path)?).read_to_end(&mut raw)?;
+    Ok(raw)
+}
+
+pub fn is_compressed_log(path: &Path) -> bool {
+    path.to_string_lossy().ends_with(GZIP_SUFFIX)
+}
+
+fn rotated_log_path(path: &Path, index: usize) -> PathBuf {
+    with_suffix(path, &format!(".{index}"))
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut value = OsString::from(path.as_os_str());
+    value.push(suffix);
+    PathBuf::from(value)
+}
+
+fn remove_if_exists(path: &Path, log_scope: &str, log: &mut impl FnMut(String)) {
+    if let Err(error) = fs::remove_file(path) {
+        if error.kind() != io::ErrorKind::NotFound {
+            log(format!(
+                "[log rotation:{log_scope}] failed to remove {}: {}",
+                path.display(),
+                error
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn set_modified(path: &Path, modified: SystemTime) {
+        OpenOptions::new()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(modified))
+            .expect("set modified time");
+    }
+
+    #[test]
+    fn retention_policy_reads_desktop_state_with_daily_defaults() {
+        assert_eq!(
+            resolve_log_retention_policy(None, 5, |_| {}),
+            LogRetentionPolicy::size_based(5)
+        );
+
+        let daily = resolve_log_retention_policy(
+            Some(&json!({ "rotation": "Daily", "compress": true })),
+            5,
+            |_| {},
+        );
+        assert_eq!(daily.rotation, LogRotation::Daily);
+        assert_eq!(daily.backup_count, 0);
+        assert_eq!(daily.max_age_days, Some(DEFAULT_DAILY_MAX_AGE_DAYS));
+        assert!(daily.compress);
+
+        let size = resolve_log_retention_policy(
+            Some(&json!({ "maxBackups": 0, "maxAgeDays": 7 })),
+            5,
+            |_| {},
+        );
+        assert_eq!(size.backup_count, 5);
+        assert_eq!(size.max_age_days, Some(7));
+
+        let mut warnings = Vec::new();
+        let invalid = resolve_log_retention_policy(
+            Some(&json!({ "rotation": "hourly", "maxAgeDays": "soon" })),
+            5,
+            |message| warnings.push(message),
+        );
+        assert_eq!(invalid, LogRetentionPolicy::size_based(5));
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn log_file_chain_orders_dated_then_numbered_backups_newest_first() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join("desktop.log");
+        for name in [
+            "desktop.log",
+            "desktop.log.2",
+            "desktop.log.2024-05-01.gz",
+            "desktop.log.2024-05-02",
+            "desktop.log.2024-05-02.1.gz",
+            "desktop.log.notes",
+            "desktop.log.0",
+        ] {
+            fs::write(temp_dir.path().join(name), name).expect("write log");
+        }
+
+        let names: Vec<String> = log_file_chain(&path)
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "desktop.log",
+                "desktop.log.2024-05-02.1.gz",
+                "desktop.log.2024-05-02",
+                "desktop.log.2024-05-01.gz",
+                "desktop.log.2",
+            ]
+        );
+    }
+
+    #[test]
+    fn daily_rotation_dates_compresses_and_prunes_backups() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join("backend.log");
+        let now = SystemTime::now();
+        let yesterday = now - Duration::from_secs(SECONDS_PER_DAY);
+        fs::write(&path, "yesterday\n").expect("write active log");
+        set_modified(&path, yesterday);
+        let stale = temp_dir.path().join("backend.log.2000-01-01");
+        fs::write(&stale, "ancient\n").expect("write stale backup");
+        set_modified(&stale, now - Duration::from_secs(400 * SECONDS_PER_DAY));
+
+        let policy = LogRetentionPolicy {
+            rotation: LogRotation::Daily,
+            backup_count: 0,
+            max_age_days: Some(30),
+            compress: true,
+        };
+        rotate_log_if_needed(&path, 1024 * 1024, &policy, "test", false, |_| {})
+            .expect("rotation due")
+            .join()
+            .expect("backup maintenance");
+
+        let chain = log_file_chain(&path);
+        assert_eq!(chain.len(), 1);
+        let expected_name = format!(
+            "backend.log.{}.gz",
+            DateTime::<Local>::from(yesterday).format(DATE_SUFFIX_FORMAT)
+        );
+        assert_eq!(
+            chain[0].file_name().unwrap().to_string_lossy(),
+            expected_name
+        );
+        assert_eq!(
+            read_log_file(&chain[0]).expect("read backup"),
+            b"yesterday\n"
+        );
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn size_rotation_shifts_compressed_backups_and_caps_count() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let path = temp_dir.path().join("desktop.log");
+        let policy = LogRetentionPolicy {
+            compress: true,
+            ..LogRetentionPolicy::size_based(2)
+        };
+
+        for round in 0..3 {
+            fs::write(&path, format!("round {round}\n")).expect("write active log");
+            rotate_log_if_needed(&path, 1, &policy, "test", false, |_| {})
+                .expect("rotation due")
+                .join()
+                .expect("backup maintenance");
+        }
+
+        let chain = log_file_chain(&path);
+        assert_eq!(
+            chain,
+            vec![
+                temp_dir.path().join("desktop.log.1.gz"),
+                temp_dir.path().join("desktop.log.2.gz"),
+            ]
+        );
+        assert_eq!(read_log_file(&chain[0]).expect("read newest"), b"round 2\n");
+        assert_eq!(read_log_file(&chain[1]).expect("read older"), b"round 1\n");
+    }
+}
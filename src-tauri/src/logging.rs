@@ -1,12 +1,22 @@
 use std::{
     env,
-    ffi::OsString,
     fs::{self, OpenOptions},
     io::Write,
     path::{Path, PathBuf},
-    sync::{Mutex, OnceLock},
+    sync::{
+        atomic::{AtomicU32, AtomicU8, Ordering},
+        Mutex, OnceLock,
+    },
 };
 
+use chrono::{DateTime, Local, SecondsFormat};
+use serde_json::{json, Value};
+
+use crate::log_retention::{self, LogRetentionPolicy};
+
+/// Pid of the selected instance's backend child, stamped on structured log lines; 0 = none.
+static LOGGED_BACKEND_PID: AtomicU32 = AtomicU32::new(0);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DesktopLogCategory {
     Startup,
@@ -26,115 +36,166 @@ impl DesktopLogCategory {
     }
 }
 
-pub fn rotate_log_if_needed(
-    path: &Path,
-    max_bytes: u64,
-    backup_count: usize,
-    log_scope: &str,
-    copy_and_truncate: bool,
-) {
-    if max_bytes == 0 || backup_count == 0 {
-        return;
-    }
-
-    let metadata = match fs::metadata(path) {
-        Ok(metadata) => metadata,
-        Err(error) => {
-            if error.kind() != std::io::ErrorKind::NotFound {
-                eprintln!(
-                    "[log rotation:{log_scope}] failed to read metadata for {}: {}",
-                    path.display(),
-                    error
-                );
-            }
-            return;
-        }
-    };
-    if metadata.len() < max_bytes {
-        return;
-    }
-
-    let oldest = rotated_log_path(path, backup_count);
-    if let Err(error) = fs::remove_file(&oldest) {
-        if error.kind() != std::io::ErrorKind::NotFound {
-            eprintln!(
-                "[log rotation:{log_scope}] failed to remove oldest backup {}: {}",
-                oldest.display(),
-                error
-            );
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DesktopLogLevel {
+    Trace,
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl DesktopLogLevel {
+    pub fn as_label(self) -> &'static str {
+        match self {
+            Self::Trace => "trace",
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
         }
     }
 
-    for index in (1..backup_count).rev() {
-        let source = rotated_log_path(path, index);
-        if !source.exists() {
-            continue;
-        }
-        let target = rotated_log_path(path, index + 1);
-        if let Err(error) = fs::remove_file(&target) {
-            if error.kind() != std::io::ErrorKind::NotFound {
-                eprintln!(
-                    "[log rotation:{log_scope}] failed to remove backup {}: {}",
-                    target.display(),
-                    error
-                );
-            }
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "trace" => Some(Self::Trace),
+            "debug" => Some(Self::Debug),
+            "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            _ => None,
         }
-        if let Err(error) = fs::rename(&source, &target) {
-            eprintln!(
-                "[log rotation:{log_scope}] failed to rename {} to {}: {}",
-                source.display(),
-                target.display(),
-                error
-            );
+    }
+
+    fn as_u8(self) -> u8 {
+        self as u8 + 1
+    }
+
+    fn from_u8(raw: u8) -> Option<Self> {
+        [
+            Self::Trace,
+            Self::Debug,
+            Self::Info,
+            Self::Warn,
+            Self::Error,
+        ]
+        .into_iter()
+        .find(|level| level.as_u8() == raw)
+    }
+}
+
+/// Minimum level set at runtime through the bridge; 0 = fall back to the configured level.
+static RUNTIME_MIN_LOG_LEVEL: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_runtime_min_log_level(level: Option<DesktopLogLevel>) {
+    RUNTIME_MIN_LOG_LEVEL.store(level.map_or(0, DesktopLogLevel::as_u8), Ordering::Relaxed);
+}
+
+pub fn runtime_min_log_level() -> Option<DesktopLogLevel> {
+    DesktopLogLevel::from_u8(RUNTIME_MIN_LOG_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Same precedence as the log format: env first, then the `logLevel` field of
+/// `desktop_state.json`, then `info`.
+pub fn resolve_desktop_log_level(
+    env_value: Option<&str>,
+    state_value: Option<&Value>,
+) -> DesktopLogLevel {
+    if let Some(raw) = env_value.filter(|raw| !raw.trim().is_empty()) {
+        if let Some(level) = DesktopLogLevel::parse(raw) {
+            return level;
         }
+        eprintln!("[logging] ignoring invalid desktop log level: {raw}");
     }
+    state_value
+        .and_then(Value::as_str)
+        .and_then(DesktopLogLevel::parse)
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DesktopLogFormat {
+    #[default]
+    Text,
+    Json,
+}
 
-    let rotated = rotated_log_path(path, 1);
-    if let Err(error) = fs::remove_file(&rotated) {
-        if error.kind() != std::io::ErrorKind::NotFound {
-            eprintln!(
-                "[log rotation:{log_scope}] failed to remove first backup {}: {}",
-                rotated.display(),
-                error
-            );
+impl DesktopLogFormat {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "text" | "plain" => Some(Self::Text),
+            "json" | "jsonl" => Some(Self::Json),
+            _ => None,
         }
     }
+}
 
-    if copy_and_truncate {
-        match fs::copy(path, &rotated) {
-            Ok(_) => {
-                if let Err(error) = OpenOptions::new().write(true).truncate(true).open(path) {
-                    eprintln!(
-                        "[log rotation:{log_scope}] failed to truncate active log {}: {}",
-                        path.display(),
-                        error
-                    );
-                }
-            }
-            Err(error) => {
-                eprintln!(
-                    "[log rotation:{log_scope}] failed to copy {} to {}: {}",
-                    path.display(),
-                    rotated.display(),
-                    error
-                );
-            }
+/// The env override wins over the `logFormat` field of `desktop_state.json`; unknown values
+/// fall back to plain text.
+pub fn resolve_desktop_log_format(
+    env_value: Option<&str>,
+    state_value: Option<&Value>,
+) -> DesktopLogFormat {
+    if let Some(raw) = env_value.filter(|raw| !raw.trim().is_empty()) {
+        if let Some(format) = DesktopLogFormat::parse(raw) {
+            return format;
         }
-    } else if let Err(error) = fs::rename(path, &rotated) {
-        eprintln!(
-            "[log rotation:{log_scope}] failed to rotate {} to {}: {}",
-            path.display(),
-            rotated.display(),
-            error
-        );
+        // append_desktop_log is what asked for the format, so it cannot report this itself.
+        eprintln!("[logging] ignoring invalid desktop log format: {raw}");
     }
+    state_value
+        .and_then(Value::as_str)
+        .and_then(DesktopLogFormat::parse)
+        .unwrap_or_default()
+}
+
+pub fn set_logged_backend_pid(pid: Option<u32>) {
+    LOGGED_BACKEND_PID.store(pid.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Forgets `pid` unless another backend has been recorded since.
+pub fn clear_logged_backend_pid(pid: u32) {
+    let _ = LOGGED_BACKEND_PID.compare_exchange(pid, 0, Ordering::Relaxed, Ordering::Relaxed);
 }
 
-fn rotated_log_path(path: &Path, index: usize) -> PathBuf {
-    let mut value = OsString::from(path.as_os_str());
-    value.push(format!(".{index}"));
-    PathBuf::from(value)
+fn logged_backend_pid() -> Option<u32> {
+    match LOGGED_BACKEND_PID.load(Ordering::Relaxed) {
+        0 => None,
+        pid => Some(pid),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn format_desktop_log_line(
+    format: DesktopLogFormat,
+    timestamp: &DateTime<Local>,
+    category: DesktopLogCategory,
+    level: DesktopLogLevel,
+    pid: u32,
+    backend_pid: Option<u32>,
+    message: &str,
+) -> String {
+    match format {
+        DesktopLogFormat::Text => format!(
+            "[{}] [{}] [{}] {}\n",
+            timestamp.format("%Y-%m-%d %H:%M:%S%.3f %z"),
+            category.as_label(),
+            level.as_label(),
+            message
+        ),
+        DesktopLogFormat::Json => {
+            let record = json!({
+                "timestamp": timestamp.to_rfc3339_opts(SecondsFormat::Millis, false),
+                "category": category.as_label(),
+                "level": level.as_label(),
+                "pid": pid,
+                "backendPid": backend_pid,
+                "message": message,
+            });
+            format!("{record}\n")
+        }
+    }
 }
 
 pub fn resolve_desktop_log_path(packaged_root: Option<PathBuf>, desktop_log_file: &str) -> PathBuf {
@@ -185,13 +246,16 @@ pub fn resolve_backend_log_path(
         .join("backend.log")
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn append_desktop_log(
     category: DesktopLogCategory,
+    level: DesktopLogLevel,
     message: &str,
+    format: DesktopLogFormat,
     packaged_root: Option<PathBuf>,
     desktop_log_file: &str,
     max_bytes: u64,
-    backup_count: usize,
+    retention: &LogRetentionPolicy,
     write_lock: &OnceLock<Mutex<()>>,
 ) {
     let path = resolve_desktop_log_path(packaged_root, desktop_log_file);
@@ -202,14 +266,127 @@ pub fn append_desktop_log(
         Ok(guard) => guard,
         Err(poisoned) => poisoned.into_inner(),
     };
-    rotate_log_if_needed(&path, max_bytes, backup_count, "desktop", false);
-    let timestamp = chrono::Local::now()
-        .format("%Y-%m-%d %H:%M:%S%.3f %z")
-        .to_string();
-    let line = format!("[{}] [{}] {}\n", timestamp, category.as_label(), message);
+    // This is the desktop log rotating itself; stderr is the only place left to report to.
+    log_retention::rotate_log_if_needed(&path, max_bytes, retention, "desktop", false, |error| {
+        eprintln!("{error}")
+    });
+    let line = format_desktop_log_line(
+        format,
+        &Local::now(),
+        category,
+        level,
+        std::process::id(),
+        logged_backend_pid(),
+        message,
+    );
     let _ = OpenOptions::new()
         .create(true)
         .append(true)
         .open(path)
         .and_then(|mut file| file.write_all(line.as_bytes()));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample_timestamp() -> DateTime<Local> {
+        Local
+            .timestamp_millis_opt(1_714_564_800_123)
+            .single()
+            .expect("timestamp")
+    }
+
+    #[test]
+    fn json_log_line_carries_structured_fields() {
+        let timestamp = sample_timestamp();
+        let line = format_desktop_log_line(
+            DesktopLogFormat::Json,
+            &timestamp,
+            DesktopLogCategory::Restart,
+            DesktopLogLevel::Warn,
+            42,
+            Some(4242),
+            "backend \"restarted\"",
+        );
+
+        assert!(line.ends_with('\n'));
+        let record: Value = serde_json::from_str(line.trim_end()).expect("json line");
+        assert_eq!(record["category"], "restart");
+        assert_eq!(record["level"], "warn");
+        assert_eq!(record["pid"], 42);
+        assert_eq!(record["backendPid"], 4242);
+        assert_eq!(record["message"], "backend \"restarted\"");
+        assert_eq!(
+            DateTime::parse_from_rfc3339(record["timestamp"].as_str().unwrap())
+                .unwrap()
+                .timestamp_millis(),
+            1_714_564_800_123
+        );
+    }
+
+    #[test]
+    fn text_log_line_keeps_plain_layout() {
+        let line = format_desktop_log_line(
+            DesktopLogFormat::Text,
+            &sample_timestamp(),
+            DesktopLogCategory::Startup,
+            DesktopLogLevel::Info,
+            42,
+            None,
+            "ready",
+        );
+        assert!(line.starts_with('['));
+        assert!(line.ends_with("] [startup] [info] ready\n"));
+    }
+
+    #[test]
+    fn log_format_env_overrides_desktop_state() {
+        let json_state = json!("json");
+        assert_eq!(
+            resolve_desktop_log_format(None, Some(&json_state)),
+            DesktopLogFormat::Json
+        );
+        assert_eq!(
+            resolve_desktop_log_format(Some("text"), Some(&json_state)),
+            DesktopLogFormat::Text
+        );
+        assert_eq!(
+            resolve_desktop_log_format(Some("yaml"), Some(&json_state)),
+            DesktopLogFormat::Json
+        );
+        assert_eq!(
+            resolve_desktop_log_format(None, None),
+            DesktopLogFormat::Text
+        );
+    }
+
+    #[test]
+    fn log_levels_order_and_resolve_with_env_precedence() {
+        assert!(DesktopLogLevel::Trace < DesktopLogLevel::Debug);
+        assert!(DesktopLogLevel::Warn < DesktopLogLevel::Error);
+        assert_eq!(
+            DesktopLogLevel::parse("WARNING"),
+            Some(DesktopLogLevel::Warn)
+        );
+
+        let debug_state = json!("debug");
+        assert_eq!(
+            resolve_desktop_log_level(None, Some(&debug_state)),
+            DesktopLogLevel::Debug
+        );
+        assert_eq!(
+            resolve_desktop_log_level(Some("error"), Some(&debug_state)),
+            DesktopLogLevel::Error
+        );
+        assert_eq!(
+            resolve_desktop_log_level(Some("loud"), None),
+            DesktopLogLevel::Info
+        );
+        for level in [DesktopLogLevel::Trace, DesktopLogLevel::Error] {
+            assert_eq!(DesktopLogLevel::from_u8(level.as_u8()), Some(level));
+        }
+        assert_eq!(DesktopLogLevel::from_u8(0), None);
+    }
+}
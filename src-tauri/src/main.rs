@@ -9,14 +9,19 @@ mod app_types;
 mod backend;
 mod bridge;
 mod desktop_state;
+mod diagnostics;
 
 mod exit_state;
 mod lifecycle;
 
 mod launch_plan;
+mod log_query;
+mod log_retention;
 mod logging;
 mod packaged_webui;
 mod process_control;
+mod profile_switch_flow;
+mod proxy_config;
 mod restart_backend_flow;
 mod runtime_paths;
 mod shell_locale;
@@ -31,13 +36,17 @@ mod window;
 
 pub(crate) use app_constants::*;
 pub(crate) use app_helpers::{
-    append_desktop_log, append_restart_log, append_shutdown_log, append_startup_log,
-    backend_path_override, build_debug_command, inject_desktop_bridge,
+    append_desktop_log, append_desktop_log_at, append_restart_log, append_restart_log_at,
+    append_shutdown_log, append_shutdown_log_at, append_startup_log, append_startup_log_at,
+    backend_path_override, build_debug_command, inject_desktop_bridge, log_retention_policy,
     navigate_main_window_to_backend,
 };
 pub(crate) use app_types::{
-    AtomicFlagGuard, BackendBridgeResult, BackendBridgeState, BackendState, LaunchPlan,
-    RuntimeManifest, TrayMenuState,
+    AtomicFlagGuard, BackendBridgeResult, BackendBridgeState, BackendCrashHistoryResult,
+    BackendEnvResult, BackendLogsResult, BackendMetricsResult, BackendRegistry,
+    BackendRestartScheduleResult, BackendSpawnInfo, BackendState, DesktopLogLevelResult,
+    DesktopProxyConfigResult, DiagnosticsExportResult, LaunchPlan, LogQueryResult, RuntimeManifest,
+    StartupErrorDetails, TrayInstanceMenu, TrayMenuState,
 };
 
 fn main() {
@@ -1,6 +1,7 @@
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 use std::{
+    collections::{BTreeMap, VecDeque},
     io,
     process::{Child, Command, ExitStatus, Stdio},
     thread,
@@ -14,6 +15,10 @@ const WINDOWS_GRACEFUL_STOP_NONZERO_WAIT_MS: u64 = 350;
 const FORCE_STOP_WAIT_MAX_WINDOWS_MS: u64 = 2_200;
 #[cfg(not(target_os = "windows"))]
 const FORCE_STOP_WAIT_MAX_NON_WINDOWS_MS: u64 = 1_500;
+#[cfg(target_os = "linux")]
+const PROCESS_GROUP_POLL_INTERVAL_MS: u64 = 120;
+#[cfg(target_os = "linux")]
+const SURVIVOR_SETTLE_TIMEOUT_MS: u64 = 500;
 #[cfg(target_os = "windows")]
 const WINDOWS_CREATE_NO_WINDOW: u32 = 0x0800_0000;
 
@@ -158,7 +163,425 @@ where
     wait_for_child_exit(child, followup_wait)
 }
 
+/// The backend leads its own process group on Linux (see `backend/launch.rs`), so both signals
+/// go to the whole group and reach plugin / pip / node subprocesses as well.
+#[cfg(target_os = "linux")]
+pub fn stop_child_process_gracefully<F>(child: &mut Child, timeout: Duration, log: F) -> bool
+where
+    F: Fn(&str) + Copy,
+{
+    let pid = child.id();
+    let group_arg = format!("-{pid}");
+    let started_at = Instant::now();
+
+    let graceful_status = run_stop_command(
+        pid,
+        "kill -TERM (process group)",
+        "kill",
+        &["-TERM", "--", &group_arg],
+        log,
+    );
+
+    let graceful_wait_timeout = resolve_graceful_wait_timeout(
+        pid,
+        timeout,
+        timeout,
+        &graceful_status,
+        "kill -TERM (process group)",
+        log,
+    );
+    if wait_for_child_exit(child, graceful_wait_timeout) {
+        // The rest of the group gets whatever is left of the grace window.
+        let remaining = graceful_wait_timeout.saturating_sub(started_at.elapsed());
+        if !wait_for_process_group_exit(pid, remaining) {
+            let force_status = run_stop_command(
+                pid,
+                "kill -KILL (process group)",
+                "kill",
+                &["-KILL", "--", &group_arg],
+                log,
+            );
+            log(&format!(
+                "process group outlived backend after graceful stop, force-kill issued: pgid={pid}, force={force_status:?}"
+            ));
+        }
+        return true;
+    }
+
+    let force_status = run_stop_command(
+        pid,
+        "kill -KILL (process group)",
+        "kill",
+        &["-KILL", "--", &group_arg],
+        log,
+    );
+
+    let followup_wait = compute_followup_wait(
+        timeout,
+        Duration::from_millis(FORCE_STOP_WAIT_MAX_NON_WINDOWS_MS),
+    );
+    log(&format!(
+        "child graceful stop timed out, force-kill issued: pid={pid}, graceful={graceful_status:?}, force={force_status:?}, followup_wait_ms={}",
+        followup_wait.as_millis(),
+    ));
+
+    wait_for_child_exit(child, followup_wait)
+}
+
+#[cfg(target_os = "linux")]
+fn wait_for_process_group_exit(pgid: u32, timeout: Duration) -> bool {
+    let start = Instant::now();
+    loop {
+        let Ok(stats) = read_process_stats() else {
+            return false;
+        };
+        if process_group_members(pgid, &stats).is_empty() {
+            return true;
+        }
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(PROCESS_GROUP_POLL_INTERVAL_MS));
+    }
+}
+
+/// Descendants of `pid` right now, to be checked again by `surviving_descendants` after a stop.
+/// Empty where process trees are not inspected.
+#[cfg(target_os = "linux")]
+pub fn snapshot_descendants(pid: u32) -> Vec<u32> {
+    let Ok(stats) = read_process_stats() else {
+        return Vec::new();
+    };
+    collect_process_tree(pid, &stats)
+        .into_iter()
+        .filter(|descendant| *descendant != pid)
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn snapshot_descendants(_pid: u32) -> Vec<u32> {
+    Vec::new()
+}
+
+/// Processes from `snapshot`, or still in the process group led by `pgid`, that are alive after
+/// the leader stopped. Descendants that moved to a new session are only caught via `snapshot`.
+/// A group SIGKILL takes a moment to land, so survivors are polled until they are gone or the
+/// settle window ends.
+#[cfg(target_os = "linux")]
+pub fn surviving_descendants(pgid: u32, snapshot: &[u32]) -> Vec<u32> {
+    let start = Instant::now();
+    loop {
+        let Ok(stats) = read_process_stats() else {
+            return Vec::new();
+        };
+        let mut survivors: Vec<u32> = stats
+            .iter()
+            .filter(|stat| {
+                stat.pid != pgid
+                    && !stat.zombie
+                    && (stat.pgrp == pgid || snapshot.contains(&stat.pid))
+            })
+            .map(|stat| stat.pid)
+            .collect();
+        if survivors.is_empty()
+            || start.elapsed() >= Duration::from_millis(SURVIVOR_SETTLE_TIMEOUT_MS)
+        {
+            survivors.sort_unstable();
+            return survivors;
+        }
+        thread::sleep(Duration::from_millis(PROCESS_GROUP_POLL_INTERVAL_MS));
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn surviving_descendants(_pgid: u32, _snapshot: &[u32]) -> Vec<u32> {
+    Vec::new()
+}
+
+/// Stops a process this shell did not spawn (an adopted orphan), waiting on its pid instead of
+/// a `Child` handle. A process leading its own group on Linux is stopped as a group.
+pub fn stop_process_by_pid<F>(pid: u32, timeout: Duration, log: F) -> bool
+where
+    F: Fn(&str) + Copy,
+{
+    let (program, graceful_args, force_args, force_wait_cap_ms) = pid_stop_commands(pid);
+    let graceful_args: Vec<&str> = graceful_args.iter().map(String::as_str).collect();
+    let graceful_status = run_stop_command(pid, "graceful stop", program, &graceful_args, log);
+    if wait_for_pid_exit(pid, timeout) {
+        return true;
+    }
+
+    let force_args: Vec<&str> = force_args.iter().map(String::as_str).collect();
+    let force_status = run_stop_command(pid, "force stop", program, &force_args, log);
+    let followup_wait = compute_followup_wait(timeout, Duration::from_millis(force_wait_cap_ms));
+    log(&format!(
+        "process graceful stop timed out, force-kill issued: pid={pid}, graceful={graceful_status:?}, force={force_status:?}, followup_wait_ms={}",
+        followup_wait.as_millis(),
+    ));
+    wait_for_pid_exit(pid, followup_wait)
+}
+
+/// Program, graceful args, force args and the force-stop wait cap for `stop_process_by_pid`.
+#[cfg(target_os = "windows")]
+fn pid_stop_commands(pid: u32) -> (&'static str, Vec<String>, Vec<String>, u64) {
+    let pid_arg = pid.to_string();
+    (
+        "taskkill",
+        vec!["/pid".into(), pid_arg.clone(), "/t".into()],
+        vec!["/pid".into(), pid_arg, "/t".into(), "/f".into()],
+        FORCE_STOP_WAIT_MAX_WINDOWS_MS,
+    )
+}
+
 #[cfg(not(target_os = "windows"))]
+fn pid_stop_commands(pid: u32) -> (&'static str, Vec<String>, Vec<String>, u64) {
+    #[cfg(target_os = "linux")]
+    let target = if is_process_group_leader(pid) {
+        format!("-{pid}")
+    } else {
+        pid.to_string()
+    };
+    #[cfg(not(target_os = "linux"))]
+    let target = pid.to_string();
+    (
+        "kill",
+        vec!["-TERM".into(), "--".into(), target.clone()],
+        vec!["-KILL".into(), "--".into(), target],
+        FORCE_STOP_WAIT_MAX_NON_WINDOWS_MS,
+    )
+}
+
+fn wait_for_pid_exit(pid: u32, timeout: Duration) -> bool {
+    let start = Instant::now();
+    loop {
+        if !is_process_alive(pid) {
+            return true;
+        }
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(120));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessStat {
+    pub pid: u32,
+    pub ppid: u32,
+    pub pgrp: u32,
+    /// Exited but not reaped yet.
+    pub zombie: bool,
+    pub cpu_ticks: u64,
+    pub threads: u64,
+}
+
+/// Parses `/proc/<pid>/stat`. The command name is parenthesized and may itself contain spaces
+/// or parentheses, so fields are counted from the last `)`.
+pub fn parse_proc_stat(raw: &str) -> Option<ProcessStat> {
+    let (head, tail) = raw.rsplit_once(')')?;
+    let pid = head.split_once('(')?.0.trim().parse().ok()?;
+    // `tail` starts at field 3 (state); pgrp is field 5, utime/stime are fields 14/15,
+    // num_threads is 20.
+    let fields: Vec<&str> = tail.split_whitespace().collect();
+    let field = |index: usize| fields.get(index - 3)?.parse::<u64>().ok();
+    Some(ProcessStat {
+        pid,
+        ppid: u32::try_from(field(4)?).ok()?,
+        pgrp: u32::try_from(field(5)?).ok()?,
+        zombie: fields.first() == Some(&"Z"),
+        cpu_ticks: field(14)? + field(15)?,
+        threads: field(20)?,
+    })
+}
+
+/// `root` followed by all of its descendants, breadth first.
+pub fn collect_process_tree(root: u32, stats: &[ProcessStat]) -> Vec<u32> {
+    let mut children: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+    for stat in stats {
+        children.entry(stat.ppid).or_default().push(stat.pid);
+    }
+    let mut tree = Vec::new();
+    let mut queue = VecDeque::from([root]);
+    while let Some(pid) = queue.pop_front() {
+        if tree.contains(&pid) {
+            continue;
+        }
+        tree.push(pid);
+        if let Some(pids) = children.get(&pid) {
+            queue.extend(pids.iter().copied());
+        }
+    }
+    tree
+}
+
+pub fn process_group_members(pgid: u32, stats: &[ProcessStat]) -> Vec<u32> {
+    stats
+        .iter()
+        .filter(|stat| stat.pgrp == pgid && !stat.zombie)
+        .map(|stat| stat.pid)
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+pub fn read_process_stats() -> Result<Vec<ProcessStat>, String> {
+    Ok(std::fs::read_dir("/proc")
+        .map_err(|error| format!("Failed to list /proc: {error}"))?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().parse::<u32>().is_ok())
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("stat")).ok())
+        .filter_map(|raw| parse_proc_stat(&raw))
+        .collect())
+}
+
+#[cfg(target_os = "linux")]
+fn read_process_stat(pid: u32) -> Option<ProcessStat> {
+    std::fs::read_to_string(format!("/proc/{pid}/stat"))
+        .ok()
+        .and_then(|raw| parse_proc_stat(&raw))
+}
+
+#[cfg(target_os = "linux")]
+fn is_process_group_leader(pid: u32) -> bool {
+    read_process_stat(pid).is_some_and(|stat| stat.pgrp == pid)
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_process_alive(pid: u32) -> bool {
+    read_process_stat(pid).is_some_and(|stat| !stat.zombie)
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_process_alive(pid: u32) -> bool {
+    let filter = format!("PID eq {pid}");
+    let mut command = Command::new("tasklist");
+    command
+        .args(["/FI", &filter, "/NH", "/FO", "CSV"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    command.creation_flags(WINDOWS_CREATE_NO_WINDOW);
+    command
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(&format!("\"{pid}\"")))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn is_process_alive(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Argument vector of a running process, or `None` where it cannot be read.
+#[cfg(target_os = "linux")]
+pub fn read_process_command_line(pid: u32) -> Option<Vec<String>> {
+    let raw = std::fs::read(format!("/proc/{pid}/cmdline")).ok()?;
+    let args: Vec<String> = raw
+        .split(|byte| *byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+    (!args.is_empty()).then_some(args)
+}
+
+/// `ps` only reports the joined command, so arguments are split on whitespace.
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn read_process_command_line(pid: u32) -> Option<Vec<String>> {
+    let output = Command::new("ps")
+        .args(["-o", "command=", "-p", &pid.to_string()])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let args: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    (!args.is_empty()).then_some(args)
+}
+
+/// Windows has no procfs; the command line comes from CIM (`Win32_Process.CommandLine`).
+#[cfg(target_os = "windows")]
+pub fn read_process_command_line(pid: u32) -> Option<Vec<String>> {
+    let query = format!("(Get-CimInstance Win32_Process -Filter 'ProcessId={pid}').CommandLine");
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command", &query])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    command.creation_flags(WINDOWS_CREATE_NO_WINDOW);
+    let output = command.output().ok()?;
+    let args = split_windows_command_line(String::from_utf8_lossy(&output.stdout).trim());
+    (!args.is_empty()).then_some(args)
+}
+
+/// Splits on whitespace outside double quotes and drops the quotes; enough to compare with a
+/// recorded launch command, not a full `CommandLineToArgvW`.
+#[cfg(any(target_os = "windows", test))]
+fn split_windows_command_line(raw: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for ch in raw.chars() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            ch if ch.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            ch => current.push(ch),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+/// File name of a running process's executable; the fallback check where the command line
+/// cannot be read.
+#[cfg(target_os = "linux")]
+pub fn read_process_executable_name(pid: u32) -> Option<String> {
+    let exe = std::fs::read_link(format!("/proc/{pid}/exe")).ok()?;
+    exe.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+#[cfg(target_os = "windows")]
+pub fn read_process_executable_name(pid: u32) -> Option<String> {
+    let filter = format!("PID eq {pid}");
+    let mut command = Command::new("tasklist");
+    command
+        .args(["/FI", &filter, "/NH", "/FO", "CSV"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    command.creation_flags(WINDOWS_CREATE_NO_WINDOW);
+    let output = command.output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout
+        .lines()
+        .find(|line| line.contains(&format!("\"{pid}\"")))?;
+    let name = line.split(',').next()?.trim().trim_matches('"');
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn read_process_executable_name(pid: u32) -> Option<String> {
+    let output = Command::new("ps")
+        .args(["-o", "comm=", "-p", &pid.to_string()])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let raw = String::from_utf8_lossy(&output.stdout);
+    let name = std::path::Path::new(raw.trim()).file_name()?;
+    Some(name.to_string_lossy().into_owned())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 pub fn stop_child_process_gracefully<F>(child: &mut Child, timeout: Duration, log: F) -> bool
 where
     F: Fn(&str) + Copy,
@@ -193,6 +616,63 @@ mod tests {
     use super::*;
     use std::sync::Mutex;
 
+    const STAT: &str = "4242 (python3 (astr) bot) S 4000 4242 4242 0 -1 4194560 \
+        100 0 0 0 250 50 0 0 20 0 7 0 123456 1000 200 18446744073709551615";
+
+    #[test]
+    fn proc_stat_parsing_handles_parenthesized_command_names() {
+        assert_eq!(
+            parse_proc_stat(STAT),
+            Some(ProcessStat {
+                pid: 4242,
+                ppid: 4000,
+                pgrp: 4242,
+                zombie: false,
+                cpu_ticks: 300,
+                threads: 7,
+            })
+        );
+        assert_eq!(parse_proc_stat("4242 (python3) S 1"), None);
+    }
+
+    #[test]
+    fn process_tree_includes_nested_descendants_only() {
+        let stat = |pid, ppid| ProcessStat {
+            pid,
+            ppid,
+            pgrp: 10,
+            zombie: false,
+            cpu_ticks: 0,
+            threads: 1,
+        };
+        let stats = [
+            stat(10, 1),
+            stat(11, 10),
+            stat(12, 11),
+            stat(20, 1),
+            stat(13, 10),
+        ];
+        assert_eq!(collect_process_tree(10, &stats), vec![10, 11, 13, 12]);
+        assert_eq!(process_group_members(10, &stats[..2]), vec![10, 11]);
+        assert!(process_group_members(20, &stats).is_empty());
+    }
+
+    #[test]
+    fn split_windows_command_line_keeps_quoted_paths_together() {
+        assert_eq!(
+            split_windows_command_line(
+                r#""C:\Program Files\AstrBot\python.exe"  launch_backend.py --port 6185"#
+            ),
+            vec![
+                r"C:\Program Files\AstrBot\python.exe",
+                "launch_backend.py",
+                "--port",
+                "6185"
+            ]
+        );
+        assert!(split_windows_command_line("   ").is_empty());
+    }
+
     #[test]
     fn compute_followup_wait_respects_min_and_cap() {
         assert_eq!(
@@ -227,4 +707,61 @@ mod tests {
         assert_eq!(snapshot.len(), 1);
         assert!(snapshot[0].contains("shorten graceful wait"));
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn stop_child_process_gracefully_stops_the_whole_process_group() {
+        use std::os::unix::process::CommandExt;
+
+        let mut child = Command::new("sh")
+            .args(["-c", "sleep 30 & wait"])
+            .process_group(0)
+            .spawn()
+            .expect("spawn sh");
+        let pid = child.id();
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let mut descendants = snapshot_descendants(pid);
+        while descendants.is_empty() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+            descendants = snapshot_descendants(pid);
+        }
+        assert!(!descendants.is_empty(), "expected sleep to be running");
+
+        assert!(stop_child_process_gracefully(
+            &mut child,
+            Duration::from_millis(2_000),
+            |_| {}
+        ));
+        assert!(surviving_descendants(pid, &descendants).is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn stop_process_by_pid_stops_a_process_without_its_child_handle() {
+        let mut child = Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .expect("spawn sleep");
+        let pid = child.id();
+        // The command line is briefly empty while the child is still inside exec.
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let mut command_line = read_process_command_line(pid);
+        while command_line.is_none() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+            command_line = read_process_command_line(pid);
+        }
+        assert_eq!(
+            command_line,
+            Some(vec!["sleep".to_string(), "30".to_string()])
+        );
+        assert!(is_process_alive(pid));
+
+        assert!(stop_process_by_pid(
+            pid,
+            Duration::from_millis(2_000),
+            |_| {}
+        ));
+        assert!(!is_process_alive(pid));
+        child.wait().expect("reap sleep");
+    }
 }
@@ -0,0 +1,152 @@
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    append_restart_log,
+    backend::{self, profile::DEFAULT_PROFILE_ID},
+    restart_backend_flow, runtime_paths, AtomicFlagGuard, BackendBridgeResult, BackendRegistry,
+};
+
+fn resolve_target_profile(
+    profile_id: &str,
+) -> Result<Option<backend::profile::BackendProfile>, String> {
+    let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+    let profiles = backend::profile::load_backend_profiles(packaged_root_dir.as_deref());
+    match backend::profile::find_backend_profile(&profiles, Some(profile_id)) {
+        Some(profile) => Ok(Some(profile)),
+        None if profile_id == DEFAULT_PROFILE_ID => Ok(None),
+        None => Err(format!("Unknown backend profile: {profile_id}")),
+    }
+}
+
+/// Refuses `profile` while another running instance (other than the one it `replaces`) uses
+/// the same backend root.
+fn ensure_profile_root_is_free(
+    registry: &BackendRegistry,
+    profile_id: &str,
+    profile: Option<&backend::profile::BackendProfile>,
+    replaces: Option<&str>,
+) -> Result<(), String> {
+    let root_dir = backend::profile::resolved_profile_root_dir(profile);
+    let Some(holder) = registry
+        .running_instances_with_root(root_dir.as_deref())
+        .into_iter()
+        .find(|instance| instance.id != profile_id && Some(instance.id.as_str()) != replaces)
+    else {
+        return Ok(());
+    };
+    let root = root_dir.map_or_else(
+        || "the default root".to_string(),
+        |root| root.display().to_string(),
+    );
+    Err(format!(
+        "Backend instance {} already runs in {root}; give profile {profile_id} its own root.",
+        holder.id
+    ))
+}
+
+fn do_switch_profile(app_handle: &AppHandle, profile_id: &str) -> Result<(), String> {
+    let profile = resolve_target_profile(profile_id)?;
+    let registry = app_handle.state::<BackendRegistry>();
+    let previous = registry.selected();
+    ensure_profile_root_is_free(
+        &registry,
+        profile_id,
+        profile.as_ref(),
+        Some(previous.id.as_str()),
+    )?;
+    let _restart_guard = AtomicFlagGuard::try_set(&previous.is_restarting)
+        .ok_or_else(|| "Backend action already in progress.".to_string())?;
+
+    append_restart_log(&format!("switching backend profile to {profile_id}"));
+    previous.stop_backend()?;
+
+    let target = registry.get_or_insert(profile);
+    registry.select(&target.id);
+    registry.remove(&previous.id);
+    target.reset_crash_restart_tracker();
+
+    target.ensure_backend_ready(app_handle)
+}
+
+/// Starts the instance for `instance_id` next to the running ones and shows it in the main
+/// window once it is ready.
+fn do_open_instance(app_handle: &AppHandle, instance_id: &str) -> Result<(), String> {
+    let profile = resolve_target_profile(instance_id)?;
+    let registry = app_handle.state::<BackendRegistry>();
+    ensure_profile_root_is_free(&registry, instance_id, profile.as_ref(), None)?;
+    let target = registry.get_or_insert(profile);
+    append_restart_log(&format!("opening backend instance {instance_id}"));
+    target.ensure_backend_ready(app_handle)?;
+    registry.select(&target.id);
+    Ok(())
+}
+
+fn do_stop_instance(app_handle: &AppHandle, instance_id: &str) -> Result<(), String> {
+    let registry = app_handle.state::<BackendRegistry>();
+    let Some(state) = registry.get(instance_id) else {
+        return Ok(());
+    };
+    if restart_backend_flow::is_backend_action_in_progress(&state) {
+        return Err("Backend action already in progress.".to_string());
+    }
+
+    append_restart_log(&format!("stopping backend instance {instance_id}"));
+    state.stop_backend()?;
+    registry.remove(instance_id);
+    Ok(())
+}
+
+async fn run_instance_task<F>(
+    app_handle: AppHandle,
+    task_name: &str,
+    task: F,
+) -> BackendBridgeResult
+where
+    F: FnOnce(&AppHandle) -> Result<(), String> + Send + 'static,
+{
+    let app_handle_for_worker = app_handle.clone();
+    match tauri::async_runtime::spawn_blocking(move || task(&app_handle_for_worker)).await {
+        Ok(Ok(())) => BackendBridgeResult {
+            ok: true,
+            reason: None,
+        },
+        Ok(Err(error)) => BackendBridgeResult {
+            ok: false,
+            reason: Some(error),
+        },
+        Err(error) => BackendBridgeResult {
+            ok: false,
+            reason: Some(format!("{task_name} task failed: {error}")),
+        },
+    }
+}
+
+pub async fn run_switch_profile_task(
+    app_handle: AppHandle,
+    profile_id: String,
+) -> BackendBridgeResult {
+    run_instance_task(app_handle, "Backend profile switch", move |app_handle| {
+        do_switch_profile(app_handle, &profile_id)
+    })
+    .await
+}
+
+pub async fn run_open_instance_task(
+    app_handle: AppHandle,
+    instance_id: String,
+) -> BackendBridgeResult {
+    run_instance_task(app_handle, "Backend instance open", move |app_handle| {
+        do_open_instance(app_handle, &instance_id)
+    })
+    .await
+}
+
+pub async fn run_stop_instance_task(
+    app_handle: AppHandle,
+    instance_id: String,
+) -> BackendBridgeResult {
+    run_instance_task(app_handle, "Backend instance stop", move |app_handle| {
+        do_stop_instance(app_handle, &instance_id)
+    })
+    .await
+}
@@ -2,11 +2,60 @@ use std::sync::atomic::Ordering;
 
 use tauri::{AppHandle, Manager};
 
-use crate::{BackendBridgeResult, BackendState};
+use crate::{
+    append_desktop_log_at, logging::DesktopLogLevel, navigate_main_window_to_backend, ui_dispatch,
+    BackendBridgeResult, BackendRegistry, BackendState,
+};
 
-fn do_restart_backend(app_handle: &AppHandle, auth_token: Option<&str>) -> Result<(), String> {
-    let state = app_handle.state::<BackendState>();
-    state.restart_backend(app_handle, auth_token)
+pub(crate) fn restart_backend_instance(
+    app_handle: &AppHandle,
+    instance_id: Option<&str>,
+    auth_token: Option<&str>,
+) -> Result<(), String> {
+    let registry = app_handle.state::<BackendRegistry>();
+    let state = match instance_id {
+        Some(instance_id) => registry
+            .get(instance_id)
+            .ok_or_else(|| format!("Unknown backend instance: {instance_id}"))?,
+        None => registry.selected(),
+    };
+    let previous_backend_url = state.backend_url();
+    state.restart_backend(app_handle, auth_token)?;
+    state.reset_crash_restart_tracker();
+    follow_backend_url_change(app_handle, &state, &previous_backend_url);
+    Ok(())
+}
+
+/// Re-points the main window when `state` moved to another URL, as long as it is the instance
+/// the window shows.
+pub(crate) fn follow_backend_url_change(
+    app_handle: &AppHandle,
+    state: &BackendState,
+    previous_backend_url: &str,
+) {
+    if state.backend_url() == previous_backend_url
+        || app_handle.state::<BackendRegistry>().selected_id() != state.id
+    {
+        return;
+    }
+
+    if let Err(error) = ui_dispatch::run_on_main_thread_dispatch(
+        app_handle,
+        "navigate main window to relocated backend",
+        |main_app| {
+            if let Err(error) = navigate_main_window_to_backend(main_app) {
+                append_desktop_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!("failed to navigate main window to relocated backend: {error}"),
+                );
+            }
+        },
+    ) {
+        append_desktop_log_at(
+            DesktopLogLevel::Warn,
+            &format!("failed to schedule navigation to relocated backend: {error}"),
+        );
+    }
 }
 
 pub fn is_backend_action_in_progress(state: &BackendState) -> bool {
@@ -16,10 +65,23 @@ pub fn is_backend_action_in_progress(state: &BackendState) -> bool {
 pub async fn run_restart_backend_task(
     app_handle: AppHandle,
     auth_token: Option<String>,
+) -> BackendBridgeResult {
+    run_restart_instance_task(app_handle, None, auth_token).await
+}
+
+/// Restarts one registry instance, or the selected one when `instance_id` is `None`.
+pub async fn run_restart_instance_task(
+    app_handle: AppHandle,
+    instance_id: Option<String>,
+    auth_token: Option<String>,
 ) -> BackendBridgeResult {
     let app_handle_for_worker = app_handle.clone();
     match tauri::async_runtime::spawn_blocking(move || {
-        do_restart_backend(&app_handle_for_worker, auth_token.as_deref())
+        restart_backend_instance(
+            &app_handle_for_worker,
+            instance_id.as_deref(),
+            auth_token.as_deref(),
+        )
     })
     .await
     {
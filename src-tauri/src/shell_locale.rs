@@ -5,6 +5,8 @@ use std::{
 
 use serde_json::{Map, Value};
 
+use crate::logging::DesktopLogLevel;
+
 const LOCALE_FIELD: &str = "locale";
 
 fn empty_state_object() -> Value {
@@ -17,7 +19,22 @@ pub struct ShellTexts {
     pub tray_show: &'static str,
     pub tray_reload: &'static str,
     pub tray_restart_backend: &'static str,
+    pub tray_switch_profile: &'static str,
+    pub tray_default_profile: &'static str,
+    pub tray_instances: &'static str,
+    pub tray_instance_open: &'static str,
+    pub tray_instance_restart: &'static str,
+    pub tray_instance_stop: &'static str,
+    pub tray_instance_running: &'static str,
+    pub tray_instance_unresponsive: &'static str,
+    pub tray_instance_starting: &'static str,
+    pub tray_instance_stopped: &'static str,
+    pub tray_view_logs: &'static str,
+    pub tray_export_diagnostics: &'static str,
     pub tray_quit: &'static str,
+    pub tray_tooltip_unresponsive: &'static str,
+    pub log_viewer_title: &'static str,
+    pub watchdog_notification_title: &'static str,
 }
 
 pub fn shell_texts_for_locale(locale: &str) -> ShellTexts {
@@ -27,7 +44,22 @@ pub fn shell_texts_for_locale(locale: &str) -> ShellTexts {
             tray_show: "Show AstrBot",
             tray_reload: "Reload UI",
             tray_restart_backend: "Restart Backend",
+            tray_switch_profile: "Switch Profile",
+            tray_default_profile: "Default",
+            tray_instances: "Instances",
+            tray_instance_open: "Open",
+            tray_instance_restart: "Restart",
+            tray_instance_stop: "Stop",
+            tray_instance_running: "Running",
+            tray_instance_unresponsive: "Not Responding",
+            tray_instance_starting: "Starting",
+            tray_instance_stopped: "Stopped",
+            tray_view_logs: "View Logs",
+            tray_export_diagnostics: "Export Diagnostics",
             tray_quit: "Quit",
+            tray_tooltip_unresponsive: "AstrBot - backend not responding",
+            log_viewer_title: "AstrBot Logs",
+            watchdog_notification_title: "AstrBot backend restarted by resource watchdog",
         };
     }
 
@@ -36,7 +68,22 @@ pub fn shell_texts_for_locale(locale: &str) -> ShellTexts {
         tray_show: "显示 AstrBot",
         tray_reload: "重载界面",
         tray_restart_backend: "重启后端",
+        tray_switch_profile: "切换配置",
+        tray_default_profile: "默认",
+        tray_instances: "实例",
+        tray_instance_open: "打开",
+        tray_instance_restart: "重启",
+        tray_instance_stop: "停止",
+        tray_instance_running: "运行中",
+        tray_instance_unresponsive: "无响应",
+        tray_instance_starting: "启动中",
+        tray_instance_stopped: "已停止",
+        tray_view_logs: "查看日志",
+        tray_export_diagnostics: "导出诊断包",
         tray_quit: "退出",
+        tray_tooltip_unresponsive: "AstrBot - 后端无响应",
+        log_viewer_title: "AstrBot 日志",
+        watchdog_notification_title: "AstrBot 后端已被资源看门狗重启",
     }
 }
 
@@ -82,7 +129,7 @@ pub(crate) fn normalize_shell_locale(raw: &str) -> Option<&'static str> {
 }
 
 fn read_cached_shell_locale(packaged_root_dir: Option<&Path>) -> Option<&'static str> {
-    let state_path = crate::desktop_state::resolve_desktop_state_path(packaged_root_dir)?;
+    let state_path = crate::desktop_state::resolve_shared_desktop_state_path(packaged_root_dir)?;
     let raw = fs::read_to_string(state_path).ok()?;
     let parsed: serde_json::Value = serde_json::from_str(&raw).ok()?;
     let locale = parsed.get(LOCALE_FIELD)?.as_str()?;
@@ -115,9 +162,11 @@ pub(crate) fn write_cached_shell_locale(
         }
     }
 
-    let Some(state_path) = crate::desktop_state::resolve_desktop_state_path(packaged_root_dir)
+    let Some(state_path) =
+        crate::desktop_state::resolve_shared_desktop_state_path(packaged_root_dir)
     else {
-        crate::append_desktop_log(
+        crate::append_desktop_log_at(
+            DesktopLogLevel::Warn,
             "shell locale state path is unavailable; skipping locale persistence",
         );
         return Ok(());
@@ -137,11 +186,14 @@ pub(crate) fn write_cached_shell_locale(
         Ok(raw) => match serde_json::from_str::<Value>(&raw) {
             Ok(value) => value,
             Err(error) => {
-                crate::append_desktop_log(&format!(
-                    "failed to parse shell locale state {}: {}. resetting state file",
-                    state_path.display(),
-                    error
-                ));
+                crate::append_desktop_log_at(
+                    DesktopLogLevel::Warn,
+                    &format!(
+                        "failed to parse shell locale state {}: {}. resetting state file",
+                        state_path.display(),
+                        error
+                    ),
+                );
                 empty_state_object()
             }
         },
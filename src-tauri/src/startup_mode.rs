@@ -1,13 +1,16 @@
 use std::path::Path;
 
-// Keep in sync with STARTUP_MODES in ui/index.html.
+// Keep in sync with STARTUP_MODES in ui/startup-copy.js.
 pub const STARTUP_MODE_LOADING: &str = "loading";
 pub const STARTUP_MODE_PANEL_UPDATE: &str = "panel-update";
+pub const STARTUP_MODE_ERROR: &str = "startup-error";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StartupMode {
     Loading,
     PanelUpdate,
+    /// Only entered when startup fails; the env override cannot select it.
+    Error,
 }
 
 impl StartupMode {
@@ -15,6 +18,7 @@ impl StartupMode {
         match self {
             Self::Loading => STARTUP_MODE_LOADING,
             Self::PanelUpdate => STARTUP_MODE_PANEL_UPDATE,
+            Self::Error => STARTUP_MODE_ERROR,
         }
     }
 }
@@ -82,4 +86,11 @@ mod tests {
             .expect("expected invalid env warning")
             .contains("invalid startup mode"));
     }
+
+    #[test]
+    fn resolve_mode_from_env_does_not_force_error_mode() {
+        let (mode, message) = resolve_mode_from_env(STARTUP_MODE_ERROR, "TEST_ENV");
+        assert_eq!(mode, StartupMode::Loading);
+        assert!(message.is_some());
+    }
 }
@@ -1,16 +1,26 @@
+use std::sync::atomic::AtomicBool;
+
 use tauri::{AppHandle, Manager};
 
-use crate::{navigate_main_window_to_backend, ui_dispatch, BackendState};
+use crate::{navigate_main_window_to_backend, ui_dispatch, AtomicFlagGuard, BackendRegistry};
+
+static STARTUP_TASK_RUNNING: AtomicBool = AtomicBool::new(false);
 
-pub fn spawn_startup_task<F>(app_handle: AppHandle, log: F)
+/// Returns `false` without starting anything while an earlier startup task is still running.
+pub fn spawn_startup_task<F>(app_handle: AppHandle, log: F) -> bool
 where
     F: Fn(&str) + Copy + Send + 'static,
 {
+    let Some(running_guard) = AtomicFlagGuard::try_set(&STARTUP_TASK_RUNNING) else {
+        log("startup task already running, skip");
+        return false;
+    };
     let startup_app_handle = app_handle.clone();
     tauri::async_runtime::spawn(async move {
+        let _running_guard = running_guard;
         let startup_worker_handle = startup_app_handle.clone();
         let startup_result = tauri::async_runtime::spawn_blocking(move || {
-            let state = startup_worker_handle.state::<BackendState>();
+            let state = startup_worker_handle.state::<BackendRegistry>().selected();
             state.ensure_backend_ready(&startup_worker_handle)
         })
         .await
@@ -41,4 +51,5 @@ where
             }
         }
     });
+    true
 }
@@ -1,26 +1,127 @@
 pub const TRAY_MENU_TOGGLE_WINDOW: &str = "tray_toggle_window";
 pub const TRAY_MENU_RELOAD_WINDOW: &str = "tray_reload_window";
 pub const TRAY_MENU_RESTART_BACKEND: &str = "tray_restart_backend";
+pub const TRAY_MENU_OPEN_LOG_VIEWER: &str = "tray_open_log_viewer";
+pub const TRAY_MENU_EXPORT_DIAGNOSTICS: &str = "tray_export_diagnostics";
 pub const TRAY_MENU_QUIT: &str = "tray_quit";
+pub const TRAY_MENU_SWITCH_PROFILE: &str = "tray_switch_profile";
+const TRAY_MENU_SWITCH_PROFILE_PREFIX: &str = "tray_switch_profile:";
+pub const TRAY_MENU_INSTANCES: &str = "tray_instances";
+const TRAY_MENU_INSTANCE_PREFIX: &str = "tray_instance:";
+const TRAY_MENU_INSTANCE_SHOW_PREFIX: &str = "tray_instance_show:";
+const TRAY_MENU_INSTANCE_RESTART_PREFIX: &str = "tray_instance_restart:";
+const TRAY_MENU_INSTANCE_STOP_PREFIX: &str = "tray_instance_stop:";
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TrayMenuAction {
     ToggleWindow,
     ReloadWindow,
     RestartBackend,
+    SwitchProfile(String),
+    ShowInstance(String),
+    RestartInstance(String),
+    StopInstance(String),
+    OpenLogViewer,
+    ExportDiagnostics,
     Quit,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceStatus {
+    Running,
+    Unresponsive,
+    Starting,
+    Stopped,
+}
+
+impl InstanceStatus {
+    pub fn from_flags(has_child: bool, action_in_progress: bool, unhealthy: bool) -> Self {
+        if action_in_progress {
+            Self::Starting
+        } else if has_child && unhealthy {
+            Self::Unresponsive
+        } else if has_child {
+            Self::Running
+        } else {
+            Self::Stopped
+        }
+    }
+}
+
+fn menu_id_argument<'a>(menu_id: &'a str, prefix: &str) -> Option<&'a str> {
+    menu_id
+        .strip_prefix(prefix)
+        .filter(|argument| !argument.is_empty())
+}
+
 pub fn action_from_menu_id(menu_id: &str) -> Option<TrayMenuAction> {
     match menu_id {
         TRAY_MENU_TOGGLE_WINDOW => Some(TrayMenuAction::ToggleWindow),
         TRAY_MENU_RELOAD_WINDOW => Some(TrayMenuAction::ReloadWindow),
         TRAY_MENU_RESTART_BACKEND => Some(TrayMenuAction::RestartBackend),
+        TRAY_MENU_OPEN_LOG_VIEWER => Some(TrayMenuAction::OpenLogViewer),
+        TRAY_MENU_EXPORT_DIAGNOSTICS => Some(TrayMenuAction::ExportDiagnostics),
         TRAY_MENU_QUIT => Some(TrayMenuAction::Quit),
-        _ => None,
+        _ => menu_id_argument(menu_id, TRAY_MENU_SWITCH_PROFILE_PREFIX)
+            .map(|id| TrayMenuAction::SwitchProfile(id.to_string()))
+            .or_else(|| {
+                menu_id_argument(menu_id, TRAY_MENU_INSTANCE_SHOW_PREFIX)
+                    .map(|id| TrayMenuAction::ShowInstance(id.to_string()))
+            })
+            .or_else(|| {
+                menu_id_argument(menu_id, TRAY_MENU_INSTANCE_RESTART_PREFIX)
+                    .map(|id| TrayMenuAction::RestartInstance(id.to_string()))
+            })
+            .or_else(|| {
+                menu_id_argument(menu_id, TRAY_MENU_INSTANCE_STOP_PREFIX)
+                    .map(|id| TrayMenuAction::StopInstance(id.to_string()))
+            }),
     }
 }
 
+pub fn switch_profile_menu_id(profile_id: &str) -> String {
+    format!("{TRAY_MENU_SWITCH_PROFILE_PREFIX}{profile_id}")
+}
+
+pub fn instance_submenu_id(instance_id: &str) -> String {
+    format!("{TRAY_MENU_INSTANCE_PREFIX}{instance_id}")
+}
+
+pub fn show_instance_menu_id(instance_id: &str) -> String {
+    format!("{TRAY_MENU_INSTANCE_SHOW_PREFIX}{instance_id}")
+}
+
+pub fn restart_instance_menu_id(instance_id: &str) -> String {
+    format!("{TRAY_MENU_INSTANCE_RESTART_PREFIX}{instance_id}")
+}
+
+pub fn stop_instance_menu_id(instance_id: &str) -> String {
+    format!("{TRAY_MENU_INSTANCE_STOP_PREFIX}{instance_id}")
+}
+
+pub fn instance_menu_label(name: &str, status_text: &str) -> String {
+    format!("{name} · {status_text}")
+}
+
+/// `(profile id, label)` pairs for the "Switch profile" submenu. The implicit default profile
+/// is listed first unless the state file defines one with the same id.
+pub fn profile_menu_entries(
+    profiles: &[crate::backend::profile::BackendProfile],
+    default_label: &str,
+) -> Vec<(String, String)> {
+    let default_id = crate::backend::profile::DEFAULT_PROFILE_ID;
+    let mut entries = Vec::with_capacity(profiles.len() + 1);
+    if !profiles.iter().any(|profile| profile.id == default_id) {
+        entries.push((default_id.to_string(), default_label.to_string()));
+    }
+    entries.extend(
+        profiles
+            .iter()
+            .map(|profile| (profile.id.clone(), profile.display_name().to_string())),
+    );
+    entries
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,6 +140,14 @@ mod tests {
             action_from_menu_id(TRAY_MENU_RESTART_BACKEND),
             Some(TrayMenuAction::RestartBackend)
         );
+        assert_eq!(
+            action_from_menu_id(TRAY_MENU_OPEN_LOG_VIEWER),
+            Some(TrayMenuAction::OpenLogViewer)
+        );
+        assert_eq!(
+            action_from_menu_id(TRAY_MENU_EXPORT_DIAGNOSTICS),
+            Some(TrayMenuAction::ExportDiagnostics)
+        );
         assert_eq!(
             action_from_menu_id(TRAY_MENU_QUIT),
             Some(TrayMenuAction::Quit)
@@ -48,5 +157,77 @@ mod tests {
     #[test]
     fn action_from_menu_id_returns_none_for_unknown_menu_id() {
         assert_eq!(action_from_menu_id("unknown-menu"), None);
+        assert_eq!(action_from_menu_id(&switch_profile_menu_id("")), None);
+    }
+
+    #[test]
+    fn action_from_menu_id_maps_switch_profile_ids() {
+        assert_eq!(
+            action_from_menu_id(&switch_profile_menu_id("staging")),
+            Some(TrayMenuAction::SwitchProfile("staging".to_string()))
+        );
+    }
+
+    #[test]
+    fn action_from_menu_id_maps_instance_ids() {
+        assert_eq!(
+            action_from_menu_id(&show_instance_menu_id("prod")),
+            Some(TrayMenuAction::ShowInstance("prod".to_string()))
+        );
+        assert_eq!(
+            action_from_menu_id(&restart_instance_menu_id("prod")),
+            Some(TrayMenuAction::RestartInstance("prod".to_string()))
+        );
+        assert_eq!(
+            action_from_menu_id(&stop_instance_menu_id("prod")),
+            Some(TrayMenuAction::StopInstance("prod".to_string()))
+        );
+        assert_eq!(action_from_menu_id(&instance_submenu_id("prod")), None);
+    }
+
+    #[test]
+    fn instance_status_prefers_in_progress_actions() {
+        assert_eq!(
+            InstanceStatus::from_flags(true, true, true),
+            InstanceStatus::Starting
+        );
+        assert_eq!(
+            InstanceStatus::from_flags(true, false, false),
+            InstanceStatus::Running
+        );
+        assert_eq!(
+            InstanceStatus::from_flags(true, false, true),
+            InstanceStatus::Unresponsive
+        );
+        assert_eq!(
+            InstanceStatus::from_flags(false, false, true),
+            InstanceStatus::Stopped
+        );
+    }
+
+    #[test]
+    fn profile_menu_entries_prepend_implicit_default_profile() {
+        let profiles = vec![crate::backend::profile::BackendProfile {
+            id: "prod".to_string(),
+            name: Some("Production".to_string()),
+            ..Default::default()
+        }];
+        assert_eq!(
+            profile_menu_entries(&profiles, "Default"),
+            vec![
+                ("default".to_string(), "Default".to_string()),
+                ("prod".to_string(), "Production".to_string()),
+            ]
+        );
+
+        let with_default = vec![crate::backend::profile::BackendProfile {
+            id: "default".to_string(),
+            name: Some("Main".to_string()),
+            ..Default::default()
+        }];
+        assert_eq!(
+            profile_menu_entries(&with_default, "Default"),
+            vec![("default".to_string(), "Main".to_string())]
+        );
     }
 }
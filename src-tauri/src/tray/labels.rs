@@ -1,6 +1,11 @@
 use tauri::{menu::MenuItem, AppHandle, Manager};
 
-use crate::{runtime_paths, shell_locale, tray::actions, TrayMenuState};
+use crate::{
+    restart_backend_flow, runtime_paths,
+    shell_locale::{self, ShellTexts},
+    tray::actions,
+    BackendRegistry, TrayMenuState, TRAY_ID,
+};
 
 fn set_menu_text_safe<F>(item: &MenuItem<tauri::Wry>, text: &str, item_name: &str, log: F)
 where
@@ -74,6 +79,66 @@ pub fn update_tray_menu_labels_with_visibility<F>(
         actions::TRAY_MENU_RESTART_BACKEND,
         &log,
     );
+    if let Err(error) = tray_state
+        .switch_profile_menu
+        .set_text(shell_texts.tray_switch_profile)
+    {
+        log(&format!(
+            "failed to update tray menu text for {}: {}",
+            actions::TRAY_MENU_SWITCH_PROFILE,
+            error
+        ));
+    }
+    if let Some(item) = tray_state.implicit_default_profile_item.as_ref() {
+        if let Err(error) = item.set_text(shell_texts.tray_default_profile) {
+            log(&format!(
+                "failed to update tray menu text for default profile: {error}"
+            ));
+        }
+    }
+    if let Err(error) = tray_state
+        .instances_menu
+        .set_text(shell_texts.tray_instances)
+    {
+        log(&format!(
+            "failed to update tray menu text for {}: {}",
+            actions::TRAY_MENU_INSTANCES,
+            error
+        ));
+    }
+    for instance_menu in &tray_state.instance_menus {
+        set_menu_text_safe(
+            &instance_menu.open_item,
+            shell_texts.tray_instance_open,
+            "instance open",
+            &log,
+        );
+        set_menu_text_safe(
+            &instance_menu.restart_item,
+            shell_texts.tray_instance_restart,
+            "instance restart",
+            &log,
+        );
+        set_menu_text_safe(
+            &instance_menu.stop_item,
+            shell_texts.tray_instance_stop,
+            "instance stop",
+            &log,
+        );
+    }
+    update_tray_instance_statuses_with_texts(app_handle, &tray_state, &shell_texts, &log);
+    set_menu_text_safe(
+        &tray_state.log_viewer_item,
+        shell_texts.tray_view_logs,
+        actions::TRAY_MENU_OPEN_LOG_VIEWER,
+        &log,
+    );
+    set_menu_text_safe(
+        &tray_state.export_diagnostics_item,
+        shell_texts.tray_export_diagnostics,
+        actions::TRAY_MENU_EXPORT_DIAGNOSTICS,
+        &log,
+    );
     set_menu_text_safe(
         &tray_state.quit_item,
         shell_texts.tray_quit,
@@ -81,3 +146,95 @@ pub fn update_tray_menu_labels_with_visibility<F>(
         &log,
     );
 }
+
+fn update_tray_instance_statuses_with_texts<F>(
+    app_handle: &AppHandle,
+    tray_state: &TrayMenuState,
+    shell_texts: &ShellTexts,
+    log: F,
+) where
+    F: Fn(&str),
+{
+    let registry = app_handle.state::<BackendRegistry>();
+    let mut any_unresponsive = false;
+    for instance_menu in &tray_state.instance_menus {
+        let status = registry
+            .get(&instance_menu.instance_id)
+            .map(|state| {
+                actions::InstanceStatus::from_flags(
+                    state.current_child_pid().is_some(),
+                    restart_backend_flow::is_backend_action_in_progress(&state),
+                    state.backend_health().healthy == Some(false),
+                )
+            })
+            .unwrap_or(actions::InstanceStatus::Stopped);
+        any_unresponsive |= status == actions::InstanceStatus::Unresponsive;
+        let status_text = match status {
+            actions::InstanceStatus::Running => shell_texts.tray_instance_running,
+            actions::InstanceStatus::Unresponsive => shell_texts.tray_instance_unresponsive,
+            actions::InstanceStatus::Starting => shell_texts.tray_instance_starting,
+            actions::InstanceStatus::Stopped => shell_texts.tray_instance_stopped,
+        };
+        let name = instance_menu
+            .name
+            .as_deref()
+            .unwrap_or(shell_texts.tray_default_profile);
+        if let Err(error) = instance_menu
+            .submenu
+            .set_text(actions::instance_menu_label(name, status_text))
+        {
+            log(&format!(
+                "failed to update tray instance status for {}: {}",
+                instance_menu.instance_id, error
+            ));
+        }
+    }
+
+    let Some(tray) = app_handle.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let tooltip = if any_unresponsive {
+        shell_texts.tray_tooltip_unresponsive
+    } else {
+        "AstrBot"
+    };
+    if let Err(error) = tray.set_tooltip(Some(tooltip)) {
+        log(&format!("failed to update tray tooltip: {error}"));
+    }
+}
+
+pub fn update_tray_instance_statuses<F>(
+    app_handle: &AppHandle,
+    default_shell_locale: &'static str,
+    log: F,
+) where
+    F: Fn(&str),
+{
+    let Some(tray_state) = app_handle.try_state::<TrayMenuState>() else {
+        return;
+    };
+    let locale = shell_locale::resolve_shell_locale(
+        default_shell_locale,
+        runtime_paths::default_packaged_root_dir(),
+    );
+    let shell_texts = shell_locale::shell_texts_for_locale(locale);
+    update_tray_instance_statuses_with_texts(app_handle, &tray_state, &shell_texts, log);
+}
+
+pub fn update_tray_profile_checks<F>(app_handle: &AppHandle, log: F)
+where
+    F: Fn(&str),
+{
+    let Some(tray_state) = app_handle.try_state::<TrayMenuState>() else {
+        return;
+    };
+
+    let active_profile_id = app_handle.state::<BackendRegistry>().selected_id();
+    for (profile_id, item) in &tray_state.profile_items {
+        if let Err(error) = item.set_checked(*profile_id == active_profile_id) {
+            log(&format!(
+                "failed to update tray profile check state for {profile_id}: {error}"
+            ));
+        }
+    }
+}
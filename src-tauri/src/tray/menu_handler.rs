@@ -1,9 +1,14 @@
+use std::path::Path;
+
 use tauri::{AppHandle, Manager};
 
 use crate::{
-    append_desktop_log, append_restart_log, append_shutdown_log, restart_backend_flow,
-    tray::{actions, bridge_event},
-    ui_dispatch, window, BackendState, DEFAULT_SHELL_LOCALE, TRAY_RESTART_BACKEND_EVENT,
+    append_desktop_log, append_desktop_log_at, append_restart_log, append_restart_log_at,
+    append_shutdown_log, diagnostics,
+    logging::DesktopLogLevel,
+    navigate_main_window_to_backend, profile_switch_flow, restart_backend_flow,
+    tray::{actions, bridge_event, labels},
+    ui_dispatch, window, BackendRegistry, DEFAULT_SHELL_LOCALE, TRAY_RESTART_BACKEND_EVENT,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -31,7 +36,7 @@ pub fn handle_tray_menu_event(app_handle: &AppHandle, menu_id: &str) {
             window::actions::reload_main_window(app_handle, append_desktop_log)
         }
         Some(actions::TrayMenuAction::RestartBackend) => {
-            let state = app_handle.state::<BackendState>();
+            let state = app_handle.state::<BackendRegistry>().selected();
             match decide_tray_restart(restart_backend_flow::is_backend_action_in_progress(&state)) {
                 TrayRestartDecision::IgnoreBecauseBackendActionInProgress => {
                     append_restart_log("tray restart ignored: backend action already in progress");
@@ -61,19 +66,44 @@ pub fn handle_tray_menu_event(app_handle: &AppHandle, menu_id: &str) {
                             window::actions::reload_main_window(main_app, append_desktop_log);
                         },
                     ) {
-                        append_restart_log(&format!(
-                            "failed to schedule main window reload after tray restart: {error}"
-                        ));
+                        append_restart_log_at(
+                            DesktopLogLevel::Warn,
+                            &format!(
+                                "failed to schedule main window reload after tray restart: {error}"
+                            ),
+                        );
                     }
                 } else {
                     let reason = result.reason.unwrap_or_else(|| "unknown error".to_string());
-                    append_restart_log(&format!("backend restart from tray menu failed: {reason}"));
+                    append_restart_log_at(
+                        DesktopLogLevel::Warn,
+                        &format!("backend restart from tray menu failed: {reason}"),
+                    );
                 }
             });
         }
+        Some(actions::TrayMenuAction::SwitchProfile(profile_id)) => {
+            switch_backend_profile_from_tray(app_handle, profile_id)
+        }
+        Some(actions::TrayMenuAction::ShowInstance(instance_id)) => {
+            show_backend_instance_from_tray(app_handle, instance_id)
+        }
+        Some(actions::TrayMenuAction::RestartInstance(instance_id)) => {
+            restart_backend_instance_from_tray(app_handle, instance_id)
+        }
+        Some(actions::TrayMenuAction::StopInstance(instance_id)) => {
+            stop_backend_instance_from_tray(app_handle, instance_id)
+        }
+        Some(actions::TrayMenuAction::OpenLogViewer) => window::log_viewer::open_log_viewer_window(
+            app_handle,
+            DEFAULT_SHELL_LOCALE,
+            append_desktop_log,
+        ),
+        Some(actions::TrayMenuAction::ExportDiagnostics) => {
+            export_diagnostics_from_tray(app_handle)
+        }
         Some(actions::TrayMenuAction::Quit) => {
-            let state = app_handle.state::<BackendState>();
-            state.mark_quitting();
+            app_handle.state::<BackendRegistry>().mark_all_quitting();
             append_shutdown_log("tray quit requested, exiting desktop process");
             app_handle.exit(0);
         }
@@ -81,6 +111,189 @@ pub fn handle_tray_menu_event(app_handle: &AppHandle, menu_id: &str) {
     }
 }
 
+fn switch_backend_profile_from_tray(app_handle: &AppHandle, profile_id: String) {
+    if profile_id == app_handle.state::<BackendRegistry>().selected_id() {
+        // Clicking a check item toggles it natively; restore the marks to the real state.
+        labels::update_tray_profile_checks(app_handle, append_desktop_log);
+        return;
+    }
+    let state = app_handle.state::<BackendRegistry>().selected();
+    if restart_backend_flow::is_backend_action_in_progress(&state) {
+        append_restart_log("tray profile switch ignored: backend action already in progress");
+        labels::update_tray_profile_checks(app_handle, append_desktop_log);
+        return;
+    }
+
+    append_restart_log(&format!(
+        "tray requested backend profile switch: {profile_id}"
+    ));
+    let app_handle_cloned = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let result =
+            profile_switch_flow::run_switch_profile_task(app_handle_cloned.clone(), profile_id)
+                .await;
+        if !result.ok {
+            let reason = result.reason.unwrap_or_else(|| "unknown error".to_string());
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("backend profile switch from tray failed: {reason}"),
+            );
+        }
+        let navigate = result.ok;
+        if let Err(error) = ui_dispatch::run_on_main_thread_dispatch(
+            &app_handle_cloned,
+            "refresh window after profile switch",
+            move |main_app| {
+                labels::update_tray_profile_checks(main_app, append_desktop_log);
+                if navigate {
+                    if let Err(error) = navigate_main_window_to_backend(main_app) {
+                        append_restart_log_at(
+                            DesktopLogLevel::Warn,
+                            &format!(
+                                "failed to navigate main window after profile switch: {error}"
+                            ),
+                        );
+                    }
+                }
+            },
+        ) {
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to schedule window refresh after profile switch: {error}"),
+            );
+        }
+    });
+}
+
+fn refresh_tray_after_instance_action(app_handle: &AppHandle, navigate: bool, task_name: &str) {
+    if let Err(error) = ui_dispatch::run_on_main_thread_dispatch(
+        app_handle,
+        "refresh tray after instance action",
+        move |main_app| {
+            labels::update_tray_instance_statuses(
+                main_app,
+                DEFAULT_SHELL_LOCALE,
+                append_desktop_log,
+            );
+            labels::update_tray_profile_checks(main_app, append_desktop_log);
+            if navigate {
+                if let Err(error) = navigate_main_window_to_backend(main_app) {
+                    append_restart_log_at(
+                        DesktopLogLevel::Warn,
+                        &format!("failed to navigate main window to selected instance: {error}"),
+                    );
+                }
+            }
+        },
+    ) {
+        append_restart_log_at(
+            DesktopLogLevel::Warn,
+            &format!("failed to schedule tray refresh after {task_name}: {error}"),
+        );
+    }
+}
+
+fn show_backend_instance_from_tray(app_handle: &AppHandle, instance_id: String) {
+    window::actions::show_main_window(app_handle, DEFAULT_SHELL_LOCALE, append_desktop_log);
+    if instance_id == app_handle.state::<BackendRegistry>().selected_id() {
+        return;
+    }
+
+    append_restart_log(&format!(
+        "tray requested backend instance open: {instance_id}"
+    ));
+    let app_handle_cloned = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let result =
+            profile_switch_flow::run_open_instance_task(app_handle_cloned.clone(), instance_id)
+                .await;
+        if !result.ok {
+            let reason = result.reason.unwrap_or_else(|| "unknown error".to_string());
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("backend instance open from tray failed: {reason}"),
+            );
+        }
+        refresh_tray_after_instance_action(&app_handle_cloned, result.ok, "instance open");
+    });
+}
+
+fn restart_backend_instance_from_tray(app_handle: &AppHandle, instance_id: String) {
+    let registry = app_handle.state::<BackendRegistry>();
+    let Some(state) = registry.get(&instance_id) else {
+        // Instances that were never started are started by opening them.
+        show_backend_instance_from_tray(app_handle, instance_id);
+        return;
+    };
+    if restart_backend_flow::is_backend_action_in_progress(&state) {
+        append_restart_log("tray instance restart ignored: backend action already in progress");
+        return;
+    }
+
+    append_restart_log(&format!(
+        "tray requested backend instance restart: {instance_id}"
+    ));
+    let is_selected = instance_id == registry.selected_id();
+    let app_handle_cloned = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = restart_backend_flow::run_restart_instance_task(
+            app_handle_cloned.clone(),
+            Some(instance_id),
+            None,
+        )
+        .await;
+        if !result.ok {
+            let reason = result.reason.unwrap_or_else(|| "unknown error".to_string());
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("backend instance restart from tray failed: {reason}"),
+            );
+        }
+        refresh_tray_after_instance_action(
+            &app_handle_cloned,
+            result.ok && is_selected,
+            "instance restart",
+        );
+    });
+}
+
+fn stop_backend_instance_from_tray(app_handle: &AppHandle, instance_id: String) {
+    append_restart_log(&format!(
+        "tray requested backend instance stop: {instance_id}"
+    ));
+    let app_handle_cloned = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let result =
+            profile_switch_flow::run_stop_instance_task(app_handle_cloned.clone(), instance_id)
+                .await;
+        if !result.ok {
+            let reason = result.reason.unwrap_or_else(|| "unknown error".to_string());
+            append_restart_log_at(
+                DesktopLogLevel::Warn,
+                &format!("backend instance stop from tray failed: {reason}"),
+            );
+        }
+        refresh_tray_after_instance_action(&app_handle_cloned, false, "instance stop");
+    });
+}
+
+fn export_diagnostics_from_tray(app_handle: &AppHandle) {
+    append_desktop_log("tray requested diagnostics export");
+    let app_handle_cloned = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = diagnostics::run_export_diagnostics_task(app_handle_cloned, None).await;
+        let Some(path) = result.path.filter(|_| result.ok) else {
+            return;
+        };
+        if let Err(error) = diagnostics::reveal_in_file_manager(Path::new(&path)) {
+            append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!("failed to reveal diagnostics bundle: {error}"),
+            );
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::{decide_tray_restart, TrayRestartDecision};
@@ -1,15 +1,66 @@
 use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     AppHandle, Manager,
 };
 
 use crate::{
-    append_desktop_log, runtime_paths, shell_locale,
+    append_desktop_log, backend, runtime_paths, shell_locale,
+    shell_locale::ShellTexts,
     tray::{actions, labels, menu_handler},
-    window, TrayMenuState, DEFAULT_SHELL_LOCALE, TRAY_ID,
+    window, BackendRegistry, TrayInstanceMenu, TrayMenuState, DEFAULT_SHELL_LOCALE, TRAY_ID,
 };
 
+fn build_instance_menu(
+    app_handle: &AppHandle,
+    shell_texts: &ShellTexts,
+    instance_id: &str,
+    label: &str,
+    is_implicit_default: bool,
+) -> Result<TrayInstanceMenu, String> {
+    let open_item = MenuItem::with_id(
+        app_handle,
+        actions::show_instance_menu_id(instance_id),
+        shell_texts.tray_instance_open,
+        true,
+        None::<&str>,
+    )
+    .map_err(|error| format!("Failed to create tray instance open item: {error}"))?;
+    let restart_item = MenuItem::with_id(
+        app_handle,
+        actions::restart_instance_menu_id(instance_id),
+        shell_texts.tray_instance_restart,
+        true,
+        None::<&str>,
+    )
+    .map_err(|error| format!("Failed to create tray instance restart item: {error}"))?;
+    let stop_item = MenuItem::with_id(
+        app_handle,
+        actions::stop_instance_menu_id(instance_id),
+        shell_texts.tray_instance_stop,
+        true,
+        None::<&str>,
+    )
+    .map_err(|error| format!("Failed to create tray instance stop item: {error}"))?;
+    let submenu = Submenu::with_id_and_items(
+        app_handle,
+        actions::instance_submenu_id(instance_id),
+        actions::instance_menu_label(label, shell_texts.tray_instance_stopped),
+        true,
+        &[&open_item, &restart_item, &stop_item],
+    )
+    .map_err(|error| format!("Failed to create tray instance submenu: {error}"))?;
+
+    Ok(TrayInstanceMenu {
+        instance_id: instance_id.to_string(),
+        name: (!is_implicit_default).then(|| label.to_string()),
+        submenu,
+        open_item,
+        restart_item,
+        stop_item,
+    })
+}
+
 pub fn setup_tray(app_handle: &AppHandle) -> Result<(), String> {
     let locale = shell_locale::resolve_shell_locale(
         DEFAULT_SHELL_LOCALE,
@@ -50,6 +101,79 @@ pub fn setup_tray(app_handle: &AppHandle) -> Result<(), String> {
         None::<&str>,
     )
     .map_err(|error| format!("Failed to create tray restart menu item: {error}"))?;
+    let switch_profile_menu = Submenu::with_id(
+        app_handle,
+        actions::TRAY_MENU_SWITCH_PROFILE,
+        shell_texts.tray_switch_profile,
+        true,
+    )
+    .map_err(|error| format!("Failed to create tray profile submenu: {error}"))?;
+    let packaged_root_dir = runtime_paths::default_packaged_root_dir();
+    let profiles = backend::profile::load_backend_profiles(packaged_root_dir.as_deref());
+    let active_profile_id = app_handle.state::<BackendRegistry>().selected_id();
+    let has_configured_default = profiles
+        .iter()
+        .any(|profile| profile.id == backend::profile::DEFAULT_PROFILE_ID);
+    let mut implicit_default_profile_item = None;
+    let mut profile_items = Vec::new();
+    let instances_menu = Submenu::with_id(
+        app_handle,
+        actions::TRAY_MENU_INSTANCES,
+        shell_texts.tray_instances,
+        true,
+    )
+    .map_err(|error| format!("Failed to create tray instances submenu: {error}"))?;
+    let mut instance_menus = Vec::new();
+    for (profile_id, label) in
+        actions::profile_menu_entries(&profiles, shell_texts.tray_default_profile)
+    {
+        let is_implicit_default =
+            !has_configured_default && profile_id == backend::profile::DEFAULT_PROFILE_ID;
+        let instance_menu = build_instance_menu(
+            app_handle,
+            &shell_texts,
+            &profile_id,
+            &label,
+            is_implicit_default,
+        )?;
+        instances_menu
+            .append(&instance_menu.submenu)
+            .map_err(|error| format!("Failed to append tray instance submenu: {error}"))?;
+        instance_menus.push(instance_menu);
+
+        let item = CheckMenuItem::with_id(
+            app_handle,
+            actions::switch_profile_menu_id(&profile_id),
+            label,
+            true,
+            profile_id == active_profile_id,
+            None::<&str>,
+        )
+        .map_err(|error| format!("Failed to create tray profile menu item: {error}"))?;
+        switch_profile_menu
+            .append(&item)
+            .map_err(|error| format!("Failed to append tray profile menu item: {error}"))?;
+        if is_implicit_default {
+            implicit_default_profile_item = Some(item.clone());
+        }
+        profile_items.push((profile_id, item));
+    }
+    let log_viewer_item = MenuItem::with_id(
+        app_handle,
+        actions::TRAY_MENU_OPEN_LOG_VIEWER,
+        shell_texts.tray_view_logs,
+        true,
+        None::<&str>,
+    )
+    .map_err(|error| format!("Failed to create tray log viewer menu item: {error}"))?;
+    let export_diagnostics_item = MenuItem::with_id(
+        app_handle,
+        actions::TRAY_MENU_EXPORT_DIAGNOSTICS,
+        shell_texts.tray_export_diagnostics,
+        true,
+        None::<&str>,
+    )
+    .map_err(|error| format!("Failed to create tray diagnostics menu item: {error}"))?;
     let quit_item = MenuItem::with_id(
         app_handle,
         actions::TRAY_MENU_QUIT,
@@ -67,6 +191,10 @@ pub fn setup_tray(app_handle: &AppHandle) -> Result<(), String> {
             &toggle_item,
             &reload_item,
             &restart_backend_item,
+            &switch_profile_menu,
+            &instances_menu,
+            &log_viewer_item,
+            &export_diagnostics_item,
             &separator,
             &quit_item,
         ],
@@ -77,6 +205,13 @@ pub fn setup_tray(app_handle: &AppHandle) -> Result<(), String> {
         toggle_item: toggle_item.clone(),
         reload_item: reload_item.clone(),
         restart_backend_item: restart_backend_item.clone(),
+        switch_profile_menu: switch_profile_menu.clone(),
+        profile_items,
+        implicit_default_profile_item,
+        instances_menu: instances_menu.clone(),
+        instance_menus,
+        log_viewer_item: log_viewer_item.clone(),
+        export_diagnostics_item: export_diagnostics_item.clone(),
         quit_item: quit_item.clone(),
     }) {
         append_desktop_log("tray menu state already exists, skipping manage");
@@ -1,5 +1,7 @@
 use tauri::AppHandle;
 
+use crate::window;
+
 pub fn run_on_main_thread_dispatch<F>(
     app_handle: &AppHandle,
     task_name: &str,
@@ -21,6 +23,9 @@ where
     F: Fn(&str),
 {
     log(&format!("startup error: {message}"));
+    if window::startup_loading::show_startup_error_mode(app_handle, message, &log) {
+        return;
+    }
     eprintln!("AstrBot startup failed: {message}");
     app_handle.exit(1);
 }
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::{collections::HashMap, env, fs, io::Write, path::Path, sync::OnceLock};
 
+use crate::logging::DesktopLogLevel;
+
 #[cfg(test)]
 const UPDATE_CHANNEL_FIELD: &str = "updateChannel";
 const NIGHTLY_IDENTIFIER: &str = "nightly";
@@ -170,12 +172,15 @@ fn parse_nightly_version_info(version: &Version) -> Option<NightlyVersionInfo> {
     }
 
     let log_failure = |reason: &str| {
-        crate::append_desktop_log(&format!(
-            "failed to parse nightly prerelease '{}' as '{}': {}",
-            version,
-            nightly_prerelease_format(),
-            reason,
-        ));
+        crate::append_desktop_log_at(
+            DesktopLogLevel::Warn,
+            &format!(
+                "failed to parse nightly prerelease '{}' as '{}': {}",
+                version,
+                nightly_prerelease_format(),
+                reason,
+            ),
+        );
     };
 
     let Some(date_raw) = identifiers.next() else {
@@ -264,7 +269,7 @@ pub(crate) fn infer_channel_from_version(version: &Version) -> UpdateChannel {
 pub(crate) fn read_cached_update_channel(
     packaged_root_dir: Option<&Path>,
 ) -> Option<UpdateChannel> {
-    let state_path = crate::desktop_state::resolve_desktop_state_path(packaged_root_dir)?;
+    let state_path = crate::desktop_state::resolve_shared_desktop_state_path(packaged_root_dir)?;
     let raw = fs::read_to_string(state_path).ok()?;
     let state: DesktopState = serde_json::from_str(&raw).ok()?;
     UpdateChannel::parse(state.update_channel.as_deref()?)
@@ -310,11 +315,14 @@ fn load_state(path: &Path) -> Result<DesktopState, String> {
     match serde_json::from_str::<DesktopState>(&raw) {
         Ok(state) => Ok(state),
         Err(error) => {
-            crate::append_desktop_log(&format!(
-                "failed to parse update channel state {}: {}. resetting state file",
-                path.display(),
-                error
-            ));
+            crate::append_desktop_log_at(
+                DesktopLogLevel::Warn,
+                &format!(
+                    "failed to parse update channel state {}: {}. resetting state file",
+                    path.display(),
+                    error
+                ),
+            );
             Ok(DesktopState::default())
         }
     }
@@ -362,7 +370,8 @@ pub(crate) fn write_cached_update_channel(
     channel: Option<UpdateChannel>,
     packaged_root_dir: Option<&Path>,
 ) -> Result<(), String> {
-    let Some(state_path) = crate::desktop_state::resolve_desktop_state_path(packaged_root_dir)
+    let Some(state_path) =
+        crate::desktop_state::resolve_shared_desktop_state_path(packaged_root_dir)
     else {
         let message =
             "Update channel state path is unavailable; cannot persist update channel selection."
@@ -0,0 +1,44 @@
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::{runtime_paths, shell_locale, LOG_VIEWER_WINDOW_LABEL};
+
+const LOG_VIEWER_PAGE: &str = "log-viewer.html";
+
+/// Focuses the log viewer window, creating it on first use. Closing it destroys the window;
+/// the next tray click builds a fresh one.
+pub fn open_log_viewer_window<F>(app_handle: &AppHandle, default_shell_locale: &'static str, log: F)
+where
+    F: Fn(&str),
+{
+    if let Some(window) = app_handle.get_webview_window(LOG_VIEWER_WINDOW_LABEL) {
+        if let Err(error) = window.unminimize() {
+            log(&format!("failed to unminimize log viewer window: {error}"));
+        }
+        if let Err(error) = window.show() {
+            log(&format!("failed to show log viewer window: {error}"));
+        }
+        if let Err(error) = window.set_focus() {
+            log(&format!("failed to focus log viewer window: {error}"));
+        }
+        return;
+    }
+
+    let locale = shell_locale::resolve_shell_locale(
+        default_shell_locale,
+        runtime_paths::default_packaged_root_dir(),
+    );
+    let shell_texts = shell_locale::shell_texts_for_locale(locale);
+    let page_url = format!("{LOG_VIEWER_PAGE}?locale={locale}");
+    if let Err(error) = WebviewWindowBuilder::new(
+        app_handle,
+        LOG_VIEWER_WINDOW_LABEL,
+        WebviewUrl::App(page_url.into()),
+    )
+    .title(shell_texts.log_viewer_title)
+    .inner_size(960.0, 640.0)
+    .min_inner_size(640.0, 400.0)
+    .build()
+    {
+        log(&format!("failed to open log viewer window: {error}"));
+    }
+}
@@ -1,3 +1,4 @@
 pub(crate) mod actions;
+pub(crate) mod log_viewer;
 pub(crate) mod main_window;
 pub(crate) mod startup_loading;
@@ -3,7 +3,13 @@ use std::env;
 use tauri::{AppHandle, Manager};
 use url::Url;
 
-use crate::{startup_mode, BackendState};
+use crate::{
+    backend::orphan::OrphanBackend,
+    logging, runtime_paths,
+    startup_mode::{self, StartupMode},
+    BackendRegistry, BackendState, StartupErrorDetails, DESKTOP_LOG_FILE,
+    STARTUP_ERROR_RECENT_LOG_LINES,
+};
 
 pub fn should_apply_startup_loading_mode(webview_label: &str, page_url: &Url) -> bool {
     if webview_label != "main" {
@@ -26,14 +32,120 @@ pub fn apply_startup_loading_mode<F>(
 ) where
     F: Fn(&str) + Copy,
 {
-    let mode = resolve_startup_loading_mode(app_handle, startup_mode_env, log);
-    let mode_js = serde_json::to_string(mode).expect("serializing startup mode");
-    let script = format!(
-        "if (typeof window !== 'undefined' && typeof window.__astrbotSetStartupMode === 'function') {{ window.__astrbotSetStartupMode({mode_js}); }}"
-    );
+    let state = app_handle.state::<BackendRegistry>().selected();
+    let script = match stashed_startup_error(&state) {
+        Some(details) => startup_mode_script(StartupMode::Error.as_str(), Some(&details)),
+        None => startup_mode_script(
+            resolve_startup_loading_mode(app_handle, startup_mode_env, log),
+            None,
+        ),
+    };
     if let Err(error) = webview.eval(&script) {
         log(&format!("failed to apply startup loading mode: {error}"));
     }
+    if let Some(orphan) = state.pending_orphan() {
+        if let Err(error) = webview.eval(orphan_prompt_script(&orphan)) {
+            log(&format!("failed to apply orphaned backend prompt: {error}"));
+        }
+    }
+}
+
+/// Asks the startup page whether to adopt or respawn `orphan`. Returns `false` when the main
+/// window is not on that page.
+pub fn show_orphan_prompt<F>(app_handle: &AppHandle, orphan: &OrphanBackend, log: F) -> bool
+where
+    F: Fn(&str),
+{
+    let Some(window) = main_window_on_startup_page(app_handle) else {
+        return false;
+    };
+    if let Err(error) = window.eval(orphan_prompt_script(orphan)) {
+        log(&format!("failed to show orphaned backend prompt: {error}"));
+        return false;
+    }
+    true
+}
+
+fn main_window_on_startup_page(app_handle: &AppHandle) -> Option<tauri::WebviewWindow> {
+    let window = app_handle.get_webview_window("main")?;
+    window
+        .url()
+        .is_ok_and(|page_url| should_apply_startup_loading_mode("main", &page_url))
+        .then_some(window)
+}
+
+fn orphan_prompt_script(orphan: &OrphanBackend) -> String {
+    let orphan_js = serde_json::to_string(orphan).expect("serializing orphaned backend");
+    format!(
+        "if (typeof window !== 'undefined' && typeof window.__astrbotShowOrphanPrompt === 'function') {{ window.__astrbotShowOrphanPrompt({orphan_js}); }}"
+    )
+}
+
+/// Switches the startup page to the error mode. Returns `false` when the main window is not on
+/// that page, so the caller can fall back to exiting.
+pub fn show_startup_error_mode<F>(app_handle: &AppHandle, message: &str, log: F) -> bool
+where
+    F: Fn(&str),
+{
+    let Some(window) = main_window_on_startup_page(app_handle) else {
+        return false;
+    };
+
+    let state = app_handle.state::<BackendRegistry>().selected();
+    let details = StartupErrorDetails {
+        message: message.to_string(),
+        desktop_log_path: logging::resolve_desktop_log_path(
+            runtime_paths::default_packaged_root_dir(),
+            DESKTOP_LOG_FILE,
+        )
+        .display()
+        .to_string(),
+        backend_log_path: state.backend_log_path().display().to_string(),
+        recent_logs: state
+            .recent_backend_logs(STARTUP_ERROR_RECENT_LOG_LINES)
+            .into_iter()
+            .map(|line| line.line)
+            .collect(),
+    };
+    let script = startup_mode_script(StartupMode::Error.as_str(), Some(&details));
+    set_stashed_startup_error(&state, Some(details));
+    if let Err(error) = window.eval(&script) {
+        log(&format!("failed to show startup error mode: {error}"));
+        return false;
+    }
+    if let Err(error) = window.show() {
+        log(&format!(
+            "failed to show main window for startup error: {error}"
+        ));
+    }
+    true
+}
+
+/// Forgets the last startup error so a retry renders the regular loading mode again.
+pub fn clear_startup_error(state: &BackendState) {
+    set_stashed_startup_error(state, None);
+}
+
+fn stashed_startup_error(state: &BackendState) -> Option<StartupErrorDetails> {
+    match state.startup_error.lock() {
+        Ok(guard) => guard.clone(),
+        Err(error) => error.into_inner().clone(),
+    }
+}
+
+fn set_stashed_startup_error(state: &BackendState, details: Option<StartupErrorDetails>) {
+    match state.startup_error.lock() {
+        Ok(mut guard) => *guard = details,
+        Err(error) => *error.into_inner() = details,
+    }
+}
+
+fn startup_mode_script(mode: &str, details: Option<&StartupErrorDetails>) -> String {
+    let mode_js = serde_json::to_string(mode).expect("serializing startup mode");
+    let details_js = serde_json::to_string(&details).expect("serializing startup error details");
+    format!(
+        "if (typeof window !== 'undefined' && typeof window.__astrbotSetStartupMode === 'function') {{ window.__astrbotSetStartupMode({mode_js}, {details_js}); }}"
+    )
 }
 
 fn resolve_startup_loading_mode<F>(
@@ -44,7 +156,7 @@ fn resolve_startup_loading_mode<F>(
 where
     F: Fn(&str) + Copy,
 {
-    let state = app_handle.state::<BackendState>();
+    let state = app_handle.state::<BackendRegistry>().selected();
     match state.startup_loading_mode.lock() {
         Ok(guard) => {
             if let Some(mode) = *guard {
@@ -120,4 +232,22 @@ mod tests {
         assert!(!should_apply_startup_loading_mode("main", &http_index));
         assert!(!should_apply_startup_loading_mode("other", &file_index));
     }
+
+    #[test]
+    fn startup_mode_script_passes_error_details_as_json() {
+        let details = StartupErrorDetails {
+            message: "Backend exited with \"code 1\"".to_string(),
+            desktop_log_path: "/tmp/desktop.log".to_string(),
+            backend_log_path: "/tmp/backend.log".to_string(),
+            recent_logs: vec!["Traceback".to_string()],
+        };
+        let script = startup_mode_script(startup_mode::STARTUP_MODE_ERROR, Some(&details));
+        assert!(script.contains(
+            r#"__astrbotSetStartupMode("startup-error", {"message":"Backend exited with \"code 1\"","#
+        ));
+        assert!(script.contains(r#""recentLogs":["Traceback"]"#));
+
+        let loading = startup_mode::STARTUP_MODE_LOADING;
+        assert!(startup_mode_script(loading, None).contains(r#"("loading", null)"#));
+    }
 }
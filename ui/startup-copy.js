@@ -12,11 +12,61 @@ const deepFreeze = (obj) => {
 const STARTUP_MODES = {
   LOADING: 'loading',
   PANEL_UPDATE: 'panel-update',
+  ERROR: 'startup-error',
+};
+
+// Keep this in sync with BACKEND_STARTUP_PROGRESS_EVENT in src-tauri/src/app_constants.rs.
+const STARTUP_PROGRESS_EVENT = 'astrbot://backend-startup-progress';
+
+// Keep these in sync with the commands registered in src-tauri/src/app_runtime.rs.
+const STARTUP_COMMANDS = {
+  CANCEL: 'desktop_bridge_cancel_startup',
+  RETRY: 'desktop_bridge_retry_startup',
+  RESOLVE_ORPHAN: 'desktop_bridge_resolve_orphan_backend',
+  OPEN_LOGS: 'desktop_bridge_open_log_viewer',
+  EXPORT_DIAGNOSTICS: 'desktop_bridge_export_diagnostics',
+  QUIT: 'desktop_bridge_quit_app',
 };
 
 window.astrbot = window.astrbot || {};
 window.astrbot.startupShell = deepFreeze({
   STARTUP_MODES,
+  STARTUP_PROGRESS_EVENT,
+  STARTUP_COMMANDS,
+  STARTUP_ACTION_COPY: {
+    en: {
+      cancel: 'Cancel',
+      retry: 'Retry',
+      openLogs: 'Open logs',
+      exportDiagnostics: 'Export diagnostics',
+      quit: 'Quit',
+      cancelling: 'Cancelling startup...',
+      exporting: 'Exporting diagnostics...',
+      exported: 'Diagnostics saved to',
+      logPath: 'Log file',
+      recentLogs: 'Recent backend output',
+      orphanFound: 'AstrBot from a previous session is still running (PID {pid}, started {startedAt}).',
+      orphanPlanChanged: 'It was started with a different configuration; restarting it is recommended.',
+      orphanAdopt: 'Keep using it',
+      orphanRespawn: 'Restart it',
+    },
+    zh: {
+      cancel: '取消',
+      retry: '重试',
+      openLogs: '查看日志',
+      exportDiagnostics: '导出诊断信息',
+      quit: '退出',
+      cancelling: '正在取消启动...',
+      exporting: '正在导出诊断信息...',
+      exported: '诊断信息已保存到',
+      logPath: '日志文件',
+      recentLogs: '最近的后端输出',
+      orphanFound: '上次会话启动的 AstrBot 仍在运行（PID {pid}，启动于 {startedAt}）。',
+      orphanPlanChanged: '它使用的启动配置与当前不同，建议重新启动。',
+      orphanAdopt: '继续使用',
+      orphanRespawn: '重新启动',
+    },
+  },
   STARTUP_COPY: {
     en: {
       [STARTUP_MODES.LOADING]: {
@@ -29,6 +79,11 @@ window.astrbot.startupShell = deepFreeze({
         desc: 'A new panel version is available and is being downloaded and applied.',
         status: 'Syncing panel assets...',
       },
+      [STARTUP_MODES.ERROR]: {
+        title: 'AstrBot failed to start',
+        desc: 'The backend could not be started.',
+        status: 'Startup failed.',
+      },
     },
     zh: {
       [STARTUP_MODES.LOADING]: {
@@ -41,6 +96,11 @@ window.astrbot.startupShell = deepFreeze({
         desc: '检测到新面板版本，正在下载并应用。',
         status: '正在同步面板资源...',
       },
+      [STARTUP_MODES.ERROR]: {
+        title: 'AstrBot 启动失败',
+        desc: '后端未能成功启动。',
+        status: '启动失败。',
+      },
     },
   },
 });